@@ -5,10 +5,15 @@ pub trait Frame: std::fmt::Debug {
     fn name(&self) -> &str;
     fn kind(&self) -> FrameKind;
     fn body(&self) -> Option<&NamespaceBody>;
+
+    fn imports(&self) -> &[Import] {
+        &[]
+    }
 }
 
 pub enum FrameKind<'a> {
     Schema(&'a Schema),
+    Import(&'a Import),
     Namespace(&'a NamespaceDecl),
     Function(&'a FunctionDecl),
     Synthetic(&'a SyntheticFrame),
@@ -54,6 +59,33 @@ impl Frame for Schema {
     fn body(&self) -> Option<&NamespaceBody> {
         Some(&self.body)
     }
+
+    fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+}
+
+/// An import acts like a namespace named after its alias,
+/// which contains the imported schema.
+impl Frame for Import {
+    fn name(&self) -> &str {
+        self.name.text()
+    }
+
+    fn kind(&self) -> FrameKind {
+        FrameKind::Import(self)
+    }
+
+    fn body(&self) -> Option<&NamespaceBody> {
+        self.schema.as_ref().map(|s| &s.body)
+    }
+
+    fn imports(&self) -> &[Import] {
+        self.schema
+            .as_ref()
+            .map(|s| &s.imports[..])
+            .unwrap_or_default()
+    }
 }
 
 impl Frame for FunctionDecl {
@@ -126,6 +158,21 @@ impl<'a> Stack<'a> {
             .collect()
     }
 
+    /// Returns the full path of a symbol resolved from this stack,
+    /// starting from the schema root.
+    pub fn absolute_names(&self, path: &RelativePath<'a>) -> Vec<&'a str> {
+        let frames = &self.frames[..self.frames.len() - path.up];
+        frames
+            .iter()
+            .filter_map(|&f| match f.kind() {
+                FrameKind::Schema(_) => None,
+                FrameKind::Synthetic(_) => None,
+                _ => Some(f.name()),
+            })
+            .chain(path.down.iter().cloned())
+            .collect()
+    }
+
     pub fn names_and(&self, addition: &'a str) -> Vec<&str> {
         self.names()
             .into_iter()
@@ -173,6 +220,14 @@ impl<'a> Stack<'a> {
                     }
                 }
 
+                if symbol.is_none() {
+                    for s in &body.unions {
+                        if s.name.text() == name {
+                            symbol = Some(Symbol::Union(s))
+                        }
+                    }
+                }
+
                 if symbol.is_none() {
                     for ns in &body.namespaces {
                         if ns.name.text() == name {
@@ -181,6 +236,14 @@ impl<'a> Stack<'a> {
                     }
                 }
 
+                if symbol.is_none() {
+                    for imp in frame.imports() {
+                        if imp.name.text() == name {
+                            symbol = Some(Symbol::Import(&imp))
+                        }
+                    }
+                }
+
                 if let Some(symbol) = symbol {
                     debug!("Found match, rest = {:?}", rest);
                     if rest.is_empty() {
@@ -190,10 +253,15 @@ impl<'a> Stack<'a> {
                             symbol,
                         });
                     } else {
-                        match symbol {
-                            Symbol::Namespace(ns) => {
-                                debug!("First part of the path resolved to a namespace ({:?}), looking up rest in it", ns.name.text());
-                                let stack = self.push(ns);
+                        let inner: Option<&'a dyn Frame> = match symbol {
+                            Symbol::Namespace(ns) => Some(ns),
+                            Symbol::Import(imp) => Some(imp),
+                            _ => None,
+                        };
+                        match inner {
+                            Some(inner) => {
+                                debug!("First part of the path resolved to a namespace ({:?}), looking up rest in it", inner.name());
+                                let stack = self.push(inner);
                                 if let Some(mut path) =
                                     stack.lookup_struct(LookupMode::Strict, rest)
                                 {
@@ -242,16 +310,20 @@ pub struct RelativePath<'a> {
 
 pub enum Symbol<'a> {
     Namespace(&'a NamespaceDecl),
+    Import(&'a Import),
     Struct(&'a StructDecl),
     Enum(&'a EnumDecl),
+    Union(&'a UnionDecl),
 }
 
 impl<'a> fmt::Debug for Symbol<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Symbol::Namespace(node) => write!(f, "Namespace({:?})", node.name.text()),
+            Symbol::Import(node) => write!(f, "Import({:?})", node.name.text()),
             Symbol::Struct(node) => write!(f, "Struct({:?})", node.name.text()),
             Symbol::Enum(node) => write!(f, "Enum({:?})", node.name.text()),
+            Symbol::Union(node) => write!(f, "Union({:?})", node.name.text()),
         }
     }
 }
@@ -289,6 +361,12 @@ impl<'a> Anchored<'a, &NamespaceBody> {
         }
     }
 
+    pub fn for_each_union(&self, cb: &mut dyn FnMut(Anchored<&UnionDecl>)) {
+        for f in &self.unions {
+            cb(self.stack.anchor(f));
+        }
+    }
+
     pub fn for_each_namespace(&self, cb: &mut FnMut(Anchored<&NamespaceBody>)) {
         for ns in &self.namespaces {
             cb(self.stack.push(ns).anchor(&ns.body));
@@ -322,6 +400,37 @@ impl<'a> Anchored<'a, &NamespaceBody> {
         });
         self.for_each_namespace(&mut |ns| ns.for_each_enum_of_schema(cb));
     }
+
+    pub fn for_each_union_of_schema(&self, cb: &mut dyn FnMut(Anchored<&UnionDecl>)) {
+        self.for_each_union(&mut |f| {
+            cb(f);
+        });
+        self.for_each_fun(&mut |f| {
+            f.for_each_union_of_schema(cb);
+        });
+        self.for_each_namespace(&mut |ns| ns.for_each_union_of_schema(cb));
+    }
+
+    /// Like `for_each_struct_of_schema`, but leaves out the structs
+    /// declared by functions, which other schemas can't refer to.
+    pub fn for_each_struct_of_namespaces(&self, cb: &mut dyn FnMut(Anchored<&StructDecl>)) {
+        self.for_each_struct(cb);
+        self.for_each_namespace(&mut |ns| ns.for_each_struct_of_namespaces(cb));
+    }
+
+    /// Like `for_each_enum_of_schema`, but leaves out the enums
+    /// declared by functions, which other schemas can't refer to.
+    pub fn for_each_enum_of_namespaces(&self, cb: &mut dyn FnMut(Anchored<&EnumDecl>)) {
+        self.for_each_enum(cb);
+        self.for_each_namespace(&mut |ns| ns.for_each_enum_of_namespaces(cb));
+    }
+
+    /// Like `for_each_union_of_schema`, but leaves out the unions
+    /// declared by functions, which other schemas can't refer to.
+    pub fn for_each_union_of_namespaces(&self, cb: &mut dyn FnMut(Anchored<&UnionDecl>)) {
+        self.for_each_union(cb);
+        self.for_each_namespace(&mut |ns| ns.for_each_union_of_namespaces(cb));
+    }
 }
 
 impl<'a> Anchored<'a, &FunctionDecl> {
@@ -354,6 +463,13 @@ impl<'a> Anchored<'a, &FunctionDecl> {
         }
     }
 
+    pub fn for_each_union_of_schema(&self, cb: &mut dyn FnMut(Anchored<&UnionDecl>)) {
+        let stack = self.stack.push(self.inner);
+        if let Some(body) = self.body.as_ref() {
+            stack.anchor(body).for_each_union_of_schema(cb);
+        }
+    }
+
     pub fn method(&self) -> String {
         self.names().join(".")
     }
@@ -383,6 +499,16 @@ impl<'a> Anchored<'a, &EnumDecl> {
     }
 }
 
+impl<'a> Anchored<'a, &UnionDecl> {
+    pub fn names(&self) -> Vec<&str> {
+        self.stack.names_and(self.name())
+    }
+
+    pub fn name(&self) -> &str {
+        self.inner.name.text()
+    }
+}
+
 impl<'a> Anchored<'a, &Field> {
     pub fn name(&self) -> &str {
         self.inner.name.text()
@@ -0,0 +1,224 @@
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+/// A struct or a union: the types that can contain each other.
+#[derive(Clone, Copy)]
+pub enum Decl<'a> {
+    Struct(&'a StructDecl),
+    Union(&'a UnionDecl),
+}
+
+impl<'a> Decl<'a> {
+    pub fn name(&self) -> &'a Identifier {
+        match self {
+            Decl::Struct(st) => &st.name,
+            Decl::Union(un) => &un.name,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Decl::Struct(_) => "struct",
+            Decl::Union(_) => "union",
+        }
+    }
+
+    fn key(&self) -> usize {
+        match self {
+            Decl::Struct(st) => *st as *const StructDecl as usize,
+            Decl::Union(un) => *un as *const UnionDecl as usize,
+        }
+    }
+}
+
+/// A struct field or union member holding another declaration by
+/// value. Arrays and maps store their items elsewhere, so they
+/// don't make edges.
+struct Edge {
+    /// Index of the field or member
+    via: usize,
+    /// Index of the node it points to
+    to: usize,
+    /// Whether the value is behind an option
+    optional: bool,
+}
+
+struct Node<'a> {
+    decl: Decl<'a>,
+    edges: Vec<Edge>,
+}
+
+/// How the structs and unions of a schema contain each other. Types
+/// that end up containing themselves need indirection in languages
+/// that store values inline, and can't be constructed at all if
+/// nothing on the cycle is optional.
+pub struct TypeGraph<'a> {
+    nodes: Vec<Node<'a>>,
+    /// Fields and members on a cycle, by node key and index
+    recursive: HashSet<(usize, usize)>,
+}
+
+impl<'a> TypeGraph<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        let mut anchored = Vec::new();
+        collect(&Stack::new(schema), &schema.body, &mut anchored);
+
+        let index: HashMap<usize, usize> = anchored
+            .iter()
+            .enumerate()
+            .map(|(i, (decl, _))| (decl.key(), i))
+            .collect();
+
+        let mut nodes = Vec::new();
+        for (decl, stack) in &anchored {
+            let mut edges = Vec::new();
+            let types: Vec<&Type> = match decl {
+                Decl::Struct(st) => st.fields.iter().map(|f| &f.typ).collect(),
+                Decl::Union(un) => un.members.iter().map(|m| &m.typ).collect(),
+            };
+            for (via, typ) in types.into_iter().enumerate() {
+                if let Some((target, optional)) = by_value(stack, typ, false) {
+                    // types from other schemas can't refer back to this one
+                    if let Some(&to) = index.get(&target.key()) {
+                        edges.push(Edge { via, to, optional });
+                    }
+                }
+            }
+            nodes.push(Node { decl: *decl, edges });
+        }
+
+        let mut graph = Self {
+            nodes,
+            recursive: HashSet::new(),
+        };
+        for (from, node) in graph.nodes.iter().enumerate() {
+            for edge in &node.edges {
+                if graph.reaches(edge.to, from) {
+                    graph.recursive.insert((node.decl.key(), edge.via));
+                }
+            }
+        }
+        graph
+    }
+
+    /// Returns true if `from` contains `to` by value, directly or not
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut seen = HashSet::new();
+        let mut queue = vec![from];
+        while let Some(i) = queue.pop() {
+            if i == to {
+                return true;
+            }
+            if seen.insert(i) {
+                queue.extend(self.nodes[i].edges.iter().map(|e| e.to));
+            }
+        }
+        false
+    }
+
+    /// Returns true if the `index`-th field of `st` leads back to `st`.
+    pub fn is_recursive_field(&self, st: &StructDecl, index: usize) -> bool {
+        self.recursive.contains(&(Decl::Struct(st).key(), index))
+    }
+
+    /// Returns true if the `index`-th member of `un` leads back to `un`.
+    pub fn is_recursive_member(&self, un: &UnionDecl, index: usize) -> bool {
+        self.recursive.contains(&(Decl::Union(un).key(), index))
+    }
+
+    /// Returns true if `decl` contains itself by value, directly or not.
+    pub fn is_recursive(&self, decl: Decl) -> bool {
+        let key = decl.key();
+        self.recursive.iter().any(|&(k, _)| k == key)
+    }
+
+    /// Returns the types that contain themselves without any way out,
+    /// like a struct with a mandatory field of its own type, along with
+    /// the field that first leads into the cycle, for structs.
+    pub fn unconstructible(&self) -> Vec<(Decl<'a>, Option<&'a Field>)> {
+        // a type can be constructed if all its mandatory fields can,
+        // or, for unions, if any of its members can.
+        let mut constructible = vec![false; self.nodes.len()];
+        loop {
+            let mut changed = false;
+            for (i, node) in self.nodes.iter().enumerate() {
+                if constructible[i] {
+                    continue;
+                }
+                let ok = match node.decl {
+                    Decl::Struct(_) => node.edges.iter().all(|e| e.optional || constructible[e.to]),
+                    Decl::Union(un) => {
+                        node.edges.len() < un.members.len()
+                            || node.edges.iter().any(|e| e.optional || constructible[e.to])
+                    }
+                };
+                if ok {
+                    constructible[i] = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut res = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            // empty unions can't be constructed either, but
+            // that's on purpose.
+            if constructible[i] || !node.edges.iter().any(|e| self.reaches(e.to, i)) {
+                continue;
+            }
+            let field = match node.decl {
+                Decl::Struct(st) => node
+                    .edges
+                    .iter()
+                    .find(|e| !e.optional && !constructible[e.to])
+                    .map(|e| &st.fields[e.via]),
+                Decl::Union(_) => None,
+            };
+            res.push((node.decl, field));
+        }
+        res
+    }
+}
+
+/// Collects every struct and union of a namespace body, along with the
+/// stack their types are looked up from. Unlike `for_each_struct_of_schema`,
+/// the results outlive the walk.
+fn collect<'a>(stack: &Stack<'a>, body: &'a NamespaceBody, out: &mut Vec<(Decl<'a>, Stack<'a>)>) {
+    for st in &body.structs {
+        out.push((Decl::Struct(st), stack.clone()));
+    }
+    for un in &body.unions {
+        out.push((Decl::Union(un), stack.clone()));
+    }
+    for f in &body.functions {
+        let stack = stack.push(f);
+        out.push((Decl::Struct(&f.params), stack.clone()));
+        out.push((Decl::Struct(&f.results), stack.clone()));
+        if let Some(body) = f.body.as_ref() {
+            collect(&stack, body, out);
+        }
+    }
+    for ns in &body.namespaces {
+        collect(&stack.push(ns), &ns.body, out);
+    }
+}
+
+/// Returns the struct or union a type holds by value, if any, and
+/// whether it's optional.
+fn by_value<'a>(stack: &Stack<'a>, typ: &'a Type, optional: bool) -> Option<(Decl<'a>, bool)> {
+    match &typ.kind {
+        TypeKind::Option(opt) => by_value(stack, &opt.inner, true),
+        TypeKind::User => {
+            let down: Vec<&str> = typ.text().split('.').collect();
+            match stack.lookup_struct(LookupMode::Relaxed, &down[..])?.symbol {
+                Symbol::Struct(st) => Some((Decl::Struct(st), optional)),
+                Symbol::Union(un) => Some((Decl::Union(un), optional)),
+                _ => None,
+            }
+        }
+        TypeKind::Base(_) | TypeKind::Array(_) | TypeKind::Map(_) => None,
+    }
+}
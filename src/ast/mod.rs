@@ -3,3 +3,6 @@ pub use nodes::*;
 
 mod anchored;
 pub use anchored::*;
+
+mod graph;
+pub use graph::*;
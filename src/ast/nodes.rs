@@ -1,29 +1,88 @@
-use crate::ast;
 use crate::parser::Span;
 use log::*;
 use simple_error::SimpleError;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 pub const LAVISH_EXT: &str = ".lavish";
 pub const VENDOR_DIR: &str = "lavish-vendor";
+pub const LOCK_FILE: &str = "lavish-lock";
+pub const RULES_FILE: &str = "lavish-rules";
 
 #[derive(Debug, Clone)]
 pub struct Rules {
     pub loc: Span,
-    pub target: Target,
+    /// Only optional for repositories that just export schemas
+    pub target: Option<Target>,
     pub builds: Vec<Build>,
+    pub exports: Vec<Export>,
+    pub lints: Vec<LintDirective>,
+    /// Directives and trivia in source order, for tools that reprint rules
+    pub layout: Vec<RulesLayout>,
 }
 
 impl Rules {
-    pub fn new(loc: Span, target: Target, builds: Vec<Build>) -> Self {
-        Self {
+    pub fn new(loc: Span, target: Option<Target>, items: Vec<RulesItem>) -> Self {
+        let mut s = Self {
             loc,
             target,
-            builds,
+            builds: Vec::new(),
+            exports: Vec::new(),
+            lints: Vec::new(),
+            layout: Vec::new(),
+        };
+        for item in items {
+            let entry = match item {
+                RulesItem::Build(build) => {
+                    s.builds.push(build);
+                    RulesLayout::Build(s.builds.len() - 1)
+                }
+                RulesItem::Export(export) => {
+                    s.exports.push(export);
+                    RulesLayout::Export(s.exports.len() - 1)
+                }
+                RulesItem::Lint(lint) => {
+                    s.lints.push(lint);
+                    RulesLayout::Lint(s.lints.len() - 1)
+                }
+                RulesItem::Trivia(trivia) => RulesLayout::Trivia(trivia),
+            };
+            s.layout.push(entry);
         }
+        s
     }
+
+    /// Returns the export named `name`, if any.
+    pub fn find_export(&self, name: &str) -> Option<&Export> {
+        self.exports.iter().find(|e| e.name.text() == name)
+    }
+}
+
+pub enum RulesItem {
+    Build(Build),
+    Export(Export),
+    Lint(LintDirective),
+    Trivia(Trivia),
+}
+
+/// An entry of `Rules::layout`: directives are indices into their lists.
+#[derive(Debug, Clone)]
+pub enum RulesLayout {
+    Build(usize),
+    Export(usize),
+    Lint(usize),
+    Trivia(Trivia),
+}
+
+/// What the parser would otherwise throw away, but formatters need.
+#[derive(Debug, Clone)]
+pub enum Trivia {
+    /// Comment lines that aren't attached to anything
+    Comment(Comment),
+    /// One or more empty lines
+    BlankLine,
 }
 
 #[derive(Debug, Clone)]
@@ -32,17 +91,91 @@ pub struct Build {
     pub from: Option<FromDirective>,
 }
 
+/// In rules: `export "./path/to/schema.lavish" as name`, lets other
+/// workspaces fetch a schema from this repository by name.
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub path: StringLiteral,
+    pub name: Identifier,
+}
+
+/// In rules: `deny non_snake_case`, changes the severity of a lint
+/// for every schema of the workspace.
+#[derive(Debug, Clone)]
+pub struct LintDirective {
+    pub severity: Severity,
+    pub id: Identifier,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Severity {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Allow => "allow",
+            Severity::Warn => "warn",
+            Severity::Deny => "deny",
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FromDirective {
     pub path: StringLiteral,
 }
 
+impl FromDirective {
+    /// Local sources are schema files, relative to the workspace.
+    /// Anything else is vendored by `lavish fetch`.
+    pub fn is_local(&self) -> bool {
+        let value = &self.path.value;
+        value.ends_with(LAVISH_EXT) && !value.starts_with("file://")
+    }
+}
+
+/// Contents of a `lavish-lock` file, as written by `lavish fetch`
+#[derive(Debug, Clone)]
+pub struct Lockfile {
+    pub loc: Span,
+    pub entries: Vec<LockEntry>,
+}
+
+impl Lockfile {
+    /// Returns the entry for `name`, if it was locked from `from`.
+    pub fn find(&self, name: &str, from: &FromDirective) -> Option<&LockEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.name.text() == name && e.from.path.value == from.path.value)
+    }
+}
+
+/// In lockfile: `lock name from "source" { revision "..." sha256 "..." }`
+#[derive(Debug, Clone)]
+pub struct LockEntry {
+    pub name: Identifier,
+    pub from: FromDirective,
+    /// Only set for sources that are git repositories
+    pub revision: Option<StringLiteral>,
+    pub sha256: StringLiteral,
+}
+
 #[derive(Debug, Clone)]
 pub struct StringLiteral {
     pub loc: Span,
     pub value: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct IntegerLiteral {
+    pub loc: Span,
+    pub value: i64,
+}
+
 #[derive(Debug, Clone)]
 pub enum Target {
     Rust(RustTarget),
@@ -92,26 +225,40 @@ pub struct TypeScriptTarget {}
 pub struct Workspace {
     pub dir: PathBuf,
     pub rules: Rules,
-    pub members: HashMap<String, WorkspaceMember>,
+    /// By name, so that generated code lists them in a stable order
+    pub members: BTreeMap<String, WorkspaceMember>,
 }
 
 impl Workspace {
-    pub fn resolve(&self, build: &ast::Build) -> Result<PathBuf, SimpleError> {
-        if let Some(from) = build.from.as_ref() {
-            // TODO: test for relative paths, otherwise
-            // fall back on HTTP
-            let path = self.dir.join(&from.path.value);
-            return Ok(path);
-        }
-
-        let name = build.name.text().to_string();
+    /// Finds the schema for a build or an import. Local sources are
+    /// relative to `base_dir`, remote ones are looked up in `lavish-vendor`.
+    pub fn resolve_source(
+        &self,
+        base_dir: &Path,
+        name: &Identifier,
+        from: Option<&FromDirective>,
+    ) -> Result<PathBuf, SimpleError> {
+        let name = name.text().to_string();
 
         let source_name = format!("{}{}", name, LAVISH_EXT);
 
-        let self_path = self.dir.join(&source_name);
-        debug!("Trying self path {:?}", self_path);
-        if self_path.exists() {
-            return Ok(self_path);
+        match from {
+            Some(from) if from.is_local() => {
+                let path = base_dir.join(&from.path.value);
+                return Ok(path);
+            }
+            // remote sources only ever resolve to their vendored copy
+            Some(_) => {}
+            None => {
+                // next to the importing schema, then in the workspace
+                for dir in &[base_dir, self.dir.as_path()] {
+                    let self_path = dir.join(&source_name);
+                    debug!("Trying self path {:?}", self_path);
+                    if self_path.exists() {
+                        return Ok(self_path);
+                    }
+                }
+            }
         }
 
         let vendor_path = self.dir.join(VENDOR_DIR).join(&source_name);
@@ -125,6 +272,20 @@ impl Workspace {
             name
         )))
     }
+
+    /// Returns the member an import was resolved to, which may be
+    /// a build of the workspace that goes by another name.
+    pub fn member_for(&self, import: &Import) -> Option<&WorkspaceMember> {
+        let schema = import.schema.as_ref()?;
+        let mut members: Vec<_> = self
+            .members
+            .values()
+            .filter(|m| m.schema.as_ref().map(|s| Rc::ptr_eq(s, schema)) == Some(true))
+            .collect();
+        // prefer members that go by the import's name, for stable output
+        members.sort_by_key(|m| (m.name != import.name.text(), m.name.clone()));
+        members.into_iter().next()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -132,7 +293,7 @@ pub struct WorkspaceMember {
     pub name: String,
     pub build: Option<Build>,
     pub imports: Vec<Import>,
-    pub schema: Option<Schema>,
+    pub schema: Option<Rc<Schema>>,
 }
 
 #[derive(Debug, Clone)]
@@ -140,11 +301,41 @@ pub struct Schema {
     pub loc: Span,
     pub imports: Vec<Import>,
     pub body: NamespaceBody,
+    /// Names of the items that failed to parse and were left out
+    pub broken: Vec<String>,
 }
 
 impl Schema {
     pub fn new(loc: Span, imports: Vec<Import>, body: NamespaceBody) -> Self {
-        Schema { loc, imports, body }
+        Schema {
+            loc,
+            imports,
+            body,
+            broken: Vec::new(),
+        }
+    }
+
+    /// Returns every import of this schema, and of the schemas it
+    /// imports, once per name and once per imported schema.
+    pub fn transitive_imports(&self) -> Vec<&Import> {
+        fn visit<'a>(schema: &'a Schema, res: &mut Vec<&'a Import>) {
+            for import in &schema.imports {
+                if res
+                    .iter()
+                    .any(|i| i.name.text() == import.name.text() || i.same_schema(import))
+                {
+                    continue;
+                }
+                res.push(import);
+                if let Some(schema) = import.schema.as_ref() {
+                    visit(schema, res);
+                }
+            }
+        }
+
+        let mut res = Vec::new();
+        visit(self, &mut res);
+        res
     }
 }
 
@@ -152,6 +343,18 @@ impl Schema {
 pub struct Import {
     pub name: Identifier,
     pub from: Option<FromDirective>,
+    /// Set once the workspace has resolved the import
+    pub schema: Option<Rc<Schema>>,
+}
+
+impl Import {
+    /// Returns true if both imports resolved to the same schema.
+    pub fn same_schema(&self, other: &Import) -> bool {
+        match (self.schema.as_ref(), other.schema.as_ref()) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -182,7 +385,13 @@ pub struct NamespaceBody {
     pub functions: Vec<FunctionDecl>,
     pub structs: Vec<StructDecl>,
     pub enums: Vec<EnumDecl>,
+    pub unions: Vec<UnionDecl>,
     pub namespaces: Vec<NamespaceDecl>,
+    /// Items and trivia in source order, for tools that reprint schemas
+    pub layout: Vec<NamespaceLayout>,
+    /// Everything between the braces, or the whole schema after its
+    /// imports, to know what's in scope at a given offset
+    pub span: Span,
 }
 
 impl NamespaceDecl {
@@ -197,12 +406,15 @@ impl NamespaceDecl {
 }
 
 impl NamespaceBody {
-    pub fn new(items: Vec<NamespaceItem>) -> Self {
+    pub fn new(span: Span, items: Vec<NamespaceItem>) -> Self {
         let mut bod = Self {
             functions: Vec::new(),
             structs: Vec::new(),
             enums: Vec::new(),
+            unions: Vec::new(),
             namespaces: Vec::new(),
+            layout: Vec::new(),
+            span,
         };
         for item in items {
             bod.add_item(item);
@@ -211,20 +423,30 @@ impl NamespaceBody {
     }
 
     fn add_item(&mut self, item: NamespaceItem) {
-        match item {
+        let entry = match item {
             NamespaceItem::Function(i) => {
                 self.functions.push(i);
+                NamespaceLayout::Function(self.functions.len() - 1)
             }
             NamespaceItem::Struct(i) => {
                 self.structs.push(i);
+                NamespaceLayout::Struct(self.structs.len() - 1)
             }
             NamespaceItem::Enum(i) => {
                 self.enums.push(i);
+                NamespaceLayout::Enum(self.enums.len() - 1)
+            }
+            NamespaceItem::Union(i) => {
+                self.unions.push(i);
+                NamespaceLayout::Union(self.unions.len() - 1)
             }
             NamespaceItem::Namespace(i) => {
                 self.namespaces.push(i);
+                NamespaceLayout::Namespace(self.namespaces.len() - 1)
             }
+            NamespaceItem::Trivia(trivia) => NamespaceLayout::Trivia(trivia),
         };
+        self.layout.push(entry);
     }
 }
 
@@ -233,7 +455,20 @@ pub enum NamespaceItem {
     Function(FunctionDecl),
     Struct(StructDecl),
     Enum(EnumDecl),
+    Union(UnionDecl),
     Namespace(NamespaceDecl),
+    Trivia(Trivia),
+}
+
+/// An entry of `NamespaceBody::layout`: items are indices into their lists.
+#[derive(Debug, Clone)]
+pub enum NamespaceLayout {
+    Function(usize),
+    Struct(usize),
+    Enum(usize),
+    Union(usize),
+    Namespace(usize),
+    Trivia(Trivia),
 }
 
 #[derive(Debug, Clone)]
@@ -248,7 +483,7 @@ pub struct FunctionDecl {
     pub side: Side,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum Side {
     Client,
     Server,
@@ -359,20 +594,106 @@ pub struct EnumDecl {
     pub variants: Vec<EnumVariant>,
 }
 
+impl EnumDecl {
+    /// How variants of this enum are sent over the wire. The checker
+    /// makes sure all variants agree.
+    pub fn repr(&self) -> EnumRepr {
+        match self.variants.first().and_then(|v| v.value.as_ref()) {
+            None => EnumRepr::Index,
+            Some(EnumValue::String(_)) => EnumRepr::String,
+            Some(EnumValue::Integer(_)) => EnumRepr::Integer,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum EnumRepr {
+    /// No explicit values: variants are sent as their index
+    Index,
+    String,
+    Integer,
+}
+
 #[derive(Debug, Clone)]
 pub struct EnumVariant {
     pub loc: Span,
     pub comment: Option<Comment>,
     pub name: Identifier,
+    /// In schema: `Anonymous = "anonymous"` or `Admin = 4`
+    pub value: Option<EnumValue>,
+}
+
+#[derive(Debug, Clone)]
+pub enum EnumValue {
+    String(StringLiteral),
+    Integer(IntegerLiteral),
+}
+
+impl EnumValue {
+    pub fn loc(&self) -> &Span {
+        match self {
+            EnumValue::String(lit) => &lit.loc,
+            EnumValue::Integer(lit) => &lit.loc,
+        }
+    }
+}
+
+impl fmt::Display for EnumValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EnumValue::String(lit) => write!(f, "{:?}", lit.value),
+            EnumValue::Integer(lit) => write!(f, "{}", lit.value),
+        }
+    }
+}
+
+/// In schema: `union Either { Mood, Participant }`. Values of
+/// a union are a value of exactly one of its member types.
+#[derive(Debug, Clone)]
+pub struct UnionDecl {
+    pub loc: Span,
+    pub comment: Option<Comment>,
+    pub name: Identifier,
+    pub members: Vec<UnionMember>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnionMember {
+    pub comment: Option<Comment>,
+    pub typ: Type,
+}
+
+impl UnionMember {
+    /// Name of the variant holding this member: the last
+    /// part of its type name, so `itchio.Game` is `Game`.
+    pub fn variant(&self) -> &str {
+        self.typ.text().rsplit('.').next().unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Comment {
+    /// Text after `//` and one optional space
     pub lines: Vec<String>,
+    /// Indices of the lines preceded by a blank line, which only
+    /// the formatter keeps. `lines.len()` if one separates the
+    /// comment from its item.
+    pub breaks: Vec<usize>,
+}
+
+impl Comment {
+    /// Returns the lines as generated code and editors show them:
+    /// without their indentation.
+    pub fn doc_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(|l| l.trim_start())
+    }
 }
 
 impl std::default::Default for Comment {
     fn default() -> Self {
-        Comment { lines: Vec::new() }
+        Comment {
+            lines: Vec::new(),
+            breaks: Vec::new(),
+        }
     }
 }
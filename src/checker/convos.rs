@@ -1,9 +1,19 @@
 use super::ast;
 use super::Error;
-use colored::*;
 
+/// Makes sure functions declared in the body of another function
+/// are implemented by the other side: while the server is handling
+/// a call, it can only call back into the client, and vice versa.
 struct Visitor {
     num_errors: i64,
+    /// The innermost function whose body we're in, if any
+    parent: Option<Parent>,
+}
+
+#[derive(Clone)]
+struct Parent {
+    name: ast::Identifier,
+    side: ast::Side,
 }
 
 impl Visitor {
@@ -21,9 +31,7 @@ trait Visitable {
 
 impl Visitable for &ast::Schema {
     fn visit(self, v: &mut Visitor) {
-        for ns in &self.body.namespaces {
-            v.visit(ns);
-        }
+        v.visit(&self.body);
     }
 }
 
@@ -46,44 +54,43 @@ impl Visitable for &ast::NamespaceBody {
 
 impl Visitable for &ast::FunctionDecl {
     fn visit(self, v: &mut Visitor) {
-        if let Some(body) = self.body.as_ref() {
-            let expected_side = self.side.other();
-            for f in &body.functions {
-                if f.side != expected_side {
-                    v.num_errors += 1;
-                    f.name
-                        .span
-                        .position()
-                        .diag_err(format!(
-                            "{} {} should be {}",
-                            "error:".red().bold(),
-                            f.name.text(),
-                            expected_side
-                        ))
-                        .print();
-                    self.name
-                        .span
-                        .position()
-                        .diag_info(format!(
+        if let Some(parent) = v.parent.as_ref() {
+            let expected_side = parent.side.other();
+            if self.side != expected_side {
+                v.num_errors += 1;
+                self.name
+                    .span
+                    .position()
+                    .diag_err(format!("{} should be {}", self.name.text(), expected_side))
+                    .code("wrong_side")
+                    .note(
+                        parent.name.span.position(),
+                        format!(
                             "because its parent, {}, is {}",
-                            self.name.text(),
-                            self.side
-                        ))
-                        .print();
-                }
+                            parent.name.text(),
+                            parent.side
+                        ),
+                    )
+                    .print();
             }
+        }
 
+        if let Some(body) = self.body.as_ref() {
+            let outer = v.parent.replace(Parent {
+                name: self.name.clone(),
+                side: self.side,
+            });
             v.visit(body);
+            v.parent = outer;
         }
     }
 }
 
-impl Visitable for &ast::Field {
-    fn visit(self, _v: &mut Visitor) {}
-}
-
 pub fn check(schema: &ast::Schema) -> Result<(), Error> {
-    let mut v = Visitor { num_errors: 0 };
+    let mut v = Visitor {
+        num_errors: 0,
+        parent: None,
+    };
     v.visit(schema);
     if v.num_errors > 0 {
         Err(Error {
@@ -0,0 +1,90 @@
+use super::ast;
+use super::Error;
+use std::collections::HashMap;
+
+/// Makes sure explicit enum values can be used on the wire: either
+/// every variant has one or none does, they're all of the same kind,
+/// and no two variants share a value.
+struct Visitor {
+    num_errors: i64,
+}
+
+impl Visitor {
+    fn check_enum(&mut self, en: &ast::EnumDecl) {
+        let first = match en.variants.first() {
+            Some(first) => first,
+            None => return,
+        };
+
+        let mut values: HashMap<String, &ast::EnumVariant> = HashMap::new();
+        for variant in &en.variants {
+            let value = match (first.value.as_ref(), variant.value.as_ref()) {
+                (None, None) => continue,
+                (Some(_), Some(value)) => value,
+                _ => {
+                    self.num_errors += 1;
+                    variant
+                        .name
+                        .span
+                        .position()
+                        .diag_err(format!(
+                            "either every variant of {} has a value, or none does",
+                            en.name.text()
+                        ))
+                        .code("mixed_enum_values")
+                        .print();
+                    continue;
+                }
+            };
+
+            if let (Some(ast::EnumValue::String(_)), ast::EnumValue::Integer(_))
+            | (Some(ast::EnumValue::Integer(_)), ast::EnumValue::String(_)) =
+                (first.value.as_ref(), value)
+            {
+                self.num_errors += 1;
+                value
+                    .loc()
+                    .position()
+                    .diag_err("enum values must all be strings or all be integers".into())
+                    .code("mixed_enum_values")
+                    .print();
+                continue;
+            }
+
+            if let Some(old) = values.insert(value.to_string(), variant) {
+                self.num_errors += 1;
+                value
+                    .loc()
+                    .position()
+                    .diag_err(format!(
+                        "{} and {} have the same value, {}",
+                        old.name.text(),
+                        variant.name.text(),
+                        value,
+                    ))
+                    .code("duplicate_enum_value")
+                    .note(
+                        old.name.span.position(),
+                        "first variant with that value was here".into(),
+                    )
+                    .print();
+            }
+        }
+    }
+}
+
+pub fn check(schema: &ast::Schema) -> Result<(), Error> {
+    let mut v = Visitor { num_errors: 0 };
+    let stack = ast::Stack::new(schema);
+    stack
+        .anchor(&schema.body)
+        .for_each_enum_of_schema(&mut |en| v.check_enum(en.inner));
+
+    if v.num_errors > 0 {
+        Err(Error {
+            num_errors: v.num_errors,
+        })
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use super::ast;
+use super::maps;
+use super::Error;
+use crate::parser;
+use heck::{CamelCase, SnakeCase};
+use std::collections::HashMap;
+
+/// A naming convention, which can be allowed, warned about
+/// or denied from `lavish-rules`.
+pub struct Lint {
+    /// Stable identifier, as used in `lavish-rules`
+    pub id: &'static str,
+    pub default: ast::Severity,
+}
+
+/// Struct, enum, union and enum variant names should be `CamelCase`
+pub const NON_CAMEL_CASE_TYPES: Lint = Lint {
+    id: "non_camel_case_types",
+    default: ast::Severity::Warn,
+};
+
+/// Function, field and namespace names should be `snake_case`
+pub const NON_SNAKE_CASE: Lint = Lint {
+    id: "non_snake_case",
+    default: ast::Severity::Warn,
+};
+
+/// Names can't start with a digit in most target languages
+pub const LEADING_DIGIT: Lint = Lint {
+    id: "leading_digit",
+    default: ast::Severity::Deny,
+};
+
+/// `data` map keys can't be hashed in every target language: Go
+/// generates `string` keys for them, and the Rust runtime's `Bin`
+/// has to be hashable.
+pub const DATA_MAP_KEYS: Lint = Lint {
+    id: "data_map_keys",
+    default: ast::Severity::Deny,
+};
+
+pub const LINTS: &[&Lint] = &[
+    &NON_CAMEL_CASE_TYPES,
+    &NON_SNAKE_CASE,
+    &LEADING_DIGIT,
+    &DATA_MAP_KEYS,
+];
+
+/// The severity of every lint, after `lavish-rules` overrides.
+pub struct Levels {
+    levels: HashMap<&'static str, ast::Severity>,
+}
+
+impl Levels {
+    pub fn new(rules: &ast::Rules) -> Result<Self, Error> {
+        let mut levels: HashMap<_, _> = LINTS.iter().map(|l| (l.id, l.default)).collect();
+        let mut num_errors = 0;
+        for directive in &rules.lints {
+            match LINTS.iter().find(|l| l.id == directive.id.text()) {
+                Some(lint) => {
+                    levels.insert(lint.id, directive.severity);
+                }
+                None => {
+                    num_errors += 1;
+                    directive
+                        .id
+                        .span
+                        .position()
+                        .diag_err(format!(
+                            "unknown lint {}, expected one of: {}",
+                            directive.id.text(),
+                            LINTS.iter().map(|l| l.id).collect::<Vec<_>>().join(", ")
+                        ))
+                        .code("unknown_lint")
+                        .print();
+                }
+            }
+        }
+
+        if num_errors > 0 {
+            Err(Error { num_errors })
+        } else {
+            Ok(Self { levels })
+        }
+    }
+
+    fn get(&self, lint: &Lint) -> ast::Severity {
+        self.levels[lint.id]
+    }
+}
+
+struct Visitor<'a> {
+    levels: &'a Levels,
+    num_errors: i64,
+}
+
+impl<'a> Visitor<'a> {
+    fn report(&mut self, lint: &Lint, span: &parser::Span, message: String) {
+        let diag = match self.levels.get(lint) {
+            ast::Severity::Allow => return,
+            ast::Severity::Warn => span.position().diag_warn(message),
+            ast::Severity::Deny => {
+                self.num_errors += 1;
+                span.position().diag_err(message)
+            }
+        };
+        diag.code(lint.id).print();
+    }
+
+    /// Returns false if the name was reported for starting
+    /// with a digit, so it isn't reported twice.
+    fn check_digit(&mut self, kind: &str, name: &ast::Identifier) -> bool {
+        let starts_with_digit = name
+            .text()
+            .chars()
+            .next()
+            .map(|c| c.is_ascii_digit())
+            .unwrap_or_default();
+        if starts_with_digit {
+            self.report(
+                &LEADING_DIGIT,
+                &name.span,
+                format!("{} {} starts with a digit", kind, name.text()),
+            );
+        }
+        !starts_with_digit
+    }
+
+    fn check_camel(&mut self, kind: &str, name: &ast::Identifier) {
+        if !self.check_digit(kind, name) {
+            return;
+        }
+        let text = name.text();
+        let is_camel = text.starts_with(|c: char| c.is_ascii_uppercase()) && !text.contains('_');
+        if !is_camel {
+            self.report(
+                &NON_CAMEL_CASE_TYPES,
+                &name.span,
+                format!(
+                    "{} {} should be CamelCase, like {}",
+                    kind,
+                    text,
+                    text.to_camel_case()
+                ),
+            );
+        }
+    }
+
+    fn check_snake(&mut self, kind: &str, name: &ast::Identifier) {
+        if !self.check_digit(kind, name) {
+            return;
+        }
+        let text = name.text();
+        if text.chars().any(|c| c.is_ascii_uppercase()) {
+            self.report(
+                &NON_SNAKE_CASE,
+                &name.span,
+                format!(
+                    "{} {} should be snake_case, like {}",
+                    kind,
+                    text,
+                    text.to_snake_case()
+                ),
+            );
+        }
+    }
+
+    fn visit_body(&mut self, body: &ast::NamespaceBody) {
+        for ns in &body.namespaces {
+            self.check_snake("namespace", &ns.name);
+            self.visit_body(&ns.body);
+        }
+        for st in &body.structs {
+            self.check_camel("struct", &st.name);
+            self.visit_fields(st);
+        }
+        for en in &body.enums {
+            self.check_camel("enum", &en.name);
+            for variant in &en.variants {
+                self.check_camel("variant", &variant.name);
+            }
+        }
+        for un in &body.unions {
+            self.check_camel("union", &un.name);
+        }
+        for f in &body.functions {
+            self.check_snake("function", &f.name);
+            // params and results are named by the compiler
+            self.visit_fields(&f.params);
+            self.visit_fields(&f.results);
+            if let Some(body) = f.body.as_ref() {
+                self.visit_body(body);
+            }
+        }
+    }
+
+    fn visit_fields(&mut self, st: &ast::StructDecl) {
+        for field in &st.fields {
+            self.check_snake("field", &field.name);
+        }
+    }
+}
+
+/// Checks naming conventions and portability, reporting each problem with the
+/// severity its lint has in `levels`. Only denied lints are errors.
+pub fn check(schema: &ast::Schema, levels: &Levels) -> Result<(), Error> {
+    let mut v = Visitor {
+        levels,
+        num_errors: 0,
+    };
+    for import in &schema.imports {
+        v.check_snake("import", &import.name);
+    }
+    v.visit_body(&schema.body);
+    maps::for_each_key(schema, &mut |stack, typ| {
+        if let maps::KeyKind::Data = maps::key_kind(stack, typ) {
+            v.report(
+                &DATA_MAP_KEYS,
+                &typ.span,
+                "data map keys aren't supported by every target language".into(),
+            );
+        }
+    });
+
+    if v.num_errors > 0 {
+        Err(Error {
+            num_errors: v.num_errors,
+        })
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use super::ast;
+use super::Error;
+
+/// What a type is worth as a map key.
+pub enum KeyKind {
+    /// Hashable and comparable in every target language
+    Valid,
+    /// Only allowed when `data_map_keys` is
+    Data,
+    /// Can't be a key, for the given reason
+    Invalid(&'static str),
+}
+
+/// Returns what `typ` is worth as a map key. Unresolved user types
+/// are considered valid, the `types` pass reports them already.
+pub fn key_kind(stack: &ast::Stack, typ: &ast::Type) -> KeyKind {
+    use ast::BaseType as T;
+    use ast::TypeKind;
+
+    match &typ.kind {
+        TypeKind::Base(base) => match base {
+            T::Bool
+            | T::I8
+            | T::I16
+            | T::I32
+            | T::I64
+            | T::U8
+            | T::U16
+            | T::U32
+            | T::U64
+            | T::String => KeyKind::Valid,
+            T::Data => KeyKind::Data,
+            T::F32 | T::F64 => KeyKind::Invalid("floats can't be compared reliably"),
+            T::Timestamp => KeyKind::Invalid("timestamps have no portable key representation"),
+        },
+        TypeKind::Array(_) | TypeKind::Map(_) => {
+            KeyKind::Invalid("collections aren't hashable in every target language")
+        }
+        TypeKind::Option(_) => KeyKind::Invalid("options can't be keys"),
+        TypeKind::User => {
+            let down: Vec<&str> = typ.text().split('.').collect();
+            match stack.lookup_struct(ast::LookupMode::Relaxed, &down[..]) {
+                Some(ast::RelativePath {
+                    symbol: ast::Symbol::Struct(_),
+                    ..
+                }) => KeyKind::Invalid("structs aren't hashable in every target language"),
+                Some(ast::RelativePath {
+                    symbol: ast::Symbol::Union(_),
+                    ..
+                }) => KeyKind::Invalid("unions aren't hashable in every target language"),
+                _ => KeyKind::Valid,
+            }
+        }
+    }
+}
+
+/// Calls `cb` with the key type of every map used by fields, params,
+/// results and union members, along with the stack to look it up from.
+pub fn for_each_key(schema: &ast::Schema, cb: &mut dyn FnMut(&ast::Stack, &ast::Type)) {
+    fn visit(stack: &ast::Stack, typ: &ast::Type, cb: &mut dyn FnMut(&ast::Stack, &ast::Type)) {
+        use ast::TypeKind;
+
+        match &typ.kind {
+            TypeKind::Base(_) | TypeKind::User => {}
+            TypeKind::Array(arr) => visit(stack, &arr.inner, cb),
+            TypeKind::Option(opt) => visit(stack, &opt.inner, cb),
+            TypeKind::Map(map) => {
+                cb(stack, &map.keys);
+                visit(stack, &map.keys, cb);
+                visit(stack, &map.values, cb);
+            }
+        }
+    }
+
+    let stack = ast::Stack::new(schema);
+    let body = stack.anchor(&schema.body);
+    body.for_each_struct_of_schema(&mut |st| {
+        for field in &st.fields {
+            visit(&st.stack, &field.typ, cb);
+        }
+    });
+    body.for_each_union_of_schema(&mut |un| {
+        for member in &un.members {
+            visit(&un.stack, &member.typ, cb);
+        }
+    });
+}
+
+/// Makes sure map keys can be hashed and compared in every target
+/// language. `data` keys are left to the `data_map_keys` lint.
+pub fn check(schema: &ast::Schema) -> Result<(), Error> {
+    let mut num_errors = 0;
+    for_each_key(schema, &mut |stack, typ| {
+        if let KeyKind::Invalid(reason) = key_kind(stack, typ) {
+            num_errors += 1;
+            typ.span
+                .position()
+                .diag_err(format!(
+                    "{} can't be used as a map key ({}): keys can be integers, bools, strings or enums",
+                    typ.text(),
+                    reason,
+                ))
+                .code("invalid_map_key")
+                .print();
+        }
+    });
+
+    if num_errors > 0 {
+        Err(Error { num_errors })
+    } else {
+        Ok(())
+    }
+}
@@ -2,11 +2,20 @@ use crate::ast;
 use std::fmt;
 
 mod convos;
+mod enums;
+mod lints;
+mod maps;
 mod noredef;
+mod recursion;
+mod reserved;
+mod types;
+mod unions;
 
 mod print;
 pub use print::print;
 
+pub use lints::Levels;
+
 #[derive(Debug)]
 pub struct Error {
     pub num_errors: i64,
@@ -14,15 +23,59 @@ pub struct Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} errors found", self.num_errors)
+        match self.num_errors {
+            1 => write!(f, "1 error found"),
+            n => write!(f, "{} errors found", n),
+        }
     }
 }
 
 impl std::error::Error for Error {}
 
+/// Runs every pass, even if earlier ones found errors, so that all
+/// problems are reported at once.
 pub fn check(schema: &ast::Schema) -> Result<(), Error> {
-    // TODO: check name collisions in namespaces
-    noredef::check(schema)?;
-    convos::check(schema)?;
-    Ok(())
+    merge(vec![
+        noredef::check(schema),
+        convos::check(schema),
+        enums::check(schema),
+        unions::check(schema),
+        types::check(schema),
+        maps::check(schema),
+        recursion::check(schema),
+    ])
+}
+
+/// Adds up the errors of passes that ran independently.
+pub fn merge(results: Vec<Result<(), Error>>) -> Result<(), Error> {
+    let num_errors: i64 = results
+        .iter()
+        .filter_map(|res| res.as_ref().err())
+        .map(|e| e.num_errors)
+        .sum();
+    if num_errors > 0 {
+        Err(Error { num_errors })
+    } else {
+        Ok(())
+    }
+}
+
+/// Resolves types again, once the workspace has loaded the schema's
+/// imports: types from imported schemas can't be checked before that.
+pub fn check_imported_types(schema: &ast::Schema) -> Result<(), Error> {
+    types::check(schema)
+}
+
+/// Checks naming conventions and portability. Unlike other passes, it depends on
+/// the workspace, since `lavish-rules` sets the severity of lints.
+pub fn lint(schema: &ast::Schema, levels: &Levels) -> Result<(), Error> {
+    lints::check(schema, levels)
+}
+
+/// Rejects names the workspace's target language can't emit.
+pub fn reserved(
+    schema: &ast::Schema,
+    words: &dyn crate::codegen::ReservedWords,
+) -> Result<(), Error> {
+    reserved::check(schema, words)
 }
@@ -1,6 +1,5 @@
 use super::ast;
 use super::Error;
-use colored::*;
 use std::collections::HashMap;
 
 struct Visitor {
@@ -15,30 +14,78 @@ impl Visitor {
         v.visit(self)
     }
 
-    fn check_dupes<'b, T>(&mut self, kind: &str, items: &'b [T])
+    fn check_dupes<'b, T>(&mut self, kind: &'b str, items: &'b [T])
     where
         T: Named<'b>,
     {
-        let mut set: HashMap<&str, &T> = HashMap::new();
-        for item in items {
-            let name = item.name();
-            if let Some(old) = set.insert(&name.text(), item) {
-                self.num_errors += 1;
-                name.span
-                    .position()
-                    .diag_err(format!(
-                        "{} {} {} redefined",
-                        "error:".red().bold(),
-                        kind,
-                        name.text()
-                    ))
-                    .print();
-                old.name()
-                    .span
-                    .position()
-                    .diag_info("first definition was here".into())
-                    .print();
-            }
+        let names: Vec<_> = items.iter().map(|item| (kind, item.name())).collect();
+        self.check_names(&names);
+    }
+
+    /// Reports items that end up with the same name. Items of a namespace
+    /// all share one scope in generated code, whatever their kind, so a
+    /// struct and an enum (or a namespace and a function) can't share a name.
+    fn check_names(&mut self, items: &[(&str, &ast::Identifier)]) {
+        let mut set: HashMap<&str, (&str, &ast::Identifier)> = HashMap::new();
+        for &(kind, name) in items {
+            let (old_kind, old) = match set.get(name.text()) {
+                Some(&old) => old,
+                None => {
+                    set.insert(name.text(), (kind, name));
+                    continue;
+                }
+            };
+
+            self.num_errors += 1;
+            let message = if kind == old_kind {
+                format!("{} {} redefined", kind, name.text())
+            } else {
+                format!(
+                    "{} {} clashes with {} {}",
+                    kind,
+                    name.text(),
+                    old_kind,
+                    old.text()
+                )
+            };
+            name.span
+                .position()
+                .diag_err(message)
+                .code(if kind == old_kind {
+                    "redefinition"
+                } else {
+                    "name_clash"
+                })
+                .note(old.span.position(), "first definition was here".into())
+                .print();
+        }
+    }
+
+    /// Checks the names of a namespace body, along with `extra`
+    /// names that live in the same scope, then visits its items.
+    fn visit_body<'b>(
+        &mut self,
+        body: &'b ast::NamespaceBody,
+        mut names: Vec<(&'b str, &'b ast::Identifier)>,
+    ) {
+        names.extend(body.namespaces.iter().map(|x| ("namespace", &x.name)));
+        names.extend(body.structs.iter().map(|x| ("struct", &x.name)));
+        names.extend(body.enums.iter().map(|x| ("enum", &x.name)));
+        names.extend(body.unions.iter().map(|x| ("union", &x.name)));
+        names.extend(body.functions.iter().map(|x| ("function", &x.name)));
+        self.check_names(&names);
+
+        for ns in &body.namespaces {
+            self.visit(ns);
+        }
+        for s in &body.structs {
+            self.visit(s);
+        }
+        for e in &body.enums {
+            self.visit(e);
+        }
+        for f in &body.functions {
+            self.visit(f);
         }
     }
 }
@@ -64,37 +111,21 @@ macro_rules! impl_named {
 impl_named!(ast::NamespaceDecl);
 impl_named!(ast::StructDecl);
 impl_named!(ast::FunctionDecl);
+impl_named!(ast::UnionDecl);
 impl_named!(ast::Field);
+impl_named!(ast::EnumVariant);
 
 impl Visitable for &ast::Schema {
     fn visit(self, v: &mut Visitor) {
-        v.check_dupes("namespace", &self.body.namespaces);
-        for ns in &self.body.namespaces {
-            v.visit(ns);
-        }
+        // imports are namespaces too, as far as generated code goes
+        let names = self.imports.iter().map(|x| ("import", &x.name)).collect();
+        v.visit_body(&self.body, names);
     }
 }
 
 impl Visitable for &ast::NamespaceDecl {
     fn visit(self, v: &mut Visitor) {
-        self.body.visit(v)
-    }
-}
-
-impl Visitable for &ast::NamespaceBody {
-    fn visit(self, v: &mut Visitor) {
-        v.check_dupes("namespace", &self.namespaces);
-        for ns in &self.namespaces {
-            v.visit(ns);
-        }
-        v.check_dupes("struct", &self.structs);
-        for s in &self.structs {
-            v.visit(s);
-        }
-        v.check_dupes("function", &self.functions);
-        for f in &self.functions {
-            v.visit(f);
-        }
+        v.visit_body(&self.body, Vec::new());
     }
 }
 
@@ -107,13 +138,24 @@ impl Visitable for &ast::StructDecl {
     }
 }
 
+impl Visitable for &ast::EnumDecl {
+    fn visit(self, v: &mut Visitor) {
+        v.check_dupes("variant", &self.variants);
+    }
+}
+
 impl Visitable for &ast::FunctionDecl {
     fn visit(self, v: &mut Visitor) {
         v.visit(&self.params);
         v.visit(&self.results);
 
         if let Some(body) = self.body.as_ref() {
-            v.visit(body);
+            // params and results are generated next to the body's items
+            let names = vec![
+                ("struct", &self.params.name),
+                ("struct", &self.results.name),
+            ];
+            v.visit_body(body, names);
         }
     }
 }
@@ -66,12 +66,34 @@ impl Visitable for &ast::NamespaceBody {
         for s in &self.structs {
             v.visit(s);
         }
+        for u in &self.unions {
+            v.visit(u);
+        }
         for f in &self.functions {
             v.visit(f);
         }
     }
 }
 
+impl Visitable for &ast::UnionDecl {
+    fn visit(self, v: &mut Visitor) {
+        v.print(
+            &self.loc,
+            format!(
+                "union {} of {}{}",
+                self.name.text().yellow(),
+                self.members
+                    .iter()
+                    .map(|m| m.typ.text())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .green(),
+                format_comment(&self.comment),
+            ),
+        );
+    }
+}
+
 impl Visitable for &ast::StructDecl {
     fn visit(self, v: &mut Visitor) {
         v.print(
@@ -0,0 +1,40 @@
+use super::ast;
+use super::Error;
+
+/// Rejects types that contain themselves with no way out: values
+/// of those types would be infinitely large. Cycles that go through
+/// an option, an array or a map are fine.
+pub fn check(schema: &ast::Schema) -> Result<(), Error> {
+    let graph = ast::TypeGraph::new(schema);
+    let mut num_errors = 0;
+
+    for (decl, field) in graph.unconstructible() {
+        num_errors += 1;
+        let mut diag = decl
+            .name()
+            .span
+            .position()
+            .diag_err(format!(
+                "{} {} contains itself, so it can't be constructed",
+                decl.kind(),
+                decl.name().text(),
+            ))
+            .code("infinite_type");
+        if let Some(field) = field {
+            diag = diag.note(
+                field.name.span.position(),
+                format!(
+                    "{} is mandatory: making it an option, an array or a map would break the cycle",
+                    field.name.text()
+                ),
+            );
+        }
+        diag.print();
+    }
+
+    if num_errors > 0 {
+        Err(Error { num_errors })
+    } else {
+        Ok(())
+    }
+}
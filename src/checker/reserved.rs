@@ -0,0 +1,230 @@
+use super::ast;
+use super::Error;
+use crate::codegen::{ReservedWords, Usage};
+use crate::parser::Position;
+use std::collections::HashMap;
+
+/// Rejects names the target language can't emit, even escaped,
+/// like `self` in Rust, and for languages that flatten namespaces,
+/// declarations that would end up with the same name.
+struct Visitor<'a> {
+    words: &'a dyn ReservedWords,
+    num_errors: i64,
+
+    /// Namespaces and functions we're in
+    path: Vec<String>,
+    /// What each flattened name was first used by
+    claims: HashMap<Flat, Claim>,
+}
+
+/// Flattened names only clash with names of the same kind: methods
+/// belong to the client of their side, and fields to their struct.
+#[derive(PartialEq, Eq, Hash)]
+enum Flat {
+    Type(String),
+    Method(ast::Side, String),
+    Field(String, String),
+}
+
+struct Claim {
+    what: String,
+    /// None for names the generated code declares on its own
+    pos: Option<Position>,
+}
+
+impl<'a> Visitor<'a> {
+    fn check_name(&mut self, kind: &str, usage: Usage, name: &ast::Identifier) {
+        if !self.words.is_unusable(usage, name.text()) {
+            return;
+        }
+
+        self.num_errors += 1;
+        name.span
+            .position()
+            .diag_err(format!(
+                "{} {} is a reserved word in {}, and can't be used as a name",
+                kind,
+                name.text(),
+                self.words.language(),
+            ))
+            .code("reserved_word")
+            .print();
+    }
+
+    /// Returns the flattened name of `names`, relative to where we are
+    fn flat_name(&self, names: &[&str]) -> Option<String> {
+        let path: Vec<&str> = self
+            .path
+            .iter()
+            .map(|s| s.as_str())
+            .chain(names.iter().cloned())
+            .collect();
+        self.words.flat_name(&path)
+    }
+
+    /// Returns the full name of `name` in the schema, like `foo.Bar`
+    fn full_name(&self, name: &str) -> String {
+        self.path
+            .iter()
+            .map(|s| s.as_str())
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Records that `what`, declared at `name`, is emitted as `flat`,
+    /// and reports it if something else already is.
+    fn claim(&mut self, flat: Flat, what: String, name: &ast::Identifier) {
+        let pos = name.span.position();
+        let emitted = match &flat {
+            Flat::Type(n) | Flat::Method(_, n) | Flat::Field(_, n) => n.clone(),
+        };
+
+        match self.claims.get(&flat) {
+            None => {
+                self.claims.insert(
+                    flat,
+                    Claim {
+                        what,
+                        pos: Some(pos),
+                    },
+                );
+            }
+            Some(other) => {
+                self.num_errors += 1;
+                let message = format!(
+                    "{} is emitted as {} in {}",
+                    what,
+                    emitted,
+                    self.words.language()
+                );
+                match other.pos.as_ref() {
+                    Some(other_pos) => pos
+                        .diag_err(format!("{}, like {}", message, other.what))
+                        .code("name_collision")
+                        .note(
+                            other_pos.clone(),
+                            format!("{} is declared here", other.what),
+                        )
+                        .print(),
+                    None => pos
+                        .diag_err(format!("{}, which {}", message, other.what))
+                        .code("name_collision")
+                        .print(),
+                }
+            }
+        }
+    }
+
+    fn claim_type(&mut self, kind: &str, name: &ast::Identifier) {
+        if let Some(flat) = self.flat_name(&[name.text()]) {
+            let what = format!("{} {}", kind, self.full_name(name.text()));
+            self.claim(Flat::Type(flat), what, name);
+        }
+    }
+
+    fn visit_body(&mut self, body: &ast::NamespaceBody) {
+        for ns in &body.namespaces {
+            self.check_name("namespace", Usage::Declaration, &ns.name);
+            self.path.push(ns.name.text().into());
+            self.visit_body(&ns.body);
+            self.path.pop();
+        }
+        for st in &body.structs {
+            self.check_name("struct", Usage::Declaration, &st.name);
+            self.claim_type("struct", &st.name);
+            self.visit_fields(&[st.name.text()], st);
+        }
+        for en in &body.enums {
+            self.check_name("enum", Usage::Declaration, &en.name);
+            self.claim_type("enum", &en.name);
+            for variant in &en.variants {
+                self.check_name("variant", Usage::Member, &variant.name);
+                if let Some(flat) = self.flat_name(&[en.name.text(), variant.name.text()]) {
+                    let what = format!(
+                        "variant {}.{}",
+                        self.full_name(en.name.text()),
+                        variant.name.text()
+                    );
+                    self.claim(Flat::Type(flat), what, &variant.name);
+                }
+            }
+        }
+        for un in &body.unions {
+            self.check_name("union", Usage::Declaration, &un.name);
+            self.claim_type("union", &un.name);
+        }
+        for f in &body.functions {
+            self.check_name("function", Usage::Declaration, &f.name);
+            self.visit_function(f);
+        }
+    }
+
+    fn visit_function(&mut self, f: &ast::FunctionDecl) {
+        let name = f.name.text();
+        if let Some(flat) = self.flat_name(&[name]) {
+            let what = format!("function {}", self.full_name(name));
+            self.claim(Flat::Method(f.side, flat), what, &f.name);
+        }
+        for (st, kind) in &[(&f.params, "params"), (&f.results, "results")] {
+            if let Some(flat) = self.flat_name(&[name, kind]) {
+                let what = format!("{} of function {}", kind, self.full_name(name));
+                self.claim(Flat::Type(flat), what, &f.name);
+            }
+            self.visit_fields(&[name, kind], st);
+        }
+
+        if let Some(body) = f.body.as_ref() {
+            self.path.push(name.into());
+            self.visit_body(body);
+            self.path.pop();
+        }
+    }
+
+    fn visit_fields(&mut self, names: &[&str], st: &ast::StructDecl) {
+        let parent = self.flat_name(names);
+        for field in &st.fields {
+            self.check_name("field", Usage::Member, &field.name);
+            if let (Some(parent), Some(flat)) =
+                (parent.as_ref(), self.words.flat_name(&[field.name.text()]))
+            {
+                let what = format!(
+                    "field {}.{}",
+                    self.full_name(&names.join(".")),
+                    field.name.text()
+                );
+                self.claim(Flat::Field(parent.clone(), flat), what, &field.name);
+            }
+        }
+    }
+}
+
+pub fn check(schema: &ast::Schema, words: &dyn ReservedWords) -> Result<(), Error> {
+    let mut v = Visitor {
+        words,
+        num_errors: 0,
+        path: Vec::new(),
+        claims: HashMap::new(),
+    };
+    for &name in words.builtin_names() {
+        v.claims.insert(
+            Flat::Type(name.into()),
+            Claim {
+                what: "the generated code declares on its own".into(),
+                pos: None,
+            },
+        );
+    }
+    for import in &schema.imports {
+        v.check_name("import", Usage::Module, &import.name);
+    }
+    v.visit_body(&schema.body);
+
+    if v.num_errors > 0 {
+        Err(Error {
+            num_errors: v.num_errors,
+        })
+    } else {
+        Ok(())
+    }
+}
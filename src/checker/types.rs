@@ -0,0 +1,108 @@
+use super::ast;
+use super::Error;
+
+/// Makes sure every user type used by fields, params, results and
+/// union members resolves to a struct, an enum or a union, so that
+/// generators never run into a type they can't name.
+struct Visitor<'a> {
+    num_errors: i64,
+    /// Items that failed to parse: the syntax error is enough
+    broken: &'a [String],
+}
+
+impl<'a> Visitor<'a> {
+    fn check_struct(&mut self, st: ast::Anchored<&ast::StructDecl>) {
+        for field in &st.fields {
+            self.check_type(&st.stack, &field.typ);
+        }
+    }
+
+    fn check_union(&mut self, un: ast::Anchored<&ast::UnionDecl>) {
+        for member in &un.members {
+            self.check_type(&un.stack, &member.typ);
+        }
+    }
+
+    fn check_type(&mut self, stack: &ast::Stack, typ: &ast::Type) {
+        use ast::TypeKind;
+
+        match &typ.kind {
+            TypeKind::Base(_) => {}
+            TypeKind::Array(arr) => self.check_type(stack, &arr.inner),
+            TypeKind::Option(opt) => self.check_type(stack, &opt.inner),
+            TypeKind::Map(map) => {
+                self.check_type(stack, &map.keys);
+                self.check_type(stack, &map.values);
+            }
+            TypeKind::User => self.check_user_type(stack, typ),
+        }
+    }
+
+    fn check_user_type(&mut self, stack: &ast::Stack, typ: &ast::Type) {
+        let down: Vec<&str> = typ.text().split('.').collect();
+        let message = match stack.lookup_struct(ast::LookupMode::Relaxed, &down[..]) {
+            Some(path) => match path.symbol {
+                ast::Symbol::Struct(_) | ast::Symbol::Enum(_) | ast::Symbol::Union(_) => return,
+                ast::Symbol::Namespace(_) => format!("{} is a namespace, not a type", typ.text()),
+                ast::Symbol::Import(_) => format!("{} is an import, not a type", typ.text()),
+            },
+            None => {
+                if through_unloaded_import(stack, &down[..]) {
+                    // checked again once the workspace has loaded it.
+                    return;
+                }
+                if down
+                    .iter()
+                    .any(|name| self.broken.iter().any(|b| b == name))
+                {
+                    return;
+                }
+                format!(
+                    "unknown type {:?}: not a built-in, and not in scope either",
+                    typ.text()
+                )
+            }
+        };
+
+        self.num_errors += 1;
+        typ.span
+            .position()
+            .diag_err(message)
+            .code("unknown_type")
+            .print();
+    }
+}
+
+/// Returns true if a type path starts with an import whose schema
+/// hasn't been loaded yet, so its types can't be looked up.
+fn through_unloaded_import(stack: &ast::Stack, down: &[&str]) -> bool {
+    if down.len() < 2 {
+        return false;
+    }
+    match stack.lookup_struct(ast::LookupMode::Relaxed, &down[..1]) {
+        Some(ast::RelativePath {
+            symbol: ast::Symbol::Import(import),
+            ..
+        }) => import.schema.is_none(),
+        _ => false,
+    }
+}
+
+pub fn check(schema: &ast::Schema) -> Result<(), Error> {
+    let mut v = Visitor {
+        num_errors: 0,
+        broken: &schema.broken,
+    };
+    let stack = ast::Stack::new(schema);
+    let body = stack.anchor(&schema.body);
+    body.for_each_struct_of_schema(&mut |st| v.check_struct(st));
+    body.for_each_union_of_schema(&mut |un| v.check_union(un));
+
+    if v.num_errors > 0 {
+        Err(Error {
+            num_errors: v.num_errors,
+        })
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use super::ast;
+use super::Error;
+use std::collections::HashMap;
+
+/// Makes sure no two members of a union would get the same variant
+/// name. Member types are resolved by the `types` pass.
+struct Visitor {
+    num_errors: i64,
+}
+
+impl Visitor {
+    fn check_union(&mut self, un: ast::Anchored<&ast::UnionDecl>) {
+        let mut variants: HashMap<&str, &ast::UnionMember> = HashMap::new();
+        for member in &un.members {
+            if let Some(old) = variants.insert(member.variant(), member) {
+                self.num_errors += 1;
+                member
+                    .typ
+                    .span
+                    .position()
+                    .diag_err(format!(
+                        "union {} has two {} variants",
+                        un.name(),
+                        member.variant(),
+                    ))
+                    .code("duplicate_union_variant")
+                    .note(old.typ.span.position(), "first variant was here".into())
+                    .print();
+            }
+        }
+    }
+}
+
+pub fn check(schema: &ast::Schema) -> Result<(), Error> {
+    let mut v = Visitor { num_errors: 0 };
+    let stack = ast::Stack::new(schema);
+    stack
+        .anchor(&schema.body)
+        .for_each_union_of_schema(&mut |un| v.check_union(un));
+
+    if v.num_errors > 0 {
+        Err(Error {
+            num_errors: v.num_errors,
+        })
+    } else {
+        Ok(())
+    }
+}
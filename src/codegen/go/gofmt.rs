@@ -0,0 +1,101 @@
+use crate::codegen::output::INDENT_WIDTH;
+
+/// Separates the cells of a line that `gofmt` aligns with the lines
+/// around it, like the name, type and tag of struct fields. It's a
+/// vertical tab, like in `go/printer`.
+pub const CELL: char = '\u{b}';
+
+/// Lays out generated code the way `gofmt` would: indents with tabs,
+/// aligns the cells of consecutive lines, strips trailing whitespace,
+/// and collapses runs of blank lines.
+pub fn format(source: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in source.lines() {
+        let text = line.trim_start_matches(' ');
+        let depth = (line.len() - text.len()) / INDENT_WIDTH;
+        let line = format!("{}{}", "\t".repeat(depth), text.trim_end());
+
+        let after_blank = lines.last().map(|l| l.is_empty()).unwrap_or(true);
+        if line.is_empty() && after_blank {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().map(|l| l.is_empty()) == Some(true) {
+        lines.pop();
+    }
+
+    let mut start = 0;
+    while start < lines.len() {
+        // comments and blank lines end blocks, and so do indentation changes
+        let depth = indentation(&lines[start]);
+        let end = start
+            + lines[start..]
+                .iter()
+                .take_while(|l| l.contains(CELL) && indentation(l) == depth)
+                .count();
+        if end == start {
+            start += 1;
+        } else {
+            align(&mut lines[start..end]);
+            start = end;
+        }
+    }
+
+    let mut res = lines.join("\n");
+    res.push('\n');
+    res
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches('\t').len()
+}
+
+/// Pads every cell but the last of each line to the width of the
+/// widest cell in its column, plus one space.
+fn align(block: &mut [String]) {
+    let rows: Vec<Vec<&str>> = block.iter().map(|l| l.split(CELL).collect()).collect();
+
+    let mut widths = Vec::<usize>::new();
+    for row in &rows {
+        for (i, cell) in row[..row.len() - 1].iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(i) {
+                Some(w) => *w = std::cmp::max(*w, width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    let aligned: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                line.push_str(cell);
+                if i < row.len() - 1 {
+                    let padding = widths[i] + 1 - cell.chars().count();
+                    line.push_str(&" ".repeat(padding));
+                }
+            }
+            line
+        })
+        .collect();
+    for (line, res) in block.iter_mut().zip(aligned) {
+        *line = res;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format;
+
+    #[test]
+    fn gofmt() {
+        let source = "type T struct {\n    // A is short\n    A\u{b}int\u{b}`lavish:\"a\"`\n    LongName\u{b}*string\u{b}`lavish:\"long_name\"`\n    B\u{b}int\u{b}`lavish:\"b\"`\n}\n\n\n// \nconst X = 1\n\n";
+        assert_eq!(
+            format(source),
+            "type T struct {\n\t// A is short\n\tA        int     `lavish:\"a\"`\n\tLongName *string `lavish:\"long_name\"`\n\tB        int     `lavish:\"b\"`\n}\n\n//\nconst X = 1\n"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+#![allow(non_snake_case)]
+
+use crate::ast;
+use heck::CamelCase;
+
+/// Go packages are flat, so namespaced symbols get their
+/// full path baked into their name: `foo.bar.Baz` becomes `FooBarBaz`.
+pub fn go_name(names: &[&str]) -> String {
+    names.iter().map(|x| x.to_camel_case()).collect()
+}
+
+pub trait GoSide {
+    fn go_name(&self) -> String;
+    fn Client(&self) -> String;
+    fn Handler(&self) -> String;
+}
+
+impl GoSide for ast::Side {
+    fn go_name(&self) -> String {
+        format!("{}", self).to_camel_case()
+    }
+
+    fn Client(&self) -> String {
+        format!("{}Client", self.go_name())
+    }
+
+    fn Handler(&self) -> String {
+        format!("{}Handler", self.go_name())
+    }
+}
+
+pub trait GoFn {
+    fn go_name(&self) -> String;
+    fn Params(&self) -> String;
+    fn Results(&self) -> String;
+}
+
+impl<'a> GoFn for ast::Anchored<'a, &ast::FunctionDecl> {
+    // Go name of a function, for example `session.attempt_login`
+    // will have name `SessionAttemptLogin`
+    fn go_name(&self) -> String {
+        go_name(&self.names())
+    }
+
+    fn Params(&self) -> String {
+        format!("{}Params", self.go_name())
+    }
+
+    fn Results(&self) -> String {
+        format!("{}Results", self.go_name())
+    }
+}
+
+pub trait GoStruct {
+    fn go_name(&self) -> String;
+}
+
+impl<'a> GoStruct for ast::Anchored<'a, &ast::StructDecl> {
+    fn go_name(&self) -> String {
+        go_name(&self.names())
+    }
+}
+
+pub trait GoEnum {
+    fn go_name(&self) -> String;
+    fn variant(&self, variant: &ast::EnumVariant) -> String;
+}
+
+impl<'a> GoEnum for ast::Anchored<'a, &ast::EnumDecl> {
+    fn go_name(&self) -> String {
+        go_name(&self.names())
+    }
+
+    fn variant(&self, variant: &ast::EnumVariant) -> String {
+        format!("{}{}", self.go_name(), variant.name.text().to_camel_case())
+    }
+}
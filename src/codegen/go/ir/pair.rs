@@ -0,0 +1,237 @@
+use crate::codegen::go::prelude::*;
+
+pub fn write_pair(s: &mut Scope, body: ast::Anchored<&ast::NamespaceBody>) {
+    for &side in &[ast::Side::Server, ast::Side::Client] {
+        s.write(Client {
+            side,
+            body: body.clone(),
+        });
+        s.write(Handler {
+            side,
+            body: body.clone(),
+        });
+    }
+}
+
+/// Calls functions implemented by `side`
+pub struct Client<'a> {
+    pub side: ast::Side,
+    pub body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Client<'a> {
+    fn for_each_fun(&self, cb: &mut dyn FnMut(ast::Anchored<&ast::FunctionDecl>)) {
+        let side = self.side;
+        self.body.for_each_fun_of_schema(&mut |f| {
+            if f.side == side {
+                cb(f);
+            }
+        });
+    }
+
+    fn define_client(&self, s: &mut Scope) {
+        let name = self.side.Client();
+
+        writeln!(
+            s,
+            "// {name} calls functions implemented by the {side}.",
+            name = name,
+            side = self.side
+        )
+        .unwrap();
+        write!(s, "type {name} struct", name = name).unwrap();
+        s.in_block(|s| {
+            s.line("caller Caller");
+        });
+        s.lf();
+
+        write!(s, "func New{name}(caller Caller) *{name}", name = name).unwrap();
+        s.in_block(|s| {
+            writeln!(s, "return &{name}{{caller: caller}}", name = name).unwrap();
+        });
+        s.lf();
+
+        self.for_each_fun(&mut |f| {
+            s.comment_with("//", &f.comment);
+            match f.kind {
+                ast::Kind::Request => {
+                    write!(
+                        s,
+                        "func (c *{name}) {method}(params {P}) ({R}, error)",
+                        name = name,
+                        method = f.go_name(),
+                        P = f.Params(),
+                        R = f.Results(),
+                    )
+                    .unwrap();
+                    s.in_block(|s| {
+                        writeln!(s, "var results {R}", R = f.Results()).unwrap();
+                        writeln!(
+                            s,
+                            "err := c.caller.Call({method:?}, &params, &results)",
+                            method = f.method()
+                        )
+                        .unwrap();
+                        s.line("return results, err");
+                    });
+                }
+                ast::Kind::Notification => {
+                    write!(
+                        s,
+                        "func (c *{name}) {method}(params {P}) error",
+                        name = name,
+                        method = f.go_name(),
+                        P = f.Params(),
+                    )
+                    .unwrap();
+                    s.in_block(|s| {
+                        writeln!(
+                            s,
+                            "return c.caller.Notify({method:?}, &params)",
+                            method = f.method()
+                        )
+                        .unwrap();
+                    });
+                }
+            }
+            s.lf();
+        });
+    }
+}
+
+impl<'a> Display for Client<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            self.define_client(s);
+        })
+    }
+}
+
+/// Routes incoming calls to implementations of functions of `side`
+pub struct Handler<'a> {
+    pub side: ast::Side,
+    pub body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Handler<'a> {
+    fn for_each_fun(&self, cb: &mut dyn FnMut(ast::Anchored<&ast::FunctionDecl>)) {
+        let side = self.side;
+        self.body.for_each_fun_of_schema(&mut |f| {
+            if f.side == side {
+                cb(f);
+            }
+        });
+    }
+
+    fn define_handler(&self, s: &mut Scope) {
+        let name = self.side.Handler();
+
+        writeln!(
+            s,
+            "// {name} routes incoming calls to implementations of {side} functions.",
+            name = name,
+            side = self.side
+        )
+        .unwrap();
+        s.line("// Its zero value is ready to use.");
+        write!(s, "type {name} struct", name = name).unwrap();
+        s.in_block(|s| {
+            s.line("slots map[string]slot");
+        });
+        s.lf();
+
+        write!(
+            s,
+            "func (h *{name}) register(method string, s slot)",
+            name = name
+        )
+        .unwrap();
+        s.in_block(|s| {
+            s.write("if h.slots == nil").in_block(|s| {
+                s.line("h.slots = make(map[string]slot)");
+            });
+            s.line("h.slots[method] = s");
+        });
+        s.lf();
+
+        self.for_each_fun(&mut |f| self.write_slot(s, &name, f));
+        self.write_handle(s, &name);
+    }
+
+    fn write_slot(&self, s: &mut Scope, name: &str, f: ast::Anchored<&ast::FunctionDecl>) {
+        let peer = self.side.other().Client();
+
+        writeln!(
+            s,
+            "// On{method} registers the implementation of `{lit}`.",
+            method = f.go_name(),
+            lit = f.method()
+        )
+        .unwrap();
+        let ret = match f.kind {
+            ast::Kind::Request => format!("({R}, error)", R = f.Results()),
+            ast::Kind::Notification => "error".into(),
+        };
+        write!(
+            s,
+            "func (h *{name}) On{method}(f func(client *{peer}, params {P}) {ret})",
+            name = name,
+            method = f.go_name(),
+            peer = peer,
+            P = f.Params(),
+            ret = ret,
+        )
+        .unwrap();
+        s.in_block(|s| {
+            write!(
+                s,
+                "h.register({lit:?}, func(caller Caller, decode Decoder) (interface{{}}, error)",
+                lit = f.method()
+            )
+            .unwrap();
+            s.in_terminated_block(")", |s| {
+                writeln!(s, "var params {P}", P = f.Params()).unwrap();
+                s.write("if err := decode(&params); err != nil")
+                    .in_block(|s| {
+                        s.line("return nil, err");
+                    });
+                match f.kind {
+                    ast::Kind::Request => {
+                        writeln!(s, "return f(New{peer}(caller), params)", peer = peer).unwrap();
+                    }
+                    ast::Kind::Notification => {
+                        writeln!(s, "return nil, f(New{peer}(caller), params)", peer = peer)
+                            .unwrap();
+                    }
+                }
+            });
+        });
+        s.lf();
+    }
+
+    fn write_handle(&self, s: &mut Scope, name: &str) {
+        s.line("// Handle is called by the lavish runtime for every incoming call.");
+        write!(
+            s,
+            "func (h *{name}) Handle(caller Caller, method string, decode Decoder) (interface{{}}, error)",
+            name = name
+        )
+        .unwrap();
+        s.in_block(|s| {
+            s.line("s, ok := h.slots[method]");
+            s.write("if !ok").in_block(|s| {
+                s.line("return nil, fmt.Errorf(\"lavish: method unimplemented: %s\", method)");
+            });
+            s.line("return s(caller, decode)");
+        });
+        s.lf();
+    }
+}
+
+impl<'a> Display for Handler<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            self.define_handler(s);
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use crate::codegen::go::prelude::*;
+
+pub struct Imports<'a> {
+    body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Imports<'a> {
+    pub fn new(body: ast::Anchored<'a, &'a ast::NamespaceBody>) -> Self {
+        Self { body }
+    }
+
+    fn uses_time(&self) -> bool {
+        let mut res = false;
+        self.body.for_each_struct_of_schema(&mut |st| {
+            res = res || st.fields.iter().any(|f| uses_time(&f.typ));
+        });
+        res
+    }
+}
+
+impl<'a> Display for Imports<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            s.line("import (");
+            s.in_scope(|s| {
+                s.line(quoted("fmt"));
+                if self.uses_time() {
+                    s.line(quoted("time"));
+                }
+            });
+            s.line(")");
+            s.lf();
+        })
+    }
+}
+
+/// Interfaces the generated code expects from the lavish Go runtime.
+pub struct Runtime {}
+
+impl Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            s.line("// Caller is implemented by the lavish runtime, and lets");
+            s.line("// clients send requests and notifications to a peer.");
+            s.write("type Caller interface").in_block(|s| {
+                s.line("Call(method string, params interface{}, results interface{}) error");
+                s.line("Notify(method string, params interface{}) error");
+            });
+            s.lf();
+
+            s.line("// Decoder is provided by the lavish runtime to fill in");
+            s.line("// the params of an incoming request or notification.");
+            s.line("type Decoder func(params interface{}) error");
+            s.lf();
+
+            s.line("type slot func(caller Caller, decode Decoder) (interface{}, error)");
+            s.lf();
+        })
+    }
+}
+
+pub fn quoted<D>(d: D) -> String
+where
+    D: fmt::Debug,
+{
+    format!("{:?}", d)
+}
@@ -0,0 +1,122 @@
+use crate::codegen::go::prelude::*;
+use heck::CamelCase;
+
+pub struct Symbols<'a> {
+    body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Symbols<'a> {
+    pub fn new(body: ast::Anchored<'a, &'a ast::NamespaceBody>) -> Self {
+        Self { body }
+    }
+}
+
+impl<'a> Display for Symbols<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            // Go packages are flat, so we walk the whole schema
+            // (including function params and results) at once.
+            self.body.for_each_struct_of_schema(&mut |node| {
+                s.write(Struct::new(node));
+            });
+            self.body.for_each_enum_of_schema(&mut |node| {
+                s.write(Enum::new(node));
+            });
+        })
+    }
+}
+
+pub struct Struct<'a> {
+    node: ast::Anchored<'a, &'a ast::StructDecl>,
+}
+
+impl<'a> Struct<'a> {
+    fn new(node: ast::Anchored<'a, &'a ast::StructDecl>) -> Self {
+        Self { node }
+    }
+}
+
+impl<'a> Display for Struct<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let stack = &self.node.stack;
+
+            s.comment_with("//", &self.node.comment);
+            write!(s, "type {name} struct", name = self.node.go_name()).unwrap();
+            s.in_block(|s| {
+                for field in &self.node.fields {
+                    s.comment_with("//", &field.comment);
+                    writeln!(
+                        s,
+                        "{name}{cell}{typ}{cell}`lavish:{tag:?}`",
+                        name = field.name.text().to_camel_case(),
+                        typ = field.typ.as_go(stack),
+                        tag = field.name.text(),
+                        cell = gofmt::CELL,
+                    )
+                    .unwrap();
+                }
+            });
+            s.lf();
+        })
+    }
+}
+
+pub struct Enum<'a> {
+    node: ast::Anchored<'a, &'a ast::EnumDecl>,
+}
+
+impl<'a> Enum<'a> {
+    fn new(node: ast::Anchored<'a, &'a ast::EnumDecl>) -> Self {
+        Self { node }
+    }
+}
+
+impl<'a> Display for Enum<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let name = self.node.go_name();
+
+            s.comment_with("//", &self.node.comment);
+            let underlying = match self.node.repr() {
+                ast::EnumRepr::Index => "uint32",
+                ast::EnumRepr::Integer => "int64",
+                ast::EnumRepr::String => "string",
+            };
+            writeln!(
+                s,
+                "type {name} {underlying}",
+                name = name,
+                underlying = underlying
+            )
+            .unwrap();
+            s.lf();
+
+            if self.node.variants.is_empty() {
+                return;
+            }
+
+            s.line("const (");
+            s.in_scope(|s| {
+                for (i, v) in self.node.variants.iter().enumerate() {
+                    s.comment_with("//", &v.comment);
+                    let value = match v.value.as_ref() {
+                        Some(value) => value.to_string(),
+                        None => i.to_string(),
+                    };
+                    writeln!(
+                        s,
+                        "{variant}{cell}{name}{cell}= {value}",
+                        variant = self.node.variant(v),
+                        name = name,
+                        value = value,
+                        cell = gofmt::CELL,
+                    )
+                    .unwrap();
+                }
+            });
+            s.line(")");
+            s.lf();
+        })
+    }
+}
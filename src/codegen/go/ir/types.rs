@@ -0,0 +1,88 @@
+use crate::codegen::go::prelude::*;
+
+pub trait AsGo {
+    fn as_go<'a>(&'a self, stack: &'a ast::Stack<'a>) -> Box<dyn fmt::Display + 'a>;
+}
+
+struct GoType<'a>(pub ast::Anchored<'a, &'a ast::Type>);
+
+impl AsGo for ast::Type {
+    fn as_go<'a>(&'a self, stack: &'a ast::Stack<'a>) -> Box<dyn fmt::Display + 'a> {
+        Box::new(GoType(stack.anchor(self)))
+    }
+}
+
+impl<'a> fmt::Display for GoType<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ast::TypeKind;
+
+        match &self.0.kind {
+            TypeKind::Base(base) => base.generate_go(f),
+            TypeKind::Map(map) => match &map.keys.kind {
+                // slices can't be map keys, but strings hold any bytes
+                TypeKind::Base(ast::BaseType::Data) => {
+                    write!(f, "map[string]{V}", V = map.values.as_go(&self.0.stack))
+                }
+                _ => write!(
+                    f,
+                    "map[{K}]{V}",
+                    K = map.keys.as_go(&self.0.stack),
+                    V = map.values.as_go(&self.0.stack)
+                ),
+            },
+            TypeKind::Option(opt) => write!(f, "*{T}", T = opt.inner.as_go(&self.0.stack)),
+            TypeKind::Array(arr) => write!(f, "[]{T}", T = arr.inner.as_go(&self.0.stack)),
+            TypeKind::User => {
+                let t = &self.0;
+                let down: Vec<_> = t.text().split('.').collect();
+                // the checker makes sure every type resolves
+                let path = t
+                    .stack
+                    .lookup_struct(ast::LookupMode::Relaxed, &down[..])
+                    .expect("type to be resolved by the checker");
+                write!(f, "{}", go_name(&t.stack.absolute_names(&path)))
+            }
+        }
+    }
+}
+
+trait GeneratesGo {
+    fn generate_go(&self, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+impl GeneratesGo for ast::BaseType {
+    fn generate_go(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ast::BaseType as T;
+
+        match self {
+            T::Bool => write!(f, "bool"),
+            T::I8 => write!(f, "int8"),
+            T::I16 => write!(f, "int16"),
+            T::I32 => write!(f, "int32"),
+            T::I64 => write!(f, "int64"),
+            T::U8 => write!(f, "uint8"),
+            T::U16 => write!(f, "uint16"),
+            T::U32 => write!(f, "uint32"),
+            T::U64 => write!(f, "uint64"),
+            T::F32 => write!(f, "float32"),
+            T::F64 => write!(f, "float64"),
+            T::String => write!(f, "string"),
+            T::Data => write!(f, "[]byte"),
+            T::Timestamp => write!(f, "time.Time"),
+        }
+    }
+}
+
+/// Returns true if the type is, or contains, a timestamp. Used
+/// to decide whether the `time` package needs to be imported.
+pub fn uses_time(typ: &ast::Type) -> bool {
+    use ast::TypeKind;
+
+    match &typ.kind {
+        TypeKind::Base(ast::BaseType::Timestamp) => true,
+        TypeKind::Base(_) | TypeKind::User => false,
+        TypeKind::Map(map) => uses_time(&map.keys) || uses_time(&map.values),
+        TypeKind::Option(opt) => uses_time(&opt.inner),
+        TypeKind::Array(arr) => uses_time(&arr.inner),
+    }
+}
@@ -0,0 +1,265 @@
+use crate::ast;
+use crate::codegen::output::*;
+use crate::codegen::Result;
+
+use std::time::Instant;
+
+pub(crate) mod gofmt;
+mod prelude;
+
+mod ir;
+use ir::*;
+
+pub struct Generator<'a> {
+    #[allow(unused)]
+    target: ast::GoTarget,
+
+    opts: &'a crate::Opts,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(opts: &'a crate::Opts, target: ast::GoTarget) -> Self {
+        Self { opts, target }
+    }
+}
+
+/// Keywords of Go, which can't be used as package names.
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// Go symbols are CamelCased and namespaced into their name,
+/// so they never clash with keywords, but may clash with each other.
+/// Packages are named after members as they are.
+pub struct Keywords;
+
+impl super::ReservedWords for Keywords {
+    fn language(&self) -> &'static str {
+        "Go"
+    }
+
+    fn is_unusable(&self, usage: super::Usage, name: &str) -> bool {
+        usage == super::Usage::Module && KEYWORDS.contains(&name)
+    }
+
+    fn flat_name(&self, path: &[&str]) -> Option<String> {
+        Some(ir::ast_ext::go_name(path))
+    }
+
+    fn builtin_names(&self) -> &'static [&'static str] {
+        &[
+            "Caller",
+            "Decoder",
+            "ServerClient",
+            "NewServerClient",
+            "ServerHandler",
+            "ClientClient",
+            "NewClientClient",
+            "ClientHandler",
+        ]
+    }
+}
+
+impl<'a> super::Generator for Generator<'a> {
+    fn emit_workspace(&self, workspace: &ast::Workspace, files: &Files) -> Result {
+        for member in workspace.members.values() {
+            self.emit(workspace, member, files)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Generator<'a> {
+    fn write_prelude(&self, s: &mut Scope) {
+        s.line("// Code generated by lavish. DO NOT EDIT.");
+        s.line("// https://github.com/fasterthanlime/lavish");
+        s.lf();
+    }
+
+    fn emit(
+        &self,
+        workspace: &ast::Workspace,
+        member: &ast::WorkspaceMember,
+        files: &Files,
+    ) -> Result {
+        let start_instant = Instant::now();
+
+        let schema = member
+            .schema
+            .as_ref()
+            .expect("schema to be parsed")
+            .as_ref();
+        if !schema.imports.is_empty() {
+            for import in &schema.imports {
+                import
+                    .name
+                    .span
+                    .position()
+                    .diag_err(format!(
+                        "{} is imported, but the Go generator doesn't support imports yet",
+                        import.name.text()
+                    ))
+                    .code("unsupported")
+                    .print();
+            }
+            return Err("imports are not supported by the Go generator yet".into());
+        }
+        let stack = ast::Stack::new(schema);
+        let body = stack.anchor(&schema.body);
+
+        let mut has_unions = false;
+        body.for_each_union_of_schema(&mut |un| {
+            has_unions = true;
+            un.name
+                .span
+                .position()
+                .diag_err(format!(
+                    "union {} can't be generated: the Go generator doesn't support unions yet",
+                    un.name.text()
+                ))
+                .code("unsupported")
+                .print();
+        });
+        if has_unions {
+            return Err("unions are not supported by the Go generator yet".into());
+        }
+
+        let output_path = workspace
+            .dir
+            .join(&member.name)
+            .join(format!("{}.go", member.name));
+        let mut output = String::new();
+        let mut scope = Scope::new(&mut output);
+        let s = &mut scope;
+        self.write_prelude(s);
+
+        s.line(format!("package {}", member.name));
+        s.lf();
+        s.write(Imports::new(body.clone()));
+        s.write(Runtime {});
+        s.write(Symbols::new(body.clone()));
+        write_pair(s, body.clone());
+        files.save(&output_path, &gofmt::format(&output))?;
+
+        let end_instant = Instant::now();
+        if self.opts.verbose {
+            println!(
+                "Generated {:?} in {:?}",
+                output_path,
+                end_instant.duration_since(start_instant)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{codegen, Mode};
+    use crate::parser::sink;
+    use std::path::Path;
+
+    /// The generated code in `tests/` is checked in: if this fails,
+    /// review the diff and run `lavish build src/codegen/go/tests`.
+    #[test]
+    fn fixtures_are_up_to_date() -> Result<(), Box<dyn std::error::Error>> {
+        let opts = crate::Opts { verbose: false };
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("codegen")
+            .join("go")
+            .join("tests");
+        let workspace = crate::workspace::parse_workspace(&opts, &dir)?;
+        codegen(&opts, &workspace, Mode::Check)?;
+        Ok(())
+    }
+
+    /// Generates code for a workspace in `unsupported/`, returning the
+    /// error and the diagnostics it printed.
+    fn unsupported(name: &str) -> (String, Vec<sink::Record>) {
+        let opts = crate::Opts { verbose: false };
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("codegen")
+            .join("go")
+            .join("unsupported")
+            .join(name);
+        let workspace = crate::workspace::parse_workspace(&opts, &dir).unwrap();
+
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = codegen(&opts, &workspace, Mode::Check);
+        sink::set(Box::new(sink::Colored));
+        (format!("{}", res.unwrap_err()), collector.take())
+    }
+
+    #[test]
+    fn unions_are_unsupported() {
+        let (err, records) = unsupported("unions");
+        assert_eq!(
+            err,
+            "codegen error: unions are not supported by the Go generator yet"
+        );
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.code, r.start.line, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Some("unsupported"),
+                    0,
+                    "union Shape can't be generated: the Go generator doesn't support unions yet"
+                ),
+                (
+                    Some("unsupported"),
+                    14,
+                    "union Stroke can't be generated: the Go generator doesn't support unions yet"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_are_unsupported() {
+        let (err, records) = unsupported("imports");
+        assert_eq!(
+            err,
+            "codegen error: imports are not supported by the Go generator yet"
+        );
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].code, Some("unsupported"));
+        assert_eq!(
+            records[0].message,
+            "calendar is imported, but the Go generator doesn't support imports yet"
+        );
+        assert_eq!(records[0].start, sink::Location { line: 0, column: 7 });
+    }
+}
@@ -0,0 +1,8 @@
+#[allow(unused)]
+pub(crate) use crate::codegen::{
+    go::{
+        gofmt,
+        ir::{ast_ext::*, types::*},
+    },
+    prelude::*,
+};
@@ -0,0 +1,116 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package complex_types
+
+import (
+	"fmt"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+type KitchenSink struct {
+	Numbers       []int16           `lavish:"numbers"`
+	Scores        map[string]int32  `lavish:"scores"`
+	Set           *string           `lavish:"set"`
+	Unset         *string           `lavish:"unset"`
+	OptionalArray []*string         `lavish:"optional_array"`
+	OptionalMap   map[int32]*[]byte `lavish:"optional_map"`
+}
+
+type GetKitchenSinkParams struct {
+}
+
+type GetKitchenSinkResults struct {
+	Sink KitchenSink `lavish:"sink"`
+}
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) GetKitchenSink(params GetKitchenSinkParams) (GetKitchenSinkResults, error) {
+	var results GetKitchenSinkResults
+	err := c.caller.Call("get_kitchen_sink", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnGetKitchenSink registers the implementation of `get_kitchen_sink`.
+func (h *ServerHandler) OnGetKitchenSink(f func(client *ClientClient, params GetKitchenSinkParams) (GetKitchenSinkResults, error)) {
+	h.register("get_kitchen_sink", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params GetKitchenSinkParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
@@ -0,0 +1,107 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package data
+
+import (
+	"fmt"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+type GetSomeBytesParams struct {
+}
+
+type GetSomeBytesResults struct {
+	Value []byte `lavish:"value"`
+}
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) GetSomeBytes(params GetSomeBytesParams) (GetSomeBytesResults, error) {
+	var results GetSomeBytesResults
+	err := c.caller.Call("get_some_bytes", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnGetSomeBytes registers the implementation of `get_some_bytes`.
+func (h *ServerHandler) OnGetSomeBytes(f func(client *ClientClient, params GetSomeBytesParams) (GetSomeBytesResults, error)) {
+	h.register("get_some_bytes", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params GetSomeBytesParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
@@ -0,0 +1,453 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package deep_paths
+
+import (
+	"fmt"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+type GetBazParams struct {
+}
+
+type GetBazResults struct {
+	Baz FooBarBazBaz `lavish:"baz"`
+}
+
+type GetBarParams struct {
+}
+
+type GetBarResults struct {
+	Bar FooBarBar `lavish:"bar"`
+}
+
+type GetFooParams struct {
+}
+
+type GetFooResults struct {
+	Foo FooFoo `lavish:"foo"`
+}
+
+type FooFoo struct {
+}
+
+type FooGetBazParams struct {
+}
+
+type FooGetBazResults struct {
+	Baz FooBarBazBaz `lavish:"baz"`
+}
+
+type FooGetBarParams struct {
+}
+
+type FooGetBarResults struct {
+	Bar FooBarBar `lavish:"bar"`
+}
+
+type FooGetFooParams struct {
+}
+
+type FooGetFooResults struct {
+	Foo FooFoo `lavish:"foo"`
+}
+
+type FooBarBar struct {
+}
+
+type FooBarGetBazParams struct {
+}
+
+type FooBarGetBazResults struct {
+	Baz FooBarBazBaz `lavish:"baz"`
+}
+
+type FooBarGetBarParams struct {
+}
+
+type FooBarGetBarResults struct {
+	Bar FooBarBar `lavish:"bar"`
+}
+
+type FooBarGetFooParams struct {
+}
+
+type FooBarGetFooResults struct {
+	Foo FooFoo `lavish:"foo"`
+}
+
+type FooBarBazBaz struct {
+	X int32 `lavish:"x"`
+}
+
+type FooBarBazGetBazParams struct {
+}
+
+type FooBarBazGetBazResults struct {
+	Baz FooBarBazBaz `lavish:"baz"`
+}
+
+type FooBarBazGetBarParams struct {
+}
+
+type FooBarBazGetBarResults struct {
+	Bar FooBarBar `lavish:"bar"`
+}
+
+type FooBarBazGetFooParams struct {
+}
+
+type FooBarBazGetFooResults struct {
+	Foo FooFoo `lavish:"foo"`
+}
+
+type FooBarBazDeepestGetBazParams struct {
+}
+
+type FooBarBazDeepestGetBazResults struct {
+	Baz FooBarBazBaz `lavish:"baz"`
+}
+
+type FooBarBazDeepestGetBarParams struct {
+}
+
+type FooBarBazDeepestGetBarResults struct {
+	Bar FooBarBar `lavish:"bar"`
+}
+
+type FooBarBazDeepestGetFooParams struct {
+}
+
+type FooBarBazDeepestGetFooResults struct {
+	Foo FooFoo `lavish:"foo"`
+}
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) GetBaz(params GetBazParams) (GetBazResults, error) {
+	var results GetBazResults
+	err := c.caller.Call("get_baz", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) GetBar(params GetBarParams) (GetBarResults, error) {
+	var results GetBarResults
+	err := c.caller.Call("get_bar", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) GetFoo(params GetFooParams) (GetFooResults, error) {
+	var results GetFooResults
+	err := c.caller.Call("get_foo", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooGetBaz(params FooGetBazParams) (FooGetBazResults, error) {
+	var results FooGetBazResults
+	err := c.caller.Call("foo.get_baz", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooGetBar(params FooGetBarParams) (FooGetBarResults, error) {
+	var results FooGetBarResults
+	err := c.caller.Call("foo.get_bar", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooGetFoo(params FooGetFooParams) (FooGetFooResults, error) {
+	var results FooGetFooResults
+	err := c.caller.Call("foo.get_foo", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarGetBaz(params FooBarGetBazParams) (FooBarGetBazResults, error) {
+	var results FooBarGetBazResults
+	err := c.caller.Call("foo.bar.get_baz", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarGetBar(params FooBarGetBarParams) (FooBarGetBarResults, error) {
+	var results FooBarGetBarResults
+	err := c.caller.Call("foo.bar.get_bar", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarGetFoo(params FooBarGetFooParams) (FooBarGetFooResults, error) {
+	var results FooBarGetFooResults
+	err := c.caller.Call("foo.bar.get_foo", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarBazGetBaz(params FooBarBazGetBazParams) (FooBarBazGetBazResults, error) {
+	var results FooBarBazGetBazResults
+	err := c.caller.Call("foo.bar.baz.get_baz", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarBazGetBar(params FooBarBazGetBarParams) (FooBarBazGetBarResults, error) {
+	var results FooBarBazGetBarResults
+	err := c.caller.Call("foo.bar.baz.get_bar", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarBazGetFoo(params FooBarBazGetFooParams) (FooBarBazGetFooResults, error) {
+	var results FooBarBazGetFooResults
+	err := c.caller.Call("foo.bar.baz.get_foo", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarBazDeepestGetBaz(params FooBarBazDeepestGetBazParams) (FooBarBazDeepestGetBazResults, error) {
+	var results FooBarBazDeepestGetBazResults
+	err := c.caller.Call("foo.bar.baz.deepest.get_baz", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarBazDeepestGetBar(params FooBarBazDeepestGetBarParams) (FooBarBazDeepestGetBarResults, error) {
+	var results FooBarBazDeepestGetBarResults
+	err := c.caller.Call("foo.bar.baz.deepest.get_bar", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) FooBarBazDeepestGetFoo(params FooBarBazDeepestGetFooParams) (FooBarBazDeepestGetFooResults, error) {
+	var results FooBarBazDeepestGetFooResults
+	err := c.caller.Call("foo.bar.baz.deepest.get_foo", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnGetBaz registers the implementation of `get_baz`.
+func (h *ServerHandler) OnGetBaz(f func(client *ClientClient, params GetBazParams) (GetBazResults, error)) {
+	h.register("get_baz", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params GetBazParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnGetBar registers the implementation of `get_bar`.
+func (h *ServerHandler) OnGetBar(f func(client *ClientClient, params GetBarParams) (GetBarResults, error)) {
+	h.register("get_bar", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params GetBarParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnGetFoo registers the implementation of `get_foo`.
+func (h *ServerHandler) OnGetFoo(f func(client *ClientClient, params GetFooParams) (GetFooResults, error)) {
+	h.register("get_foo", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params GetFooParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooGetBaz registers the implementation of `foo.get_baz`.
+func (h *ServerHandler) OnFooGetBaz(f func(client *ClientClient, params FooGetBazParams) (FooGetBazResults, error)) {
+	h.register("foo.get_baz", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooGetBazParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooGetBar registers the implementation of `foo.get_bar`.
+func (h *ServerHandler) OnFooGetBar(f func(client *ClientClient, params FooGetBarParams) (FooGetBarResults, error)) {
+	h.register("foo.get_bar", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooGetBarParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooGetFoo registers the implementation of `foo.get_foo`.
+func (h *ServerHandler) OnFooGetFoo(f func(client *ClientClient, params FooGetFooParams) (FooGetFooResults, error)) {
+	h.register("foo.get_foo", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooGetFooParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarGetBaz registers the implementation of `foo.bar.get_baz`.
+func (h *ServerHandler) OnFooBarGetBaz(f func(client *ClientClient, params FooBarGetBazParams) (FooBarGetBazResults, error)) {
+	h.register("foo.bar.get_baz", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarGetBazParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarGetBar registers the implementation of `foo.bar.get_bar`.
+func (h *ServerHandler) OnFooBarGetBar(f func(client *ClientClient, params FooBarGetBarParams) (FooBarGetBarResults, error)) {
+	h.register("foo.bar.get_bar", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarGetBarParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarGetFoo registers the implementation of `foo.bar.get_foo`.
+func (h *ServerHandler) OnFooBarGetFoo(f func(client *ClientClient, params FooBarGetFooParams) (FooBarGetFooResults, error)) {
+	h.register("foo.bar.get_foo", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarGetFooParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarBazGetBaz registers the implementation of `foo.bar.baz.get_baz`.
+func (h *ServerHandler) OnFooBarBazGetBaz(f func(client *ClientClient, params FooBarBazGetBazParams) (FooBarBazGetBazResults, error)) {
+	h.register("foo.bar.baz.get_baz", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarBazGetBazParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarBazGetBar registers the implementation of `foo.bar.baz.get_bar`.
+func (h *ServerHandler) OnFooBarBazGetBar(f func(client *ClientClient, params FooBarBazGetBarParams) (FooBarBazGetBarResults, error)) {
+	h.register("foo.bar.baz.get_bar", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarBazGetBarParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarBazGetFoo registers the implementation of `foo.bar.baz.get_foo`.
+func (h *ServerHandler) OnFooBarBazGetFoo(f func(client *ClientClient, params FooBarBazGetFooParams) (FooBarBazGetFooResults, error)) {
+	h.register("foo.bar.baz.get_foo", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarBazGetFooParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarBazDeepestGetBaz registers the implementation of `foo.bar.baz.deepest.get_baz`.
+func (h *ServerHandler) OnFooBarBazDeepestGetBaz(f func(client *ClientClient, params FooBarBazDeepestGetBazParams) (FooBarBazDeepestGetBazResults, error)) {
+	h.register("foo.bar.baz.deepest.get_baz", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarBazDeepestGetBazParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarBazDeepestGetBar registers the implementation of `foo.bar.baz.deepest.get_bar`.
+func (h *ServerHandler) OnFooBarBazDeepestGetBar(f func(client *ClientClient, params FooBarBazDeepestGetBarParams) (FooBarBazDeepestGetBarResults, error)) {
+	h.register("foo.bar.baz.deepest.get_bar", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarBazDeepestGetBarParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnFooBarBazDeepestGetFoo registers the implementation of `foo.bar.baz.deepest.get_foo`.
+func (h *ServerHandler) OnFooBarBazDeepestGetFoo(f func(client *ClientClient, params FooBarBazDeepestGetFooParams) (FooBarBazDeepestGetFooResults, error)) {
+	h.register("foo.bar.baz.deepest.get_foo", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params FooBarBazDeepestGetFooParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
@@ -0,0 +1,138 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package down_structs
+
+import (
+	"fmt"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+type AnonSessionParams struct {
+}
+
+type AnonSessionResults struct {
+	Session SessionSession `lavish:"session"`
+}
+
+type SessionSession struct {
+	Username    string `lavish:"username"`
+	DisplayName string `lavish:"display_name"`
+}
+
+type SessionLoginParams struct {
+	Username string `lavish:"username"`
+	Password string `lavish:"password"`
+}
+
+type SessionLoginResults struct {
+	Session SessionSession `lavish:"session"`
+}
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) AnonSession(params AnonSessionParams) (AnonSessionResults, error) {
+	var results AnonSessionResults
+	err := c.caller.Call("anon_session", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) SessionLogin(params SessionLoginParams) (SessionLoginResults, error) {
+	var results SessionLoginResults
+	err := c.caller.Call("session.login", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnAnonSession registers the implementation of `anon_session`.
+func (h *ServerHandler) OnAnonSession(f func(client *ClientClient, params AnonSessionParams) (AnonSessionResults, error)) {
+	h.register("anon_session", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params AnonSessionParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnSessionLogin registers the implementation of `session.login`.
+func (h *ServerHandler) OnSessionLogin(f func(client *ClientClient, params SessionLoginParams) (SessionLoginResults, error)) {
+	h.register("session.login", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params SessionLoginParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
@@ -0,0 +1,227 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package layout
+
+import (
+	"fmt"
+	"time"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+// A user of the service.
+//
+// Second paragraph.
+type User struct {
+	// Their login
+	Name        string           `lavish:"name"`
+	DisplayName *string          `lavish:"display_name"`
+	Tags        []string         `lavish:"tags"`
+	Meta        map[string]int64 `lavish:"meta"`
+	Avatar      []byte           `lavish:"avatar"`
+	JoinedAt    time.Time        `lavish:"joined_at"`
+}
+
+// Settings of a user. Doc comments keep going past blank lines,
+// and lose their indentation:
+//
+// settings.emails = true
+// (still about Settings)
+type Settings struct {
+	// Whether to send emails
+	// (still about emails)
+	Emails bool `lavish:"emails"`
+}
+
+type PingParams struct {
+}
+
+type PingResults struct {
+}
+
+type AskParams struct {
+	Question string `lavish:"question"`
+}
+
+type AskResults struct {
+	Answer string `lavish:"answer"`
+	Mood   Mood   `lavish:"mood"`
+}
+
+type SessionToken struct {
+	Value string `lavish:"value"`
+}
+
+type SessionLoginParams struct {
+	Name string `lavish:"name"`
+}
+
+type SessionLoginResults struct {
+	Token SessionToken `lavish:"token"`
+	User  User         `lavish:"user"`
+}
+
+type SessionNotifyLogoutParams struct {
+	Reason string `lavish:"reason"`
+}
+
+type SessionNotifyLogoutResults struct {
+}
+
+type Mood uint32
+
+const (
+	MoodHappy Mood = 0
+	// comment
+	MoodSad          Mood = 1
+	MoodVeryConfused Mood = 2
+)
+
+type Code int64
+
+const (
+	CodeOk       Code = 200
+	CodeNotFound Code = 404
+)
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) Ping(params PingParams) (PingResults, error) {
+	var results PingResults
+	err := c.caller.Call("ping", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) SessionLogin(params SessionLoginParams) (SessionLoginResults, error) {
+	var results SessionLoginResults
+	err := c.caller.Call("session.login", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnPing registers the implementation of `ping`.
+func (h *ServerHandler) OnPing(f func(client *ClientClient, params PingParams) (PingResults, error)) {
+	h.register("ping", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params PingParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnSessionLogin registers the implementation of `session.login`.
+func (h *ServerHandler) OnSessionLogin(f func(client *ClientClient, params SessionLoginParams) (SessionLoginResults, error)) {
+	h.register("session.login", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params SessionLoginParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+func (c *ClientClient) Ask(params AskParams) (AskResults, error) {
+	var results AskResults
+	err := c.caller.Call("ask", &params, &results)
+	return results, err
+}
+
+func (c *ClientClient) SessionNotifyLogout(params SessionNotifyLogoutParams) (SessionNotifyLogoutResults, error) {
+	var results SessionNotifyLogoutResults
+	err := c.caller.Call("session.notify_logout", &params, &results)
+	return results, err
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnAsk registers the implementation of `ask`.
+func (h *ClientHandler) OnAsk(f func(client *ServerClient, params AskParams) (AskResults, error)) {
+	h.register("ask", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params AskParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewServerClient(caller), params)
+	})
+}
+
+// OnSessionNotifyLogout registers the implementation of `session.notify_logout`.
+func (h *ClientHandler) OnSessionNotifyLogout(f func(client *ServerClient, params SessionNotifyLogoutParams) (SessionNotifyLogoutResults, error)) {
+	h.register("session.notify_logout", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params SessionNotifyLogoutParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewServerClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
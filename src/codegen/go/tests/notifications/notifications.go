@@ -0,0 +1,179 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package notifications
+
+import (
+	"fmt"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+type LogMessage struct {
+	Msg   string `lavish:"msg"`
+	Level int32  `lavish:"level"`
+}
+
+type LogParams struct {
+	Message LogMessage `lavish:"message"`
+}
+
+type LogResults struct {
+}
+
+type ProgressDownloadParams struct {
+	Url string `lavish:"url"`
+}
+
+type ProgressDownloadResults struct {
+	Size uint64 `lavish:"size"`
+}
+
+type ProgressDownloadUpdateParams struct {
+	Downloaded uint64 `lavish:"downloaded"`
+	Total      uint64 `lavish:"total"`
+}
+
+type ProgressDownloadUpdateResults struct {
+}
+
+type ProgressDoneParams struct {
+}
+
+type ProgressDoneResults struct {
+}
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) Log(params LogParams) error {
+	return c.caller.Notify("log", &params)
+}
+
+func (c *ServerClient) ProgressDownload(params ProgressDownloadParams) (ProgressDownloadResults, error) {
+	var results ProgressDownloadResults
+	err := c.caller.Call("progress.download", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnLog registers the implementation of `log`.
+func (h *ServerHandler) OnLog(f func(client *ClientClient, params LogParams) error) {
+	h.register("log", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params LogParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return nil, f(NewClientClient(caller), params)
+	})
+}
+
+// OnProgressDownload registers the implementation of `progress.download`.
+func (h *ServerHandler) OnProgressDownload(f func(client *ClientClient, params ProgressDownloadParams) (ProgressDownloadResults, error)) {
+	h.register("progress.download", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params ProgressDownloadParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+func (c *ClientClient) ProgressDownloadUpdate(params ProgressDownloadUpdateParams) error {
+	return c.caller.Notify("progress.download.update", &params)
+}
+
+func (c *ClientClient) ProgressDone(params ProgressDoneParams) error {
+	return c.caller.Notify("progress.done", &params)
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnProgressDownloadUpdate registers the implementation of `progress.download.update`.
+func (h *ClientHandler) OnProgressDownloadUpdate(f func(client *ServerClient, params ProgressDownloadUpdateParams) error) {
+	h.register("progress.download.update", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params ProgressDownloadUpdateParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return nil, f(NewServerClient(caller), params)
+	})
+}
+
+// OnProgressDone registers the implementation of `progress.done`.
+func (h *ClientHandler) OnProgressDone(f func(client *ServerClient, params ProgressDoneParams) error) {
+	h.register("progress.done", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params ProgressDoneParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return nil, f(NewServerClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
@@ -0,0 +1,133 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+package timestamp
+
+import (
+	"fmt"
+	"time"
+)
+
+// Caller is implemented by the lavish runtime, and lets
+// clients send requests and notifications to a peer.
+type Caller interface {
+	Call(method string, params interface{}, results interface{}) error
+	Notify(method string, params interface{}) error
+}
+
+// Decoder is provided by the lavish runtime to fill in
+// the params of an incoming request or notification.
+type Decoder func(params interface{}) error
+
+type slot func(caller Caller, decode Decoder) (interface{}, error)
+
+type GetTimestampParams struct {
+}
+
+type GetTimestampResults struct {
+	Value time.Time `lavish:"value"`
+}
+
+type SubtractWeekParams struct {
+	Value time.Time `lavish:"value"`
+}
+
+type SubtractWeekResults struct {
+	Value time.Time `lavish:"value"`
+}
+
+// ServerClient calls functions implemented by the server.
+type ServerClient struct {
+	caller Caller
+}
+
+func NewServerClient(caller Caller) *ServerClient {
+	return &ServerClient{caller: caller}
+}
+
+func (c *ServerClient) GetTimestamp(params GetTimestampParams) (GetTimestampResults, error) {
+	var results GetTimestampResults
+	err := c.caller.Call("get_timestamp", &params, &results)
+	return results, err
+}
+
+func (c *ServerClient) SubtractWeek(params SubtractWeekParams) (SubtractWeekResults, error) {
+	var results SubtractWeekResults
+	err := c.caller.Call("subtract_week", &params, &results)
+	return results, err
+}
+
+// ServerHandler routes incoming calls to implementations of server functions.
+// Its zero value is ready to use.
+type ServerHandler struct {
+	slots map[string]slot
+}
+
+func (h *ServerHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// OnGetTimestamp registers the implementation of `get_timestamp`.
+func (h *ServerHandler) OnGetTimestamp(f func(client *ClientClient, params GetTimestampParams) (GetTimestampResults, error)) {
+	h.register("get_timestamp", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params GetTimestampParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// OnSubtractWeek registers the implementation of `subtract_week`.
+func (h *ServerHandler) OnSubtractWeek(f func(client *ClientClient, params SubtractWeekParams) (SubtractWeekResults, error)) {
+	h.register("subtract_week", func(caller Caller, decode Decoder) (interface{}, error) {
+		var params SubtractWeekParams
+		if err := decode(&params); err != nil {
+			return nil, err
+		}
+		return f(NewClientClient(caller), params)
+	})
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ServerHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
+
+// ClientClient calls functions implemented by the client.
+type ClientClient struct {
+	caller Caller
+}
+
+func NewClientClient(caller Caller) *ClientClient {
+	return &ClientClient{caller: caller}
+}
+
+// ClientHandler routes incoming calls to implementations of client functions.
+// Its zero value is ready to use.
+type ClientHandler struct {
+	slots map[string]slot
+}
+
+func (h *ClientHandler) register(method string, s slot) {
+	if h.slots == nil {
+		h.slots = make(map[string]slot)
+	}
+	h.slots[method] = s
+}
+
+// Handle is called by the lavish runtime for every incoming call.
+func (h *ClientHandler) Handle(caller Caller, method string, decode Decoder) (interface{}, error) {
+	s, ok := h.slots[method]
+	if !ok {
+		return nil, fmt.Errorf("lavish: method unimplemented: %s", method)
+	}
+	return s(caller, decode)
+}
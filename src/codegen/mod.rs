@@ -1,11 +1,16 @@
 mod output;
 pub use output::*;
 
+mod go;
 mod rust;
+mod typescript;
 use super::ast;
 
 mod prelude;
 
+mod reserved;
+pub use reserved::*;
+
 use std::fmt;
 
 #[derive(Debug)]
@@ -30,7 +35,7 @@ impl Into<std::fmt::Error> for Error {
 pub type Result = std::result::Result<(), Error>;
 
 pub trait Generator {
-    fn emit_workspace(&self, workspace: &ast::Workspace) -> Result;
+    fn emit_workspace(&self, workspace: &ast::Workspace, files: &Files) -> Result;
 }
 
 impl From<std::io::Error> for Error {
@@ -52,15 +57,63 @@ impl fmt::Display for Error {
         write!(f, "codegen error: {}", self.message)
     }
 }
+
+impl std::error::Error for Error {}
 use crate::Opts;
 
-pub fn codegen(opts: &Opts, workspace: &ast::Workspace) -> Result {
-    let generator = match &workspace.rules.target {
-        ast::Target::Rust(target) => rust::Generator::new(&opts, target.clone()),
-        _ => panic!("Unimplemented target: {:#?}", workspace.rules.target),
+/// Returns the target to generate code for, or `None` if there's
+/// nothing to build.
+pub fn target(workspace: &ast::Workspace) -> std::result::Result<Option<&ast::Target>, Error> {
+    match &workspace.rules.target {
+        Some(target) => Ok(Some(target)),
+        // rules files that only export schemas have nothing to build
+        None if workspace.rules.builds.is_empty() => Ok(None),
+        None => Err("lavish-rules has build directives, but no target".into()),
+    }
+}
+
+/// Generates code for every member of the workspace. In check mode,
+/// nothing is written, and files that are out of date are an error.
+pub fn codegen(opts: &Opts, workspace: &ast::Workspace, mode: Mode) -> Result {
+    let target = match target(workspace)? {
+        Some(target) => target,
+        None => return Ok(()),
     };
 
-    generator.emit_workspace(&workspace)?;
+    let generator: Box<dyn Generator> = match target {
+        ast::Target::Rust(target) => Box::new(rust::Generator::new(opts, target.clone())),
+        ast::Target::Go(target) => Box::new(go::Generator::new(opts, target.clone())),
+        ast::Target::TypeScript(target) => {
+            Box::new(typescript::Generator::new(opts, target.clone()))
+        }
+    };
+
+    let files = Files::new(mode);
+    generator.emit_workspace(&workspace, &files)?;
+
+    let stale = files.stale();
+    if !stale.is_empty() {
+        return Err(Error {
+            message: format!(
+                "{} generated files are out of date: {}. Run `lavish build` to regenerate them",
+                stale.len(),
+                stale
+                    .iter()
+                    .map(|p| p.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        });
+    }
 
     Ok(())
 }
+
+/// Returns how the target language deals with reserved words
+pub fn reserved_words(target: &ast::Target) -> Box<dyn ReservedWords> {
+    match target {
+        ast::Target::Rust(_) => Box::new(rust::Keywords),
+        ast::Target::Go(_) => Box::new(go::Keywords),
+        ast::Target::TypeScript(_) => Box::new(typescript::Keywords),
+    }
+}
@@ -1,35 +1,11 @@
 use crate::ast;
+use similar::TextDiff;
+use std::cell::RefCell;
 use std::fmt::{self, Display, Write};
-use std::io::{self, BufWriter};
+use std::io;
+use std::path::{Path, PathBuf};
 
-const INDENT_WIDTH: usize = 4;
-
-pub struct Writer<W> {
-    writer: W,
-}
-
-impl<W> Writer<W>
-where
-    W: std::io::Write,
-{
-    pub fn new(writer: W) -> Self {
-        Self { writer }
-    }
-
-    #[allow(unused)]
-    pub fn into_inner(self) -> W {
-        self.writer
-    }
-}
-
-impl<W> fmt::Write for Writer<W>
-where
-    W: std::io::Write,
-{
-    fn write_str(&mut self, s: &str) -> fmt::Result {
-        write!(self.writer, "{}", s).map_err(|_| fmt::Error {})
-    }
-}
+pub const INDENT_WIDTH: usize = 4;
 
 pub struct Scope<'a> {
     writer: &'a mut fmt::Write,
@@ -52,13 +28,6 @@ impl<'a> Scope<'a> {
         }
     }
 
-    pub fn writer<W>(w: W) -> Writer<BufWriter<W>>
-    where
-        W: io::Write,
-    {
-        Writer::new(BufWriter::new(w))
-    }
-
     pub fn lf(&mut self) {
         writeln!(self).unwrap();
     }
@@ -79,9 +48,13 @@ impl<'a> Scope<'a> {
     }
 
     pub fn comment(&mut self, comment: &Option<ast::Comment>) {
+        self.comment_with("///", comment)
+    }
+
+    pub fn comment_with(&mut self, prefix: &str, comment: &Option<ast::Comment>) {
         if let Some(comment) = comment.as_ref() {
-            for line in &comment.lines {
-                self.line(format!("/// {}", line))
+            for line in comment.doc_lines() {
+                self.line(format!("{} {}", prefix, line))
             }
         }
     }
@@ -268,18 +241,78 @@ impl<'a> fmt::Write for Scope<'a> {
     }
 }
 
+/// What happens to generated files
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Write them to disk
+    Write,
+    /// Compare them with what's on disk, without writing anything
+    Check,
+}
+
+/// Where generators, and the formatter, put the files they render.
+pub struct Files {
+    mode: Mode,
+    /// Files that don't match what's on disk, in check mode
+    stale: RefCell<Vec<PathBuf>>,
+}
+
+impl Files {
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            stale: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Writes a generated file, or in check mode, prints how it
+    /// differs from what's on disk as a unified diff, on stderr so
+    /// that it doesn't get mixed with diagnostics in JSON mode.
+    pub fn save(&self, path: &Path, contents: &str) -> super::Result {
+        match self.mode {
+            Mode::Write => {
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::write(path, contents)?;
+            }
+            Mode::Check => {
+                let name = path.to_string_lossy();
+                let (old, old_name) = match std::fs::read_to_string(path) {
+                    Ok(old) => (old, name.clone()),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                        (String::new(), "/dev/null".into())
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                if old != contents {
+                    eprint!(
+                        "{}",
+                        TextDiff::from_lines(old.as_str(), contents)
+                            .unified_diff()
+                            .header(&old_name, &name)
+                    );
+                    self.stale.borrow_mut().push(path.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the files that are out of date, in check mode
+    pub fn stale(&self) -> Vec<PathBuf> {
+        self.stale.borrow().clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Scope;
-    use netbuf::Buf;
     use std::fmt::Write;
 
     #[test]
     fn test_scope() -> Result<(), Box<dyn std::error::Error + 'static>> {
-        let mut buf = Buf::new();
+        let mut buf = String::new();
         {
-            let mut w = super::Writer::new(&mut buf);
-            let mut s = Scope::new(&mut w);
+            let mut s = Scope::new(&mut buf);
             writeln!(s, "fn sample() {{")?;
             {
                 let mut s = s.scope();
@@ -297,9 +330,8 @@ mod tests {
             writeln!(s, "}}")?;
         }
 
-        let s = std::str::from_utf8(buf.as_ref()).unwrap();
         assert_eq!(
-            s,
+            buf,
             r#"fn sample() {
     let a = {
         let tmp = 7;
@@ -312,4 +344,28 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn files_check() -> Result<(), Box<dyn std::error::Error + 'static>> {
+        use super::{Files, Mode};
+
+        let dir = std::env::temp_dir().join(format!("lavish-files-check-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("mod.rs");
+        std::fs::write(&path, "a\nb\n")?;
+
+        let files = Files::new(Mode::Check);
+        files.save(&path, "a\nb\n")?;
+        assert!(files.stale().is_empty());
+        files.save(&path, "a\nc\n")?;
+        files.save(&dir.join("missing.rs"), "a\n")?;
+        assert_eq!(files.stale(), vec![path.clone(), dir.join("missing.rs")]);
+
+        // nothing gets written in check mode
+        assert_eq!(std::fs::read_to_string(&path)?, "a\nb\n");
+        assert!(!dir.join("missing.rs").exists());
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
 }
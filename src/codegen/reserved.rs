@@ -0,0 +1,43 @@
+use std::borrow::Cow;
+
+/// Where a schema name ends up in generated code: languages don't
+/// reserve their keywords everywhere.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Usage {
+    /// Builds and imports, which become modules or packages
+    Module,
+    /// Namespaces, types and functions
+    Declaration,
+    /// Fields and variants
+    Member,
+}
+
+/// How a target language deals with schema names that clash with
+/// its keywords. Every generator has one, so that the workspace can
+/// reject names it can't emit before anything gets generated.
+pub trait ReservedWords {
+    /// Name of the target language, for diagnostics
+    fn language(&self) -> &'static str;
+
+    /// Returns true if `name` can't be emitted at all as `usage`,
+    /// escaped or not
+    fn is_unusable(&self, _usage: Usage, _name: &str) -> bool {
+        false
+    }
+
+    /// Returns `name`, escaped if the language reserves it
+    fn escape<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        name.into()
+    }
+
+    /// For languages that flatten namespaces, returns the name of the
+    /// declaration at `path`, so that collisions can be rejected.
+    fn flat_name(&self, _path: &[&str]) -> Option<String> {
+        None
+    }
+
+    /// Names the generated code declares on its own, next to flattened ones
+    fn builtin_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(non_snake_case)]
 
 use crate::ast;
+use crate::codegen::rust::keywords::ident;
 use heck::CamelCase;
 
 pub trait RustStack {
@@ -12,6 +13,7 @@ pub trait RustStack {
     fn Implementable(&self) -> String;
     fn ProtocolMapping(&self) -> String;
     fn Slottable(&self) -> String;
+    fn Notifiable(&self) -> String;
     fn SideClient(&self, side: ast::Side) -> String;
     fn Params(&self) -> String;
     fn NotificationParams(&self) -> String;
@@ -52,6 +54,10 @@ impl<'a> RustStack for ast::Stack<'a> {
         format!("{}::Slottable", self.protocol())
     }
 
+    fn Notifiable(&self) -> String {
+        format!("{}::Notifiable", self.protocol())
+    }
+
     fn SideClient(&self, side: ast::Side) -> String {
         format!("super::{}::Client", side)
     }
@@ -116,7 +122,12 @@ impl<'a> RustFn for ast::Anchored<'a, &ast::FunctionDecl> {
         format!(
             "{schema}::{path}",
             schema = stack.schema(),
-            path = self.names().join("::")
+            path = self
+                .names()
+                .iter()
+                .map(|x| ident(x))
+                .collect::<Vec<_>>()
+                .join("::")
         )
     }
 
@@ -161,3 +172,17 @@ impl<'a> RustEnum for ast::Anchored<'a, &ast::EnumDecl> {
             .join("_")
     }
 }
+
+pub trait RustUnion {
+    fn variant(&self) -> String;
+}
+
+impl<'a> RustUnion for ast::Anchored<'a, &ast::UnionDecl> {
+    fn variant(&self) -> String {
+        self.names()
+            .iter()
+            .map(|x| x.to_camel_case())
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+}
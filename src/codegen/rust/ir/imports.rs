@@ -0,0 +1,94 @@
+use super::symbols::{Enum, Struct, Union};
+use crate::codegen::rust::prelude::*;
+
+/// Brings the types of imported schemas into scope, under their
+/// import alias, and lets them be sent over this member's protocol.
+///
+/// The types themselves are generated once, by the member the
+/// import resolves to, so that builds sharing an import share types.
+pub struct Imports<'a> {
+    pub workspace: &'a ast::Workspace,
+    pub stack: &'a ast::Stack<'a>,
+    pub imports: Vec<&'a ast::Import>,
+}
+
+impl<'a> Display for Imports<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            for import in &self.imports {
+                let schema = match import.schema.as_ref() {
+                    Some(schema) => schema,
+                    None => continue,
+                };
+                let member = match self.workspace.member_for(import) {
+                    Some(member) => member.name.as_str(),
+                    None => import.name.text(),
+                };
+
+                let stack = self.stack.push(*import);
+                let graph = ast::TypeGraph::new(schema);
+                write!(s, "pub mod {}", ident(import.name.text())).unwrap();
+                s.in_block(|s| {
+                    s.write(ImportedSymbols {
+                        body: stack.anchor(&schema.body),
+                        graph: &graph,
+                        path: vec![member, "schema"],
+                    });
+                });
+            }
+        })
+    }
+}
+
+struct ImportedSymbols<'a> {
+    body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+    graph: &'a ast::TypeGraph<'a>,
+    /// Path of the namespace, starting from the workspace
+    path: Vec<&'a str>,
+}
+
+impl<'a> Display for ImportedSymbols<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let body = &self.body;
+            let stack = &body.stack;
+
+            writeln!(
+                s,
+                "pub use {root}super::{path}::*;",
+                root = stack.root(),
+                path = self
+                    .path
+                    .iter()
+                    .map(|x| ident(x))
+                    .collect::<Vec<_>>()
+                    .join("::")
+            )
+            .unwrap();
+
+            for node in &body.structs {
+                s.write(Struct::imported(stack.anchor(node), self.graph));
+            }
+            for node in &body.enums {
+                s.write(Enum::imported(stack.anchor(node)));
+            }
+            for node in &body.unions {
+                s.write(Union::imported(stack.anchor(node), self.graph));
+            }
+
+            for ns in &body.inner.namespaces {
+                let mut path = self.path.clone();
+                path.push(ns.name.text());
+
+                write!(s, "pub mod {}", ident(ns.name.text())).unwrap();
+                s.in_block(|s| {
+                    s.write(ImportedSymbols {
+                        body: stack.push(ns).anchor(&ns.body),
+                        graph: self.graph,
+                        path: path.clone(),
+                    });
+                });
+            }
+        })
+    }
+}
@@ -377,6 +377,21 @@ impl Derive {
         self.items.insert("Copy".into());
         self
     }
+
+    pub fn partial_eq(mut self) -> Self {
+        self.items.insert("PartialEq".into());
+        self
+    }
+
+    pub fn eq(mut self) -> Self {
+        self.items.insert("Eq".into());
+        self
+    }
+
+    pub fn hash(mut self) -> Self {
+        self.items.insert("Hash".into());
+        self
+    }
 }
 
 impl Display for Derive {
@@ -1,6 +1,7 @@
 pub(crate) mod ast_ext;
 pub(crate) mod client;
 pub(crate) mod common;
+pub(crate) mod imports;
 pub(crate) mod lang;
 pub(crate) mod pair;
 pub(crate) mod protocol;
@@ -8,4 +9,4 @@ pub(crate) mod router;
 pub(crate) mod symbols;
 pub(crate) mod types;
 
-pub(crate) use {pair::*, protocol::*, symbols::*};
+pub(crate) use {imports::*, pair::*, protocol::*, symbols::*};
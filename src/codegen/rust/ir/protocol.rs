@@ -2,6 +2,9 @@ use crate::codegen::rust::prelude::*;
 
 pub struct Protocol<'a> {
     pub body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+    /// Every schema imported directly or indirectly: their types
+    /// are sent over this protocol too.
+    pub imports: Vec<&'a ast::Import>,
 }
 
 impl<'a> Display for Protocol<'a> {
@@ -12,12 +15,44 @@ impl<'a> Display for Protocol<'a> {
                 self.write_atoms(s);
                 self.write_specializations(s);
                 self.write_translation_tables(s);
+                self.write_descriptor(s);
+                self.write_negotiation(s);
             });
         })
     }
 }
 
 impl<'a> Protocol<'a> {
+    fn for_each_struct(&self, cb: &mut dyn FnMut(ast::Anchored<&ast::StructDecl>)) {
+        self.body.for_each_struct_of_schema(cb);
+        for import in &self.imports {
+            if let Some(schema) = import.schema.as_ref() {
+                let stack = self.body.stack.push(*import);
+                stack.anchor(&schema.body).for_each_struct_of_namespaces(cb);
+            }
+        }
+    }
+
+    fn for_each_enum(&self, cb: &mut dyn FnMut(ast::Anchored<&ast::EnumDecl>)) {
+        self.body.for_each_enum_of_schema(cb);
+        for import in &self.imports {
+            if let Some(schema) = import.schema.as_ref() {
+                let stack = self.body.stack.push(*import);
+                stack.anchor(&schema.body).for_each_enum_of_namespaces(cb);
+            }
+        }
+    }
+
+    fn for_each_union(&self, cb: &mut dyn FnMut(ast::Anchored<&ast::UnionDecl>)) {
+        self.body.for_each_union_of_schema(cb);
+        for import in &self.imports {
+            if let Some(schema) = import.schema.as_ref() {
+                let stack = self.body.stack.push(*import);
+                stack.anchor(&schema.body).for_each_union_of_namespaces(cb);
+            }
+        }
+    }
+
     fn write_translation_tables(&self, s: &mut Scope) {
         writeln!(
             s,
@@ -25,6 +60,7 @@ impl<'a> Protocol<'a> {
             facts = Mods::facts()
         )
         .unwrap();
+        writeln!(s, "use {HashMap};", HashMap = Structs::HashMap()).unwrap();
 
         s.write(derive().debug());
         s.write("pub struct ProtocolMapping").in_block(|s| {
@@ -34,14 +70,19 @@ impl<'a> Protocol<'a> {
             }
 
             s.line("// structs");
-            self.body.for_each_struct_of_schema(&mut |st| {
+            self.for_each_struct(&mut |st| {
                 writeln!(s, "pub {variant}: TypeMapping,", variant = st.variant()).unwrap();
             });
 
             s.line("// enums");
-            self.body.for_each_enum_of_schema(&mut |en| {
+            self.for_each_enum(&mut |en| {
                 writeln!(s, "pub {variant}: TypeMapping,", variant = en.variant()).unwrap();
             });
+
+            s.line("// unions");
+            self.for_each_union(&mut |un| {
+                writeln!(s, "pub {variant}: TypeMapping,", variant = un.variant()).unwrap();
+            });
         });
         s.lf();
 
@@ -71,7 +112,7 @@ impl<'a> Protocol<'a> {
                         }
 
                         s.line("// structs");
-                        self.body.for_each_struct_of_schema(&mut |st| {
+                        self.for_each_struct(&mut |st| {
                             let mut values: Vec<String> = Vec::new();
                             for i in 0..st.fields.len() {
                                 values.push(format!("{}", i));
@@ -87,7 +128,7 @@ impl<'a> Protocol<'a> {
                         });
 
                         s.line("// enums");
-                        self.body.for_each_enum_of_schema(&mut |en| {
+                        self.for_each_enum(&mut |en| {
                             let mut values: Vec<String> = Vec::new();
                             for i in 0..en.variants.len() {
                                 values.push(format!("{}", i));
@@ -100,6 +141,22 @@ impl<'a> Protocol<'a> {
                                 values = values.join(", "),
                             )
                             .unwrap();
+                        });
+
+                        s.line("// unions");
+                        self.for_each_union(&mut |un| {
+                            let mut values: Vec<String> = Vec::new();
+                            for i in 0..un.members.len() {
+                                values.push(format!("{}", i));
+                            }
+
+                            writeln!(
+                                s,
+                                "{variant}: TypeMapping::Mapped(OffsetList(vec![{values}])),",
+                                variant = un.variant(),
+                                values = values.join(", "),
+                            )
+                            .unwrap();
                         })
                     });
                 })
@@ -114,22 +171,315 @@ impl<'a> Protocol<'a> {
         .unwrap();
     }
 
+    /// Returns the dotted path of each function of the given kind, in
+    /// the order of their variants in atoms.
+    fn methods(&self, kind: ast::Kind) -> Vec<String> {
+        let mut res = Vec::new();
+        self.body.for_each_fun_of_schema(&mut |f| {
+            if f.kind == kind {
+                res.push(f.method());
+            }
+        });
+        res
+    }
+
+    fn write_descriptor(&self, s: &mut Scope) {
+        let mut structs: Vec<(String, Vec<String>)> = Vec::new();
+        self.for_each_struct(&mut |st| {
+            let fields = st.fields.iter().map(|f| f.name.text().to_string());
+            structs.push((st.names().join("."), fields.collect()));
+        });
+        let mut enums: Vec<(String, Vec<String>)> = Vec::new();
+        self.for_each_enum(&mut |en| {
+            let variants = en.variants.iter().map(|v| v.name.text().to_string());
+            enums.push((en.names().join("."), variants.collect()));
+        });
+        let mut unions: Vec<(String, Vec<String>)> = Vec::new();
+        self.for_each_union(&mut |un| {
+            let members = un.members.iter().map(|m| m.variant().to_string());
+            unions.push((un.names().join("."), members.collect()));
+        });
+
+        s.line("/// Names of the functions, fields, enum variants and union members");
+        s.line("/// of a protocol, which peers exchange to build a `ProtocolMapping`.");
+        s.write(derive().clone().debug());
+        s.write("pub struct Descriptor").in_block(|s| {
+            s.line("pub requests: Vec<String>,");
+            s.line("pub notifications: Vec<String>,");
+            for table in &["structs", "enums", "unions"] {
+                writeln!(
+                    s,
+                    "pub {table}: HashMap<String, Vec<String>>,",
+                    table = table
+                )
+                .unwrap();
+            }
+        });
+        s.lf();
+
+        // descriptors are exchanged before there's a mapping, so they
+        // can be written with any of them.
+        writeln!(
+            s,
+            "impl<M> {Factual}<M> for Descriptor",
+            Factual = Traits::Factual()
+        )
+        .unwrap();
+        s.line("where");
+        s.in_scope(|s| {
+            writeln!(s, "M: {facts}::Mapping,", facts = Mods::facts()).unwrap();
+        });
+        s.in_block(|s| {
+            _fn("read")
+                .self_bound("Sized")
+                .type_param_bound("R", Traits::Read())
+                .param(format!(
+                    "rd: &mut {Reader}<R>",
+                    Reader = Structs::FactsReader()
+                ))
+                .returns(format!(
+                    "Result<Self, {Error}>",
+                    Error = Structs::FactsError()
+                ))
+                .body(|s| {
+                    writeln!(
+                        s,
+                        "rd.expect_array_len({len})?;",
+                        len = DESCRIPTOR_TABLES.len()
+                    )
+                    .unwrap();
+                    s.write("Ok(Self").in_terminated_block(")", |s| {
+                        for table in DESCRIPTOR_TABLES {
+                            writeln!(
+                                s,
+                                "{table}: <Self as {Factual}<M>>::subread(rd)?,",
+                                table = table,
+                                Factual = Traits::Factual()
+                            )
+                            .unwrap();
+                        }
+                    });
+                })
+                .write_to(s);
+            s.lf();
+            _fn("write")
+                .type_param_bound("W", Traits::Write())
+                .self_param("&self")
+                .param("mapping: &M")
+                .param("wr: &mut W")
+                .returns(format!(
+                    "Result<(), {Error}>",
+                    Error = Structs::FactsError()
+                ))
+                .body(|s| {
+                    writeln!(
+                        s,
+                        "{lavish}::rmp::encode::write_array_len(wr, {len})?;",
+                        lavish = Mods::lavish(),
+                        len = DESCRIPTOR_TABLES.len()
+                    )
+                    .unwrap();
+                    for table in DESCRIPTOR_TABLES {
+                        writeln!(s, "self.{table}.write(mapping, wr)?;", table = table).unwrap();
+                    }
+                    s.line("Ok(())");
+                })
+                .write_to(s);
+        });
+        s.lf();
+
+        s.line("/// Describes this protocol, to be sent to peers at handshake");
+        _fn("descriptor")
+            .kw_pub()
+            .returns("Descriptor")
+            .body(|s| {
+                s.write("let mut d = Descriptor")
+                    .in_terminated_block(";", |s| {
+                        writeln!(
+                            s,
+                            "requests: names(&[{names}]),",
+                            names = quoted_list(&self.methods(ast::Kind::Request))
+                        )
+                        .unwrap();
+                        writeln!(
+                            s,
+                            "notifications: names(&[{names}]),",
+                            names = quoted_list(&self.methods(ast::Kind::Notification))
+                        )
+                        .unwrap();
+                        for table in &["structs", "enums", "unions"] {
+                            writeln!(s, "{table}: HashMap::new(),", table = table).unwrap();
+                        }
+                    });
+                for (table, entries) in &[
+                    ("structs", &structs),
+                    ("enums", &enums),
+                    ("unions", &unions),
+                ] {
+                    writeln!(s, "// {}", table).unwrap();
+                    for (name, names) in entries.iter() {
+                        writeln!(
+                            s,
+                            "d.{table}.insert({name:?}.into(), names(&[{names}]));",
+                            table = table,
+                            name = name,
+                            names = quoted_list(names)
+                        )
+                        .unwrap();
+                    }
+                }
+                s.line("d");
+            })
+            .write_to(s);
+        s.lf();
+
+        _fn("names")
+            .param("names: &[&str]")
+            .returns("Vec<String>")
+            .body(|s| {
+                s.line("names.iter().map(|&name| name.into()).collect()");
+            })
+            .write_to(s);
+        s.lf();
+    }
+
+    fn write_negotiation(&self, s: &mut Scope) {
+        s.write("impl ProtocolMapping").in_block(|s| {
+            s.line("/// Builds the mapping used to send values to a peer whose schema is");
+            s.line("/// described by `remote`, matching functions, fields, enum variants");
+            s.line("/// and union members by name, so they can be added or reordered.");
+            _fn("negotiate")
+                .kw_pub()
+                .param("local: &Descriptor")
+                .param("remote: &Descriptor")
+                .returns("Self")
+                .body(|s| {
+                    s.write("Self").in_block(|s| {
+                        s.line("// builtins");
+                        for builtin in get_builtins() {
+                            let table = match builtin.0 {
+                                ast::Kind::Request => "requests",
+                                ast::Kind::Notification => "notifications",
+                            };
+                            writeln!(
+                                s,
+                                "__{variant}: TypeMapping::Mapped(offsets(&local.{table}, &remote.{table})),",
+                                variant = builtin.1,
+                                table = table,
+                            )
+                            .unwrap();
+                        }
+
+                        s.line("// structs");
+                        self.for_each_struct(&mut |st| {
+                            writeln!(
+                                s,
+                                "{variant}: struct_mapping(&local.structs, &remote.structs, {name:?}),",
+                                variant = st.variant(),
+                                name = st.names().join("."),
+                            )
+                            .unwrap();
+                        });
+
+                        s.line("// enums");
+                        self.for_each_enum(&mut |en| {
+                            writeln!(
+                                s,
+                                "{variant}: index_mapping(&local.enums, &remote.enums, \"enum\", {name:?}),",
+                                variant = en.variant(),
+                                name = en.names().join("."),
+                            )
+                            .unwrap();
+                        });
+
+                        s.line("// unions");
+                        self.for_each_union(&mut |un| {
+                            writeln!(
+                                s,
+                                "{variant}: index_mapping(&local.unions, &remote.unions, \"union\", {name:?}),",
+                                variant = un.variant(),
+                                name = un.names().join("."),
+                            )
+                            .unwrap();
+                        });
+                    });
+                })
+                .write_to(s);
+        });
+        s.lf();
+
+        s.line("/// Returns the position of each of the `from` names among the `to` ones,");
+        s.line("/// or -1 for those missing.");
+        _fn("offsets")
+            .param("from: &[String]")
+            .param("to: &[String]")
+            .returns("OffsetList")
+            .body(|s| {
+                s.line("OffsetList(");
+                s.in_scope(|s| {
+                    s.line("from.iter()");
+                    s.in_scope(|s| {
+                        s.line(".map(|name| to.iter().position(|n| n == name).map(|i| i as i32).unwrap_or(-1))");
+                        s.line(".collect(),");
+                    });
+                });
+                s.line(")");
+            })
+            .write_to(s);
+        s.lf();
+
+        s.line("/// Fields are sent in the order the peer reads them: for each of the");
+        s.line("/// peer's fields, the mapping gives which of ours to write, if any.");
+        _fn("struct_mapping")
+            .param("local: &HashMap<String, Vec<String>>")
+            .param("remote: &HashMap<String, Vec<String>>")
+            .param("name: &str")
+            .returns("TypeMapping")
+            .body(|s| {
+                s.write("match (local.get(name), remote.get(name))").in_block(|s| {
+                    s.line("(Some(local), Some(remote)) => TypeMapping::Mapped(offsets(remote, local)),");
+                    s.line("_ => TypeMapping::Incompatible(format!(\"struct {} is not known by the peer\", name)),");
+                });
+            })
+            .write_to(s);
+        s.lf();
+
+        s.line("/// Enum variants and union members are sent by index: for each of ours,");
+        s.line("/// the mapping gives the index the peer knows it by, if any.");
+        _fn("index_mapping")
+            .param("local: &HashMap<String, Vec<String>>")
+            .param("remote: &HashMap<String, Vec<String>>")
+            .param("kind: &str")
+            .param("name: &str")
+            .returns("TypeMapping")
+            .body(|s| {
+                s.write("match (local.get(name), remote.get(name))").in_block(|s| {
+                    s.line("(Some(local), Some(remote)) => TypeMapping::Mapped(offsets(local, remote)),");
+                    s.line("_ => TypeMapping::Incompatible(format!(\"{} {} is not known by the peer\", kind, name)),");
+                });
+            })
+            .write_to(s);
+    }
+
     fn write_atoms(&self, s: &mut Scope) {
         for a in &[
             Atom {
                 proto: &self,
                 kind: ast::Kind::Request,
                 name: "Params",
+                member: "Params",
             },
             Atom {
                 proto: &self,
                 kind: ast::Kind::Request,
                 name: "Results",
+                member: "Results",
             },
             Atom {
                 proto: &self,
                 kind: ast::Kind::Notification,
                 name: "NotificationParams",
+                member: "Params",
             },
         ] {
             s.write(a).lf();
@@ -180,6 +530,14 @@ impl<'a> Protocol<'a> {
             s.line("pub phantom: std::marker::PhantomData<(P, R)>,");
         });
         s.lf();
+
+        s.line("pub trait Notifiable: Sized");
+        s.in_block(|s| {
+            s.line("fn method() -> &'static str;");
+            s.line("fn upcast_params(self) -> NotificationParams;");
+            s.line("fn downcast_params(params: NotificationParams) -> Option<Self>;");
+        });
+        s.lf();
     }
 }
 
@@ -187,6 +545,8 @@ pub struct Atom<'a> {
     pub proto: &'a Protocol<'a>,
     pub name: &'a str,
     pub kind: ast::Kind,
+    /// Name of the type each function declares for this atom
+    pub member: &'a str,
 }
 
 impl<'a> Atom<'a> {
@@ -259,9 +619,16 @@ impl<'a> Atom<'a> {
                     writeln!(s, "let len = rd.read_array_len()?;").unwrap();
                     write!(s, "if len != 2").unwrap();
                     s.in_block(|s| {
-                        // FIXME: this might not be ideal, this probably
-                        // should be a proper facts error
-                        writeln!(s, "unreachable!();").unwrap();
+                        writeln!(
+                            s,
+                            "return Err({Error}::IncompatibleSchema(format!({msg:?}, len)));",
+                            Error = Structs::FactsError(),
+                            msg = format!(
+                                "Expected {} to be an array of 2 elements, got {{}}",
+                                self.name
+                            )
+                        )
+                        .unwrap();
                     });
 
                     writeln!(s, "let typ: u32 = rd.read_int()?;").unwrap();
@@ -281,8 +648,13 @@ impl<'a> Atom<'a> {
 
                             i += 1;
                         });
-                        // FIXME: proper error
-                        s.write("_ => unreachable!(),").lf();
+                        writeln!(
+                            s,
+                            "_ => Err({Error}::IncompatibleSchema(format!({msg:?}, typ))),",
+                            Error = Structs::FactsError(),
+                            msg = format!("Received unrecognized variant for {}: {{}}", self.name)
+                        )
+                        .unwrap();
                     });
                 })
                 .write_to(s);
@@ -299,13 +671,15 @@ impl<'a> Atom<'a> {
                 ))
                 .body(|s| {
                     writeln!(s, "let o = &mapping.__{slot};", slot = self.name).unwrap();
-                    write!(s, "match self").unwrap();
+                    // like unions, atoms without variants have no values,
+                    // so there's nothing to match.
+                    write!(s, "match *self").unwrap();
                     s.in_block(|s| {
                         let mut i = 0;
                         self.for_each_fun(&mut |f| {
                             writeln!(
                                 s,
-                                "{name}::{variant}(value) =>\n    o.write_union(wr, mapping, {name:?}, {variant:?}, {index}, value),",
+                                "{name}::{variant}(ref value) =>\n    o.write_union(wr, mapping, {name:?}, {variant:?}, {index}, value),",
                                 index = i,
                                 name = &self.name,
                                 variant = f.variant(),
@@ -314,7 +688,6 @@ impl<'a> Atom<'a> {
 
                             i += 1;
                         });
-                        writeln!(s, "_ => unreachable!(),").unwrap();
                     });
                 })
                 .write_to(s);
@@ -335,7 +708,7 @@ impl<'a> Display for Atom<'a> {
                     "{variant}({module}::{name})",
                     variant = f.variant(),
                     module = f.module(&self.proto.body.stack),
-                    name = &self.name
+                    name = &self.member
                 ));
             });
             e.write_to(s);
@@ -361,3 +734,13 @@ fn get_builtins() -> Vec<(ast::Kind, String)> {
         (ast::Kind::Notification, "NotificationParams".into()),
     ]
 }
+
+const DESCRIPTOR_TABLES: &[&str] = &["requests", "notifications", "structs", "enums", "unions"];
+
+fn quoted_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{:?}", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -2,11 +2,15 @@ use crate::codegen::rust::prelude::*;
 
 pub struct Symbols<'a> {
     body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+    graph: &'a ast::TypeGraph<'a>,
 }
 
 impl<'a> Symbols<'a> {
-    pub fn new(body: ast::Anchored<'a, &'a ast::NamespaceBody>) -> Self {
-        Self { body }
+    pub fn new(
+        body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+        graph: &'a ast::TypeGraph<'a>,
+    ) -> Self {
+        Self { body, graph }
     }
 }
 
@@ -17,19 +21,22 @@ impl<'a> Display for Symbols<'a> {
             let stack = &body.stack;
 
             for node in &body.structs {
-                s.write(Struct::new(stack.anchor(node)));
+                s.write(Struct::new(stack.anchor(node), self.graph));
             }
             for node in &body.enums {
                 s.write(Enum::new(stack.anchor(node)));
             }
+            for node in &body.unions {
+                s.write(Union::new(stack.anchor(node), self.graph));
+            }
             for node in &body.functions {
-                s.write(Function::new(stack.anchor(node)));
+                s.write(Function::new(stack.anchor(node), self.graph));
             }
 
             for ns in &body.inner.namespaces {
-                write!(s, "pub mod {}", ns.name.text()).unwrap();
+                write!(s, "pub mod {}", ident(ns.name.text())).unwrap();
                 s.in_block(|s| {
-                    s.write(Symbols::new(stack.push(ns).anchor(&ns.body)));
+                    s.write(Symbols::new(stack.push(ns).anchor(&ns.body), self.graph));
                 });
             }
         })
@@ -38,11 +45,30 @@ impl<'a> Display for Symbols<'a> {
 
 pub struct Struct<'a> {
     node: ast::Anchored<'a, &'a ast::StructDecl>,
+    graph: &'a ast::TypeGraph<'a>,
+    imported: bool,
 }
 
 impl<'a> Struct<'a> {
-    fn new(node: ast::Anchored<'a, &'a ast::StructDecl>) -> Self {
-        Self { node }
+    fn new(node: ast::Anchored<'a, &'a ast::StructDecl>, graph: &'a ast::TypeGraph<'a>) -> Self {
+        Self {
+            node,
+            graph,
+            imported: false,
+        }
+    }
+
+    /// A struct declared by another member of the workspace: only
+    /// implements `Factual` for this member's protocol.
+    pub fn imported(
+        node: ast::Anchored<'a, &'a ast::StructDecl>,
+        graph: &'a ast::TypeGraph<'a>,
+    ) -> Self {
+        Self {
+            node,
+            graph,
+            imported: true,
+        }
     }
 }
 
@@ -51,22 +77,25 @@ impl<'a> Display for Struct<'a> {
         Scope::fmt(f, |s| {
             let stack = &self.node.stack;
 
-            s.write(derive().clone().debug());
-            s.write("pub struct ").write(self.node.name());
-            s.in_block(|s| {
-                for f in &self.node.fields {
-                    s.write(Field::new(stack.anchor(f))).write(",").lf();
-                }
-            });
+            if !self.imported {
+                s.write(derive().clone().debug());
+                s.write("pub struct ").write(ident(self.node.name()));
+                s.in_block(|s| {
+                    for (i, f) in self.node.fields.iter().enumerate() {
+                        let boxed = self.graph.is_recursive_field(self.node.inner, i);
+                        s.write(Field::new(stack.anchor(f), boxed)).write(",").lf();
+                    }
+                });
+                s.lf();
+            }
 
-            s.lf();
             _impl_trait(
                 format!(
                     "{Factual}<{M}>",
                     Factual = Traits::Factual(),
                     M = stack.ProtocolMapping()
                 ),
-                self.node.name(),
+                ident(self.node.name()),
             )
             .body(|s| {
                 _fn("read")
@@ -81,18 +110,35 @@ impl<'a> Display for Struct<'a> {
                         Error = Structs::FactsError()
                     ))
                     .body(|s| {
-                        writeln!(
-                            s,
-                            "rd.expect_array_len({len})?;",
-                            len = self.node.fields.len()
-                        )
-                        .unwrap();
+                        writeln!(s, "let len = rd.read_array_len()?;").unwrap();
+                        write!(s, "if len != {len}", len = self.node.fields.len()).unwrap();
+                        s.in_block(|s| {
+                            writeln!(
+                                s,
+                                "return Err({Error}::IncompatibleSchema(format!({msg:?}, len)));",
+                                Error = Structs::FactsError(),
+                                msg = format!(
+                                    "Expected {} to be an array of {} elements, got {{}}",
+                                    self.node.name(),
+                                    self.node.fields.len()
+                                )
+                            )
+                            .unwrap();
+                        });
                         s.write("Ok(Self").in_terminated_block(")", |s| {
+                            // imported types implement `Factual` once per importer,
+                            // so `Self::subread` would be ambiguous.
                             for field in &self.node.fields {
+                                let value = format!(
+                                    "<Self as {Factual}<{M}>>::subread(rd)?",
+                                    Factual = Traits::Factual(),
+                                    M = stack.ProtocolMapping()
+                                );
                                 writeln!(
                                     s,
-                                    "{field}: Self::subread(rd)?,",
-                                    field = field.name.text()
+                                    "{field}: {value},",
+                                    field = ident(field.name.text()),
+                                    value = value,
                                 )
                                 .unwrap();
                             }
@@ -122,160 +168,238 @@ impl<'a> Display for Struct<'a> {
                                     s,
                                     "{index} => self.{field}.write(mapping, wr),",
                                     index = index,
-                                    field = field.name.text()
+                                    field = ident(field.name.text())
                                 )
                                 .unwrap();
                             }
-                            writeln!(s, "_ => unreachable!(),").unwrap();
+                            // offsets come from the negotiated mapping, which
+                            // could be made with a mismatched descriptor.
+                            writeln!(
+                                s,
+                                "_ => Err({Error}::IncompatibleSchema(format!({msg:?}, i))),",
+                                Error = Structs::FactsError(),
+                                msg = format!(
+                                    "Field index out of range for {}: {{}}",
+                                    self.node.name()
+                                )
+                            )
+                            .unwrap();
                         });
                     })
                     .write_to(s);
             })
             .write_to(s);
+
+            if self.graph.is_recursive(ast::Decl::Struct(self.node.inner)) {
+                write_boxed_factual(s, stack, self.node.name());
+            }
         })
     }
 }
 
 pub struct Function<'a> {
     node: ast::Anchored<'a, &'a ast::FunctionDecl>,
+    graph: &'a ast::TypeGraph<'a>,
 }
 
 impl<'a> Function<'a> {
-    fn new(node: ast::Anchored<'a, &'a ast::FunctionDecl>) -> Self {
-        Self { node }
+    fn new(node: ast::Anchored<'a, &'a ast::FunctionDecl>, graph: &'a ast::TypeGraph<'a>) -> Self {
+        Self { node, graph }
     }
-}
 
-impl<'a> Display for Function<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Scope::fmt(f, |s| {
-            let stack = &self.node.stack;
+    fn write_request(&self, s: &mut Scope, stack: &ast::Stack) {
+        _fn("method")
+            .kw_pub()
+            .returns(format!(
+                "{Slottable}<Params, Results>",
+                Slottable = stack.Slottable(),
+            ))
+            .body(|s| {
+                writeln!(
+                    s,
+                    "{Slottable} {{ phantom: std::marker::PhantomData }}",
+                    Slottable = stack.Slottable()
+                )
+                .unwrap();
+            })
+            .write_to(s);
 
-            writeln!(
-                s,
-                "pub use {name}::method as {name};",
-                name = self.node.name()
-            )
-            .unwrap();
+        s.write(Struct::new(stack.anchor(&self.node.params), self.graph));
+        s.write(Struct::new(stack.anchor(&self.node.results), self.graph));
 
-            s.write("pub mod ").write(self.node.name());
-            s.in_block(|s| {
-                let stack = stack.push(self.node.inner);
+        s.lf();
+
+        _impl_trait(
+            format!("{Callable}<Results>", Callable = stack.Callable()),
+            "Params",
+        )
+        .body(|s| {
+            _fn("upcast_params")
+                .self_param("self")
+                .returns(stack.Params())
+                .body(|s| {
+                    writeln!(
+                        s,
+                        "{Params}::{variant}(self)",
+                        Params = stack.Params(),
+                        variant = self.node.variant()
+                    )
+                    .unwrap();
+                })
+                .write_to(s);
+
+            _fn("downcast_results")
+                .param(format!("results: {Results}", Results = stack.Results()))
+                .returns("Option<Results>")
+                .body(|s| {
+                    s.write("match results");
+                    s.in_block(|s| {
+                        writeln!(
+                            s,
+                            "{Results}::{variant}(r) => Some(r),",
+                            Results = stack.Results(),
+                            variant = self.node.variant()
+                        )
+                        .unwrap();
+                        s.line("_ => None,");
+                    });
+                })
+                .write_to(s);
+        })
+        .write_to(s);
+
+        s.lf();
+
+        _impl_trait(
+            format!(
+                "{Implementable}<Params>",
+                Implementable = stack.Implementable()
+            ),
+            "Results",
+        )
+        .body(|s| {
+            _fn("method")
+                .returns("&'static str")
+                .body(|s| {
+                    writeln!(s, "{:?}", self.node.method()).unwrap();
+                })
+                .write_to(s);
+            _fn("upcast_results")
+                .self_param("self")
+                .returns(stack.Results())
+                .body(|s| {
+                    writeln!(
+                        s,
+                        "{Results}::{variant}(self)",
+                        Results = stack.Results(),
+                        variant = self.node.variant(),
+                    )
+                    .unwrap();
+                })
+                .write_to(s);
+            _fn("downcast_params")
+                .param(format!("params: {Params}", Params = stack.Params()))
+                .returns("Option<Params>")
+                .body(|s| {
+                    s.write("match params");
+                    s.in_block(|s| {
+                        writeln!(
+                            s,
+                            "{Params}::{variant}(p) => Some(p),",
+                            Params = stack.Params(),
+                            variant = self.node.variant()
+                        )
+                        .unwrap();
+                        s.line("_ => None,");
+                    });
+                })
+                .write_to(s);
+        })
+        .write_to(s);
+    }
 
+    fn write_notification(&self, s: &mut Scope, stack: &ast::Stack) {
+        // notifications get no response, so they have no results.
+        s.write(Struct::new(stack.anchor(&self.node.params), self.graph));
+
+        s.lf();
+
+        _impl_trait(stack.Notifiable(), "Params")
+            .body(|s| {
                 _fn("method")
-                    .kw_pub()
-                    .returns(format!(
-                        "{Slottable}<Params, Results>",
-                        Slottable = stack.Slottable(),
-                    ))
+                    .returns("&'static str")
+                    .body(|s| {
+                        writeln!(s, "{:?}", self.node.method()).unwrap();
+                    })
+                    .write_to(s);
+                _fn("upcast_params")
+                    .self_param("self")
+                    .returns(stack.NotificationParams())
                     .body(|s| {
                         writeln!(
                             s,
-                            "{Slottable} {{ phantom: std::marker::PhantomData }}",
-                            Slottable = stack.Slottable()
+                            "{NotificationParams}::{variant}(self)",
+                            NotificationParams = stack.NotificationParams(),
+                            variant = self.node.variant()
                         )
                         .unwrap();
                     })
                     .write_to(s);
-
-                s.write(Struct::new(stack.anchor(&self.node.params)));
-                s.write(Struct::new(stack.anchor(&self.node.results)));
-
-                s.lf();
-
-                _impl_trait(
-                    format!("{Callable}<Results>", Callable = stack.Callable()),
-                    "Params",
-                )
-                .body(|s| {
-                    _fn("upcast_params")
-                        .self_param("self")
-                        .returns(stack.Params())
-                        .body(|s| {
+                _fn("downcast_params")
+                    .param(format!(
+                        "params: {NotificationParams}",
+                        NotificationParams = stack.NotificationParams()
+                    ))
+                    .returns("Option<Self>")
+                    .body(|s| {
+                        s.write("match params");
+                        s.in_block(|s| {
                             writeln!(
                                 s,
-                                "{Params}::{variant}(self)",
-                                Params = stack.Params(),
+                                "{NotificationParams}::{variant}(p) => Some(p),",
+                                NotificationParams = stack.NotificationParams(),
                                 variant = self.node.variant()
                             )
                             .unwrap();
-                        })
-                        .write_to(s);
-
-                    _fn("downcast_results")
-                        .param(format!("results: {Results}", Results = stack.Results()))
-                        .returns("Option<Results>")
-                        .body(|s| {
-                            s.write("match results");
-                            s.in_block(|s| {
-                                writeln!(
-                                    s,
-                                    "{Results}::{variant}(r) => Some(r),",
-                                    Results = stack.Results(),
-                                    variant = self.node.variant()
-                                )
-                                .unwrap();
-                                s.line("_ => None,");
-                            });
-                        })
-                        .write_to(s);
-                })
-                .write_to(s);
+                            s.line("_ => None,");
+                        });
+                    })
+                    .write_to(s);
+            })
+            .write_to(s);
+    }
+}
 
-                s.lf();
+impl<'a> Display for Function<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let stack = &self.node.stack;
 
-                _impl_trait(
-                    format!(
-                        "{Implementable}<Params>",
-                        Implementable = stack.Implementable()
-                    ),
-                    "Results",
+            // there's no router slot to hand a notification's method to
+            // until the runtime can deliver them.
+            if let ast::Kind::Request = self.node.kind {
+                writeln!(
+                    s,
+                    "pub use {name}::method as {name};",
+                    name = ident(self.node.name())
                 )
-                .body(|s| {
-                    _fn("method")
-                        .returns("&'static str")
-                        .body(|s| {
-                            writeln!(s, "{:?}", self.node.method()).unwrap();
-                        })
-                        .write_to(s);
-                    _fn("upcast_results")
-                        .self_param("self")
-                        .returns(stack.Results())
-                        .body(|s| {
-                            writeln!(
-                                s,
-                                "{Results}::{variant}(self)",
-                                Results = stack.Results(),
-                                variant = self.node.variant(),
-                            )
-                            .unwrap();
-                        })
-                        .write_to(s);
-                    _fn("downcast_params")
-                        .param(format!("params: {Params}", Params = stack.Params()))
-                        .returns("Option<Params>")
-                        .body(|s| {
-                            s.write("match params");
-                            s.in_block(|s| {
-                                writeln!(
-                                    s,
-                                    "{Params}::{variant}(p) => Some(p),",
-                                    Params = stack.Params(),
-                                    variant = self.node.variant()
-                                )
-                                .unwrap();
-                                s.line("_ => None,");
-                            });
-                        })
-                        .write_to(s);
-                })
-                .write_to(s);
+                .unwrap();
+            }
+
+            s.write("pub mod ").write(ident(self.node.name()));
+            s.in_block(|s| {
+                let stack = stack.push(self.node.inner);
+
+                match self.node.kind {
+                    ast::Kind::Request => self.write_request(s, &stack),
+                    ast::Kind::Notification => self.write_notification(s, &stack),
+                }
 
                 if let Some(body) = self.node.body.as_ref() {
                     s.lf();
 
                     for node in &body.functions {
-                        s.write(Function::new(stack.anchor(node)));
+                        s.write(Function::new(stack.anchor(node), self.graph));
                     }
                 }
             });
@@ -285,10 +409,12 @@ impl<'a> Display for Function<'a> {
 
 pub struct Field<'a> {
     node: ast::Anchored<'a, &'a ast::Field>,
+    /// Whether the field leads back to its struct
+    boxed: bool,
 }
 impl<'a> Field<'a> {
-    fn new(node: ast::Anchored<'a, &'a ast::Field>) -> Self {
-        Self { node }
+    fn new(node: ast::Anchored<'a, &'a ast::Field>, boxed: bool) -> Self {
+        Self { node, boxed }
     }
 }
 
@@ -300,8 +426,8 @@ impl<'a> Display for Field<'a> {
             write!(
                 s,
                 "pub {name}: {typ}",
-                name = self.node.name(),
-                typ = self.node.typ.as_rust(&self.node.stack)
+                name = ident(self.node.name()),
+                typ = boxed_type(self.node.stack.anchor(&self.node.typ), self.boxed)
             )
             .unwrap();
         })
@@ -310,11 +436,24 @@ impl<'a> Display for Field<'a> {
 
 pub struct Enum<'a> {
     node: ast::Anchored<'a, &'a ast::EnumDecl>,
+    imported: bool,
 }
 
 impl<'a> Enum<'a> {
     fn new(node: ast::Anchored<'a, &'a ast::EnumDecl>) -> Self {
-        Self { node }
+        Self {
+            node,
+            imported: false,
+        }
+    }
+
+    /// An enum declared by another member of the workspace: only
+    /// implements `Factual` for this member's protocol.
+    pub fn imported(node: ast::Anchored<'a, &'a ast::EnumDecl>) -> Self {
+        Self {
+            node,
+            imported: true,
+        }
     }
 }
 
@@ -322,26 +461,54 @@ impl<'a> Display for Enum<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Scope::fmt(f, |s| {
             let stack = &self.node.stack;
-
-            s.comment(&self.node.comment);
-            s.write(derive().clone().copy().debug());
-            s.write("#[repr(u32)]").lf();
-            s.write("pub enum ").write(self.node.name.text());
-            s.in_block(|s| {
-                for (i, v) in self.node.variants.iter().enumerate() {
-                    s.comment(&v.comment);
-                    writeln!(s, "{name} = {i},", name = v.name.text(), i = i).unwrap();
+            let repr = self.node.repr();
+
+            if !self.imported {
+                s.comment(&self.node.comment);
+                // enums can be map keys
+                s.write(derive().clone().copy().debug().partial_eq().eq().hash());
+                match repr {
+                    ast::EnumRepr::Index => {
+                        s.write("#[repr(u32)]").lf();
+                    }
+                    ast::EnumRepr::Integer => {
+                        s.write("#[repr(i64)]").lf();
+                    }
+                    // strings can't be discriminants
+                    ast::EnumRepr::String => {}
                 }
-            });
+                s.write("pub enum ").write(ident(self.node.name.text()));
+                s.in_block(|s| {
+                    for (i, v) in self.node.variants.iter().enumerate() {
+                        s.comment(&v.comment);
+                        match v.value.as_ref() {
+                            None => {
+                                writeln!(s, "{name} = {i},", name = ident(v.name.text()), i = i)
+                                    .unwrap()
+                            }
+                            Some(ast::EnumValue::Integer(lit)) => writeln!(
+                                s,
+                                "{name} = {value},",
+                                name = ident(v.name.text()),
+                                value = lit.value
+                            )
+                            .unwrap(),
+                            Some(ast::EnumValue::String(_)) => {
+                                writeln!(s, "{name},", name = ident(v.name.text())).unwrap()
+                            }
+                        }
+                    }
+                });
+                s.lf();
+            }
 
-            s.lf();
             _impl_trait(
                 format!(
                     "{Factual}<{M}>",
                     Factual = Traits::Factual(),
                     M = stack.ProtocolMapping()
                 ),
-                self.node.name(),
+                ident(self.node.name()),
             )
             .body(|s| {
                 _fn("read")
@@ -356,15 +523,39 @@ impl<'a> Display for Enum<'a> {
                         facts = Mods::facts()
                     ))
                     .body(|s| {
-                        writeln!(s, "let value: u32 = rd.read_int()?;").unwrap();
-                        writeln!(s, "use {name} as E;", name = self.node.name()).unwrap();
-                        s.write("Ok(match value").in_terminated_block(")", |s| {
+                        let subject = match repr {
+                            ast::EnumRepr::Index => {
+                                writeln!(s, "let value: u32 = rd.read_int()?;").unwrap();
+                                "value"
+                            }
+                            ast::EnumRepr::Integer => {
+                                writeln!(s, "let value: i64 = rd.read_int()?;").unwrap();
+                                "value"
+                            }
+                            ast::EnumRepr::String => {
+                                writeln!(
+                                    s,
+                                    "let value: String = <Self as {Factual}<{M}>>::subread(rd)?;",
+                                    Factual = Traits::Factual(),
+                                    M = stack.ProtocolMapping()
+                                )
+                                .unwrap();
+                                "value.as_str()"
+                            }
+                        };
+                        writeln!(s, "use {name} as E;", name = ident(self.node.name())).unwrap();
+                        write!(s, "Ok(match {subject}", subject = subject).unwrap();
+                        s.in_terminated_block(")", |s| {
                             for (i, variant) in self.node.variants.iter().enumerate() {
+                                let value = match variant.value.as_ref() {
+                                    Some(value) => value.to_string(),
+                                    None => i.to_string(),
+                                };
                                 writeln!(
                                     s,
-                                    "{i} => E::{variant},",
-                                    i = i,
-                                    variant = variant.name.text()
+                                    "{value} => E::{variant},",
+                                    value = value,
+                                    variant = ident(variant.name.text())
                                 )
                                 .unwrap();
                             }
@@ -389,22 +580,187 @@ impl<'a> Display for Enum<'a> {
                     .param(format!("mapping: &{M}", M = stack.ProtocolMapping()))
                     .param("wr: &mut W")
                     .returns(format!("Result<(), {facts}::Error>", facts = Mods::facts()))
-                    .body(|s| {
+                    .body(|s| match repr {
+                        ast::EnumRepr::Index => {
+                            writeln!(
+                                s,
+                                "let offsets = mapping.{variant}.validate()?;",
+                                variant = self.node.variant()
+                            )
+                            .unwrap();
+                            writeln!(s, "match offsets.get(*self as usize)").unwrap();
+                            s.in_block(|s| {
+                                writeln!(s, "Some(value) => value.write(mapping, wr),").unwrap();
+                                writeln!(
+                                    s,
+                                    "None => Err({Error}::IncompatibleSchema(format!({msg:?}, self))),",
+                                    Error = Structs::FactsError(),
+                                    msg = format!(
+                                        "Enum variant for {} not known by the peer: {{:#?}}",
+                                        self.node.name()
+                                    )
+                                )
+                                .unwrap();
+                            });
+                        }
+                        // explicit values are sent as-is, so peers agree on them
+                        // whatever the order of variants is.
+                        ast::EnumRepr::Integer => {
+                            writeln!(
+                                s,
+                                "mapping.{variant}.validate()?;",
+                                variant = self.node.variant()
+                            )
+                            .unwrap();
+                            writeln!(s, "(*self as i64).write(mapping, wr)").unwrap();
+                        }
+                        ast::EnumRepr::String => {
+                            writeln!(
+                                s,
+                                "mapping.{variant}.validate()?;",
+                                variant = self.node.variant()
+                            )
+                            .unwrap();
+                            writeln!(s, "use {name} as E;", name = ident(self.node.name())).unwrap();
+                            s.write("let value: &str = match self").in_terminated_block(";", |s| {
+                                for variant in &self.node.variants {
+                                    if let Some(value) = variant.value.as_ref() {
+                                        writeln!(
+                                            s,
+                                            "E::{variant} => {value},",
+                                            variant = ident(variant.name.text()),
+                                            value = value
+                                        )
+                                        .unwrap();
+                                    }
+                                }
+                            });
+                            writeln!(s, "value.write(mapping, wr)").unwrap();
+                        }
+                    })
+                    .write_to(s);
+            })
+            .write_to(s);
+        })
+    }
+}
+
+pub struct Union<'a> {
+    node: ast::Anchored<'a, &'a ast::UnionDecl>,
+    graph: &'a ast::TypeGraph<'a>,
+    imported: bool,
+}
+
+impl<'a> Union<'a> {
+    fn new(node: ast::Anchored<'a, &'a ast::UnionDecl>, graph: &'a ast::TypeGraph<'a>) -> Self {
+        Self {
+            node,
+            graph,
+            imported: false,
+        }
+    }
+
+    /// A union declared by another member of the workspace: only
+    /// implements `Factual` for this member's protocol.
+    pub fn imported(
+        node: ast::Anchored<'a, &'a ast::UnionDecl>,
+        graph: &'a ast::TypeGraph<'a>,
+    ) -> Self {
+        Self {
+            node,
+            graph,
+            imported: true,
+        }
+    }
+}
+
+impl<'a> Display for Union<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let stack = &self.node.stack;
+
+            if !self.imported {
+                s.comment(&self.node.comment);
+                s.write(derive().clone().debug());
+                s.write("pub enum ").write(ident(self.node.name()));
+                s.in_block(|s| {
+                    for (i, m) in self.node.members.iter().enumerate() {
+                        s.comment(&m.comment);
                         writeln!(
                             s,
-                            "let offsets = mapping.{variant}.validate()?;",
-                            variant = self.node.variant()
+                            "{variant}({typ}),",
+                            variant = ident(m.variant()),
+                            typ = boxed_type(
+                                stack.anchor(&m.typ),
+                                self.graph.is_recursive_member(self.node.inner, i)
+                            )
                         )
                         .unwrap();
-                        writeln!(s, "match offsets.get(*self as usize)").unwrap();
+                    }
+                });
+                s.lf();
+            }
+
+            _impl_trait(
+                format!(
+                    "{Factual}<{M}>",
+                    Factual = Traits::Factual(),
+                    M = stack.ProtocolMapping()
+                ),
+                ident(self.node.name()),
+            )
+            .body(|s| {
+                _fn("read")
+                    .self_bound("Sized")
+                    .type_param_bound("R", Traits::Read())
+                    .param(format!(
+                        "rd: &mut {Reader}<R>",
+                        Reader = Structs::FactsReader()
+                    ))
+                    .returns(format!(
+                        "Result<Self, {Error}>",
+                        Error = Structs::FactsError()
+                    ))
+                    .body(|s| {
+                        writeln!(s, "let len = rd.read_array_len()?;").unwrap();
+                        write!(s, "if len != 2").unwrap();
+                        s.in_block(|s| {
+                            writeln!(
+                                s,
+                                "return Err({Error}::IncompatibleSchema(format!({msg:?}, len)));",
+                                Error = Structs::FactsError(),
+                                msg = format!(
+                                    "Expected {} to be an array of 2 elements, got {{}}",
+                                    self.node.name()
+                                )
+                            )
+                            .unwrap();
+                        });
+                        writeln!(s, "let typ: u32 = rd.read_int()?;").unwrap();
+                        s.write("match typ");
                         s.in_block(|s| {
-                            writeln!(s, "Some(value) => value.write(mapping, wr),").unwrap();
+                            for (i, m) in self.node.members.iter().enumerate() {
+                                let value = format!(
+                                    "<Self as {Factual}<{M}>>::subread(rd)?",
+                                    Factual = Traits::Factual(),
+                                    M = stack.ProtocolMapping()
+                                );
+                                writeln!(
+                                    s,
+                                    "{i} => Ok({name}::{variant}({value})),",
+                                    i = i,
+                                    name = ident(self.node.name()),
+                                    variant = ident(m.variant()),
+                                    value = value,
+                                )
+                                .unwrap();
+                            }
                             writeln!(
                                 s,
-                                "None => Err({Error}::IncompatibleSchema(format!({msg:?}, self))),",
+                                "_ => Err({Error}::IncompatibleSchema(format!({msg:?}, typ))),",
                                 Error = Structs::FactsError(),
                                 msg = format!(
-                                    "Enum variant for {} not known by the peer: {{:#?}}",
+                                    "Received unrecognized union variant for {}: {{}}",
                                     self.node.name()
                                 )
                             )
@@ -412,8 +768,117 @@ impl<'a> Display for Enum<'a> {
                         });
                     })
                     .write_to(s);
+                s.lf();
+                _fn("write")
+                    .type_param_bound("W", Traits::Write())
+                    .self_param("&self")
+                    .param(format!("mapping: &{M}", M = stack.ProtocolMapping()))
+                    .param("wr: &mut W")
+                    .returns(format!(
+                        "Result<(), {Error}>",
+                        Error = Structs::FactsError()
+                    ))
+                    .body(|s| {
+                        writeln!(
+                            s,
+                            "let o = &mapping.{variant};",
+                            variant = self.node.variant()
+                        )
+                        .unwrap();
+                        // empty unions have no values, and `match self {}`
+                        // doesn't compile for references to them.
+                        s.write("match *self");
+                        s.in_block(|s| {
+                            for (i, m) in self.node.members.iter().enumerate() {
+                                writeln!(
+                                    s,
+                                    "{name}::{variant}(ref value) => o.write_union(wr, mapping, {name_str:?}, {variant_str:?}, {i}, value),",
+                                    i = i,
+                                    name = ident(self.node.name()),
+                                    variant = ident(m.variant()),
+                                    name_str = self.node.name(),
+                                    variant_str = m.variant(),
+                                )
+                                .unwrap();
+                            }
+                        });
+                    })
+                    .write_to(s);
             })
             .write_to(s);
+
+            if self.graph.is_recursive(ast::Decl::Union(self.node.inner)) {
+                write_boxed_factual(s, stack, self.node.name());
+            }
         })
     }
 }
+
+/// Returns the Rust type of a field or member that leads back to its
+/// own declaration: recursive types would be infinitely large otherwise,
+/// so the declaration it holds by value is boxed, inside any options.
+fn boxed_type(typ: ast::Anchored<&ast::Type>, boxed: bool) -> String {
+    if !boxed {
+        return typ.as_rust(&typ.stack).to_string();
+    }
+    match &typ.kind {
+        ast::TypeKind::Option(opt) => format!(
+            "Option<{}>",
+            boxed_type(typ.stack.anchor(&opt.inner), boxed)
+        ),
+        _ => format!("Box<{}>", typ.as_rust(&typ.stack)),
+    }
+}
+
+/// Implements `Factual` for boxes of a type that contains itself, so
+/// that the fields and members holding it are read and written like
+/// any other. The runtime doesn't implement it for boxes.
+fn write_boxed_factual(s: &mut Scope, stack: &ast::Stack, name: &str) {
+    _impl_trait(
+        format!(
+            "{Factual}<{M}>",
+            Factual = Traits::Factual(),
+            M = stack.ProtocolMapping()
+        ),
+        format!("Box<{}>", ident(name)),
+    )
+    .body(|s| {
+        _fn("read")
+            .self_bound("Sized")
+            .type_param_bound("R", Traits::Read())
+            .param(format!(
+                "rd: &mut {Reader}<R>",
+                Reader = Structs::FactsReader()
+            ))
+            .returns(format!(
+                "Result<Self, {Error}>",
+                Error = Structs::FactsError()
+            ))
+            .body(|s| {
+                writeln!(
+                    s,
+                    "Ok(Box::new(<{name} as {Factual}<{M}>>::read(rd)?))",
+                    name = ident(name),
+                    Factual = Traits::Factual(),
+                    M = stack.ProtocolMapping()
+                )
+                .unwrap();
+            })
+            .write_to(s);
+        s.lf();
+        _fn("write")
+            .type_param_bound("W", Traits::Write())
+            .self_param("&self")
+            .param(format!("mapping: &{M}", M = stack.ProtocolMapping()))
+            .param("wr: &mut W")
+            .returns(format!(
+                "Result<(), {Error}>",
+                Error = Structs::FactsError()
+            ))
+            .body(|s| {
+                writeln!(s, "(**self).write(mapping, wr)").unwrap();
+            })
+            .write_to(s);
+    })
+    .write_to(s);
+}
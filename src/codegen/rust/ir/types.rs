@@ -1,5 +1,4 @@
 use crate::codegen::rust::prelude::*;
-use colored::*;
 
 pub trait AsRust {
     fn as_rust<'a>(&'a self, stack: &'a ast::Stack<'a>) -> Box<fmt::Display + 'a>;
@@ -32,20 +31,12 @@ impl<'a> fmt::Display for RustType<'a> {
             TypeKind::User => {
                 let t = &self.0;
                 let down: Vec<_> = t.text().split(".").collect();
-                match t.stack.lookup_struct(ast::LookupMode::Relaxed, &down[..]) {
-                    Some(path) => path.generate_rust(f),
-                    None => {
-                        t.span
-                            .position()
-                            .diag_err(format!(
-                                "{} unknown type {:?}: not a built-in, and not in scope either",
-                                "error:".red().bold(),
-                                t.text(),
-                            ))
-                            .print();
-                        panic!("Failed to resolve type");
-                    }
-                }
+                // the checker makes sure every type resolves
+                let path = t
+                    .stack
+                    .lookup_struct(ast::LookupMode::Relaxed, &down[..])
+                    .expect("type to be resolved by the checker");
+                path.generate_rust(f)
             }
         }
     }
@@ -90,7 +81,7 @@ impl<'a> GeneratesRust for ast::RelativePath<'a> {
                 list.item("super");
             }
             for item in &self.down {
-                list.item(item);
+                list.item(ident(item));
             }
         })
     }
@@ -0,0 +1,41 @@
+use crate::codegen::{ReservedWords, Usage};
+use std::borrow::Cow;
+
+/// Strict and reserved keywords of Rust 2018, which can still be
+/// used as identifiers with the `r#` prefix.
+const ESCAPABLE: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Keywords that aren't allowed as raw identifiers either.
+const UNUSABLE: &[&str] = &["crate", "self", "Self", "super"];
+
+pub struct Keywords;
+
+impl ReservedWords for Keywords {
+    fn language(&self) -> &'static str {
+        "Rust"
+    }
+
+    fn is_unusable(&self, _usage: Usage, name: &str) -> bool {
+        UNUSABLE.contains(&name)
+    }
+
+    fn escape<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if ESCAPABLE.contains(&name) {
+            format!("r#{}", name).into()
+        } else {
+            name.into()
+        }
+    }
+}
+
+/// Returns `name` as a Rust identifier: keywords become raw
+/// identifiers, so a field named `type` is emitted as `r#type`.
+pub fn ident(name: &str) -> Cow<'_, str> {
+    Keywords.escape(name)
+}
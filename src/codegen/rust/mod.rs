@@ -3,11 +3,13 @@ use crate::codegen::output::*;
 use crate::codegen::Result;
 
 use std::fmt::Write;
-use std::fs::File;
 use std::time::Instant;
 
 mod prelude;
 
+mod keywords;
+pub use keywords::Keywords;
+
 mod ir;
 use ir::*;
 
@@ -25,9 +27,9 @@ impl<'a> Generator<'a> {
 }
 
 impl<'a> super::Generator for Generator<'a> {
-    fn emit_workspace(&self, workspace: &ast::Workspace) -> Result {
+    fn emit_workspace(&self, workspace: &ast::Workspace, files: &Files) -> Result {
         for member in workspace.members.values() {
-            self.emit(workspace, member)?;
+            self.emit(workspace, member, files)?;
         }
 
         {
@@ -39,13 +41,14 @@ impl<'a> super::Generator for Generator<'a> {
 
             if let Some(wrapper_name) = wrapper_name {
                 let wrapper_path = workspace.dir.join(wrapper_name);
-                let mut output = Scope::writer(File::create(&wrapper_path)?);
+                let mut output = String::new();
                 let mut s = Scope::new(&mut output);
                 self.write_prelude(&mut s);
 
                 for member in workspace.members.values() {
-                    writeln!(s, "pub mod {};", member.name)?;
+                    writeln!(s, "pub mod {};", keywords::ident(&member.name))?;
                 }
+                files.save(&wrapper_path, &output)?;
             }
         }
 
@@ -63,38 +66,58 @@ impl<'a> Generator<'a> {
         s.lf();
     }
 
-    fn emit(&self, workspace: &ast::Workspace, member: &ast::WorkspaceMember) -> Result {
+    fn emit(
+        &self,
+        workspace: &ast::Workspace,
+        member: &ast::WorkspaceMember,
+        files: &Files,
+    ) -> Result {
         let start_instant = Instant::now();
 
         let output_path = workspace.dir.join(&member.name).join("mod.rs");
-        std::fs::create_dir_all(output_path.parent().unwrap())?;
-        let mut output = Scope::writer(File::create(&output_path)?);
+        let mut output = String::new();
         let mut scope = Scope::new(&mut output);
         let s = &mut scope;
         self.write_prelude(s);
 
-        let schema = member.schema.as_ref().expect("schema to be parsed");
+        let schema = member
+            .schema
+            .as_ref()
+            .expect("schema to be parsed")
+            .as_ref();
         let stack = ast::Stack::new(schema);
         let body = stack.anchor(&schema.body);
+        let graph = ast::TypeGraph::new(schema);
 
         {
             s.line("pub use schema::*;");
             s.lf();
         }
 
+        let imports = schema.transitive_imports();
+
         {
-            s.write(Protocol { body: body.clone() });
+            s.write(Protocol {
+                body: body.clone(),
+                imports: imports.clone(),
+            });
             s.lf();
         }
 
         {
             write!(s, "pub mod schema").unwrap();
             s.in_block(|s| {
-                s.write(Symbols::new(body.clone()));
+                s.write(Imports {
+                    workspace,
+                    stack: &stack,
+                    imports: imports.clone(),
+                });
+                s.write(Symbols::new(body.clone(), &graph));
                 write_pair(s, body.clone());
             });
             s.lf();
         }
+        files.save(&output_path, &output)?;
 
         let end_instant = Instant::now();
         if self.opts.verbose {
@@ -108,3 +131,51 @@ impl<'a> Generator<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{codegen, Mode};
+    use std::fs;
+
+    /// The wrapper lists every member, and has to come out the same
+    /// from one run to the next for `--check` to pass.
+    #[test]
+    fn wrapper_is_stable() -> Result<(), Box<dyn std::error::Error>> {
+        let opts = crate::Opts { verbose: false };
+        let dir = std::env::temp_dir().join(format!("lavish-wrapper-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+
+        let names = ["zebra", "apple", "mango", "kiwi", "banana", "cherry"];
+        let mut rules = String::from("target rust {\n    wrapper = mod\n}\n\n");
+        for name in &names {
+            fs::write(dir.join(format!("{}.lavish", name)), "server fn ping()\n")?;
+            rules.push_str(&format!("build {}\n", name));
+        }
+        fs::write(dir.join("lavish-rules"), rules)?;
+
+        let workspace = crate::workspace::parse_workspace(&opts, &dir)?;
+        codegen(&opts, &workspace, Mode::Write)?;
+        assert_eq!(
+            fs::read_to_string(dir.join("mod.rs"))?
+                .lines()
+                .filter(|l| l.starts_with("pub mod"))
+                .collect::<Vec<_>>(),
+            vec![
+                "pub mod apple;",
+                "pub mod banana;",
+                "pub mod cherry;",
+                "pub mod kiwi;",
+                "pub mod mango;",
+                "pub mod zebra;",
+            ]
+        );
+
+        for _ in 0..5 {
+            let workspace = crate::workspace::parse_workspace(&opts, &dir)?;
+            codegen(&opts, &workspace, Mode::Check)?;
+        }
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -2,4 +2,5 @@
 pub(crate) use crate::codegen::{
     prelude::*,
     rust::ir::{ast_ext::*, common::*, lang::*, types::*},
+    rust::keywords::ident,
 };
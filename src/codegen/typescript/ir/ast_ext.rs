@@ -0,0 +1,32 @@
+#![allow(non_snake_case)]
+
+use crate::ast;
+use heck::MixedCase;
+
+/// TypeScript namespaces mirror schema namespaces, so symbols are
+/// referred to by their full path from the module root: `foo.bar.Baz`.
+pub fn ts_path(names: &[&str]) -> String {
+    names.join(".")
+}
+
+pub trait TsFn {
+    fn ts_method(&self) -> String;
+    fn Params(&self) -> String;
+    fn Results(&self) -> String;
+}
+
+impl<'a> TsFn for ast::Anchored<'a, &ast::FunctionDecl> {
+    // Client method of a function, for example `session.attempt_login`
+    // will have name `sessionAttemptLogin`
+    fn ts_method(&self) -> String {
+        self.names().join("_").to_mixed_case()
+    }
+
+    fn Params(&self) -> String {
+        format!("{}.Params", ts_path(&self.names()))
+    }
+
+    fn Results(&self) -> String {
+        format!("{}.Results", ts_path(&self.names()))
+    }
+}
@@ -0,0 +1,7 @@
+pub(crate) mod ast_ext;
+pub(crate) mod pair;
+pub(crate) mod runtime;
+pub(crate) mod symbols;
+pub(crate) mod types;
+
+pub(crate) use {pair::*, runtime::*, symbols::*};
@@ -0,0 +1,162 @@
+use crate::codegen::typescript::prelude::*;
+use heck::CamelCase;
+
+fn for_each_fun_of_side(
+    body: &ast::Anchored<&ast::NamespaceBody>,
+    side: ast::Side,
+    cb: &mut dyn FnMut(ast::Anchored<&ast::FunctionDecl>),
+) {
+    body.for_each_fun_of_schema(&mut |f| {
+        if f.side == side {
+            cb(f);
+        }
+    });
+}
+
+/// Calls functions implemented by the server
+pub struct Client<'a> {
+    body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Client<'a> {
+    pub fn new(body: ast::Anchored<'a, &'a ast::NamespaceBody>) -> Self {
+        Self { body }
+    }
+
+    fn write_method(&self, s: &mut Scope, f: ast::Anchored<&ast::FunctionDecl>) {
+        write_doc(s, &f.comment);
+
+        // lets callers write `client.shutdown()` instead of `client.shutdown({})`
+        let default = if f.params.fields.is_empty() {
+            " = {}"
+        } else {
+            ""
+        };
+        let ret = match f.kind {
+            ast::Kind::Request => f.Results(),
+            ast::Kind::Notification => "void".into(),
+        };
+        write!(
+            s,
+            "async {method}(params: {P}{default}): Promise<{ret}>",
+            method = f.ts_method(),
+            P = f.Params(),
+            default = default,
+            ret = ret,
+        )
+        .unwrap();
+        s.in_block(|s| match f.kind {
+            ast::Kind::Request => {
+                writeln!(
+                    s,
+                    "return await this.caller.call({method:?}, params);",
+                    method = f.method()
+                )
+                .unwrap();
+            }
+            ast::Kind::Notification => {
+                writeln!(
+                    s,
+                    "await this.caller.notify({method:?}, params);",
+                    method = f.method()
+                )
+                .unwrap();
+            }
+        });
+        s.lf();
+    }
+}
+
+impl<'a> Display for Client<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            s.line("/**");
+            s.line(" * Calls functions implemented by the server.");
+            s.line(" */");
+            s.write("export class Client").in_block(|s| {
+                s.line("constructor(private caller: Caller) {}");
+                s.lf();
+
+                for_each_fun_of_side(&self.body, ast::Side::Server, &mut |f| {
+                    self.write_method(s, f);
+                });
+            });
+            s.lf();
+        })
+    }
+}
+
+/// Routes calls made by the server to implementations of client functions
+pub struct Handler<'a> {
+    body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(body: ast::Anchored<'a, &'a ast::NamespaceBody>) -> Self {
+        Self { body }
+    }
+
+    fn write_slot(&self, s: &mut Scope, f: ast::Anchored<&ast::FunctionDecl>) {
+        writeln!(s, "/**").unwrap();
+        writeln!(
+            s,
+            " * Registers the implementation of `{method}`.",
+            method = f.method()
+        )
+        .unwrap();
+        writeln!(s, " */").unwrap();
+
+        let ret = match f.kind {
+            ast::Kind::Request => f.Results(),
+            ast::Kind::Notification => "void".into(),
+        };
+        write!(
+            s,
+            "on{Method}(f: (client: Client, params: {P}) => Promise<{ret}>)",
+            Method = f.ts_method().to_camel_case(),
+            P = f.Params(),
+            ret = ret,
+        )
+        .unwrap();
+        s.in_block(|s| {
+            writeln!(
+                s,
+                "this.slots[{method:?}] = (caller, params) => f(new Client(caller), params);",
+                method = f.method()
+            )
+            .unwrap();
+        });
+        s.lf();
+    }
+}
+
+impl<'a> Display for Handler<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            s.line("/**");
+            s.line(" * Routes calls made by the server to implementations");
+            s.line(" * of client functions.");
+            s.line(" */");
+            s.write("export class Handler").in_block(|s| {
+                s.line("private slots: { [method: string]: Slot } = {};");
+                s.lf();
+
+                for_each_fun_of_side(&self.body, ast::Side::Client, &mut |f| {
+                    self.write_slot(s, f);
+                });
+
+                s.line("/**");
+                s.line(" * Called by the lavish runtime for every incoming call.");
+                s.line(" */");
+                s.write("async handle(caller: Caller, method: string, params: any): Promise<any>")
+                    .in_block(|s| {
+                        s.line("let slot = this.slots[method];");
+                        s.write("if (!slot)").in_block(|s| {
+                            s.line("throw new Error(`lavish: method unimplemented: ${method}`);");
+                        });
+                        s.line("return await slot(caller, params);");
+                    });
+            });
+        })
+    }
+}
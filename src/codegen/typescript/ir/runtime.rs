@@ -0,0 +1,32 @@
+use crate::codegen::typescript::prelude::*;
+
+/// Interfaces the generated code expects from the lavish TypeScript runtime.
+pub struct Runtime {}
+
+impl Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            s.line("/**");
+            s.line(" * Caller is implemented by the lavish runtime, and lets");
+            s.line(" * clients send requests and notifications to a peer.");
+            s.line(" */");
+            s.write("export interface Caller").in_block(|s| {
+                s.line("call(method: string, params: any): Promise<any>;");
+                s.line("notify(method: string, params: any): Promise<void>;");
+            });
+            s.lf();
+
+            s.line("type Slot = (caller: Caller, params: any) => Promise<any>;");
+            s.lf();
+        })
+    }
+}
+
+/// Writes a schema comment as a JSDoc block, so editors show it on hover.
+pub fn write_doc(s: &mut Scope, comment: &Option<ast::Comment>) {
+    if comment.is_some() {
+        s.line("/**");
+        s.comment_with(" *", comment);
+        s.line(" */");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::codegen::typescript::prelude::*;
+
+pub struct Symbols<'a> {
+    body: ast::Anchored<'a, &'a ast::NamespaceBody>,
+}
+
+impl<'a> Symbols<'a> {
+    pub fn new(body: ast::Anchored<'a, &'a ast::NamespaceBody>) -> Self {
+        Self { body }
+    }
+}
+
+impl<'a> Display for Symbols<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let body = &self.body;
+            let stack = &body.stack;
+
+            for node in &body.structs {
+                s.write(Struct::new(stack.anchor(node)));
+            }
+            for node in &body.enums {
+                s.write(Enum::new(stack.anchor(node)));
+            }
+            for node in &body.functions {
+                s.write(Function::new(stack.anchor(node)));
+            }
+
+            for ns in &body.inner.namespaces {
+                write_doc(s, &ns.comment);
+                write!(s, "export namespace {}", ns.name.text()).unwrap();
+                s.in_block(|s| {
+                    s.write(Symbols::new(stack.push(ns).anchor(&ns.body)));
+                });
+                s.lf();
+            }
+        })
+    }
+}
+
+pub struct Struct<'a> {
+    node: ast::Anchored<'a, &'a ast::StructDecl>,
+}
+
+impl<'a> Struct<'a> {
+    fn new(node: ast::Anchored<'a, &'a ast::StructDecl>) -> Self {
+        Self { node }
+    }
+}
+
+impl<'a> Display for Struct<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            let stack = &self.node.stack;
+
+            write_doc(s, &self.node.comment);
+            write!(s, "export interface {name}", name = self.node.name()).unwrap();
+            s.in_block(|s| {
+                for field in &self.node.fields {
+                    write_doc(s, &field.comment);
+                    // optional fields are left out entirely when absent
+                    let (name, typ) = match &field.typ.kind {
+                        ast::TypeKind::Option(opt) => {
+                            (format!("{}?", field.name.text()), &*opt.inner)
+                        }
+                        _ => (field.name.text().to_string(), &field.typ),
+                    };
+                    writeln!(s, "{name}: {typ};", name = name, typ = typ.as_ts(stack)).unwrap();
+                }
+            });
+            s.lf();
+        })
+    }
+}
+
+pub struct Enum<'a> {
+    node: ast::Anchored<'a, &'a ast::EnumDecl>,
+}
+
+impl<'a> Enum<'a> {
+    fn new(node: ast::Anchored<'a, &'a ast::EnumDecl>) -> Self {
+        Self { node }
+    }
+}
+
+impl<'a> Display for Enum<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            write_doc(s, &self.node.comment);
+            write!(s, "export enum {name}", name = self.node.name()).unwrap();
+            s.in_block(|s| {
+                for (i, v) in self.node.variants.iter().enumerate() {
+                    write_doc(s, &v.comment);
+                    let value = match v.value.as_ref() {
+                        Some(value) => value.to_string(),
+                        None => i.to_string(),
+                    };
+                    writeln!(s, "{name} = {value},", name = v.name.text(), value = value).unwrap();
+                }
+            });
+            s.lf();
+        })
+    }
+}
+
+pub struct Function<'a> {
+    node: ast::Anchored<'a, &'a ast::FunctionDecl>,
+}
+
+impl<'a> Function<'a> {
+    fn new(node: ast::Anchored<'a, &'a ast::FunctionDecl>) -> Self {
+        Self { node }
+    }
+}
+
+impl<'a> Display for Function<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Scope::fmt(f, |s| {
+            write!(s, "export namespace {name}", name = self.node.name()).unwrap();
+            s.in_block(|s| {
+                let stack = self.node.stack.push(self.node.inner);
+
+                s.write(Struct::new(stack.anchor(&self.node.params)));
+                s.write(Struct::new(stack.anchor(&self.node.results)));
+
+                if let Some(body) = self.node.body.as_ref() {
+                    s.write(Symbols::new(stack.anchor(body)));
+                }
+            });
+            s.lf();
+        })
+    }
+}
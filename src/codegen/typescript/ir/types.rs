@@ -0,0 +1,77 @@
+use crate::codegen::typescript::prelude::*;
+
+pub trait AsTs {
+    fn as_ts<'a>(&'a self, stack: &'a ast::Stack<'a>) -> Box<dyn fmt::Display + 'a>;
+}
+
+struct TsType<'a>(pub ast::Anchored<'a, &'a ast::Type>);
+
+impl AsTs for ast::Type {
+    fn as_ts<'a>(&'a self, stack: &'a ast::Stack<'a>) -> Box<dyn fmt::Display + 'a> {
+        Box::new(TsType(stack.anchor(self)))
+    }
+}
+
+impl<'a> fmt::Display for TsType<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ast::TypeKind;
+
+        match &self.0.kind {
+            TypeKind::Base(base) => base.generate_ts(f),
+            TypeKind::Map(map) => {
+                let keys = map.keys.as_ts(&self.0.stack);
+                let values = map.values.as_ts(&self.0.stack);
+                match &map.keys.kind {
+                    // msgpack maps decode to plain objects, which can
+                    // only be indexed by strings and numbers.
+                    TypeKind::Base(ast::BaseType::String) => {
+                        write!(f, "{{ [key: string]: {V} }}", V = values)
+                    }
+                    TypeKind::Base(base) if base.is_number() => {
+                        write!(f, "{{ [key: number]: {V} }}", V = values)
+                    }
+                    _ => write!(f, "Map<{K}, {V}>", K = keys, V = values),
+                }
+            }
+            TypeKind::Option(opt) => {
+                write!(f, "{T} | undefined", T = opt.inner.as_ts(&self.0.stack))
+            }
+            TypeKind::Array(arr) => write!(f, "Array<{T}>", T = arr.inner.as_ts(&self.0.stack)),
+            TypeKind::User => {
+                let t = &self.0;
+                let down: Vec<_> = t.text().split('.').collect();
+                // the checker makes sure every type resolves
+                let path = t
+                    .stack
+                    .lookup_struct(ast::LookupMode::Relaxed, &down[..])
+                    .expect("type to be resolved by the checker");
+                write!(f, "{}", ts_path(&t.stack.absolute_names(&path)))
+            }
+        }
+    }
+}
+
+trait GeneratesTs {
+    fn generate_ts(&self, f: &mut fmt::Formatter) -> fmt::Result;
+    fn is_number(&self) -> bool;
+}
+
+impl GeneratesTs for ast::BaseType {
+    fn generate_ts(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ast::BaseType as T;
+
+        match self {
+            T::Bool => write!(f, "boolean"),
+            T::String => write!(f, "string"),
+            T::Data => write!(f, "Uint8Array"),
+            T::Timestamp => write!(f, "Date"),
+            _ => write!(f, "number"),
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        use ast::BaseType as T;
+
+        !matches!(self, T::Bool | T::String | T::Data | T::Timestamp)
+    }
+}
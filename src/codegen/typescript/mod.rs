@@ -0,0 +1,246 @@
+use crate::ast;
+use crate::codegen::output::*;
+use crate::codegen::Result;
+
+use std::time::Instant;
+
+mod prelude;
+
+mod ir;
+use ir::*;
+
+pub struct Generator<'a> {
+    #[allow(unused)]
+    target: ast::TypeScriptTarget,
+
+    opts: &'a crate::Opts,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(opts: &'a crate::Opts, target: ast::TypeScriptTarget) -> Self {
+        Self { opts, target }
+    }
+}
+
+/// Reserved words of TypeScript, in strict mode, which every module is in.
+const RESERVED: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Keywords are valid property names in TypeScript, so fields never
+/// need escaping, but namespaces and imports are emitted as they are.
+pub struct Keywords;
+
+impl super::ReservedWords for Keywords {
+    fn language(&self) -> &'static str {
+        "TypeScript"
+    }
+
+    fn is_unusable(&self, usage: super::Usage, name: &str) -> bool {
+        usage != super::Usage::Member && RESERVED.contains(&name)
+    }
+}
+
+impl<'a> super::Generator for Generator<'a> {
+    fn emit_workspace(&self, workspace: &ast::Workspace, files: &Files) -> Result {
+        for member in workspace.members.values() {
+            self.emit(workspace, member, files)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Generator<'a> {
+    fn write_prelude(&self, s: &mut Scope) {
+        s.line("// Code generated by lavish. DO NOT EDIT.");
+        s.line("// https://github.com/fasterthanlime/lavish");
+        s.lf();
+    }
+
+    fn emit(
+        &self,
+        workspace: &ast::Workspace,
+        member: &ast::WorkspaceMember,
+        files: &Files,
+    ) -> Result {
+        let start_instant = Instant::now();
+
+        let schema = member
+            .schema
+            .as_ref()
+            .expect("schema to be parsed")
+            .as_ref();
+        let stack = ast::Stack::new(schema);
+        let body = stack.anchor(&schema.body);
+
+        let mut has_unions = false;
+        body.for_each_union_of_schema(&mut |un| {
+            has_unions = true;
+            un.name
+                .span
+                .position()
+                .diag_err(format!(
+                    "union {} can't be generated: the TypeScript generator doesn't support unions yet",
+                    un.name.text()
+                ))
+                .code("unsupported")
+                .print();
+        });
+        if has_unions {
+            return Err("unions are not supported by the TypeScript generator yet".into());
+        }
+
+        let output_path = workspace.dir.join(&member.name).join("index.ts");
+        let mut output = String::new();
+        let mut scope = Scope::new(&mut output);
+        let s = &mut scope;
+        self.write_prelude(s);
+
+        for import in &schema.imports {
+            let member = match workspace.member_for(import) {
+                Some(member) => member.name.as_str(),
+                None => import.name.text(),
+            };
+            s.line(format!(
+                "import * as {alias} from \"../{member}\";",
+                alias = import.name.text(),
+                member = member
+            ));
+        }
+        if !schema.imports.is_empty() {
+            s.lf();
+        }
+
+        s.write(Runtime {});
+        s.write(Symbols::new(body.clone()));
+        s.write(Client::new(body.clone()));
+        s.write(Handler::new(body.clone()));
+        files.save(&output_path, &output)?;
+
+        let end_instant = Instant::now();
+        if self.opts.verbose {
+            println!(
+                "Generated {:?} in {:?}",
+                output_path,
+                end_instant.duration_since(start_instant)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{codegen, Mode};
+    use crate::parser::sink;
+    use std::path::Path;
+
+    /// The generated code in `tests/` is checked in: if this fails,
+    /// review the diff and run `lavish build src/codegen/typescript/tests`.
+    #[test]
+    fn fixtures_are_up_to_date() -> Result<(), Box<dyn std::error::Error>> {
+        let opts = crate::Opts { verbose: false };
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("codegen")
+            .join("typescript")
+            .join("tests");
+        let workspace = crate::workspace::parse_workspace(&opts, &dir)?;
+        codegen(&opts, &workspace, Mode::Check)?;
+        Ok(())
+    }
+
+    /// Generates code for a workspace in `unsupported/`, returning the
+    /// error and the diagnostics it printed.
+    fn unsupported(name: &str) -> (String, Vec<sink::Record>) {
+        let opts = crate::Opts { verbose: false };
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("codegen")
+            .join("typescript")
+            .join("unsupported")
+            .join(name);
+        let workspace = crate::workspace::parse_workspace(&opts, &dir).unwrap();
+
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = codegen(&opts, &workspace, Mode::Check);
+        sink::set(Box::new(sink::Colored));
+        (format!("{}", res.unwrap_err()), collector.take())
+    }
+
+    #[test]
+    fn unions_are_unsupported() {
+        let (err, records) = unsupported("unions");
+        assert_eq!(
+            err,
+            "codegen error: unions are not supported by the TypeScript generator yet"
+        );
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.code, r.start.line, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Some("unsupported"),
+                    0,
+                    "union Shape can't be generated: the TypeScript generator doesn't support unions yet"
+                ),
+                (
+                    Some("unsupported"),
+                    14,
+                    "union Stroke can't be generated: the TypeScript generator doesn't support unions yet"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(unused)]
+pub(crate) use crate::codegen::{
+    prelude::*,
+    typescript::ir::{ast_ext::*, runtime::write_doc, types::*},
+};
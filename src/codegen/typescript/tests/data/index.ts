@@ -0,0 +1,54 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export namespace get_some_bytes {
+    export interface Params {
+    }
+
+    export interface Results {
+        value: Uint8Array;
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async getSomeBytes(params: get_some_bytes.Params = {}): Promise<get_some_bytes.Results> {
+        return await this.caller.call("get_some_bytes", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,272 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export namespace get_baz {
+    export interface Params {
+    }
+
+    export interface Results {
+        baz: foo.bar.baz.Baz;
+    }
+
+}
+
+export namespace get_bar {
+    export interface Params {
+    }
+
+    export interface Results {
+        bar: foo.bar.Bar;
+    }
+
+}
+
+export namespace get_foo {
+    export interface Params {
+    }
+
+    export interface Results {
+        foo: foo.Foo;
+    }
+
+}
+
+export namespace foo {
+    export interface Foo {
+    }
+
+    export namespace get_baz {
+        export interface Params {
+        }
+
+        export interface Results {
+            baz: foo.bar.baz.Baz;
+        }
+
+    }
+
+    export namespace get_bar {
+        export interface Params {
+        }
+
+        export interface Results {
+            bar: foo.bar.Bar;
+        }
+
+    }
+
+    export namespace get_foo {
+        export interface Params {
+        }
+
+        export interface Results {
+            foo: foo.Foo;
+        }
+
+    }
+
+    export namespace bar {
+        export interface Bar {
+        }
+
+        export namespace get_baz {
+            export interface Params {
+            }
+
+            export interface Results {
+                baz: foo.bar.baz.Baz;
+            }
+
+        }
+
+        export namespace get_bar {
+            export interface Params {
+            }
+
+            export interface Results {
+                bar: foo.bar.Bar;
+            }
+
+        }
+
+        export namespace get_foo {
+            export interface Params {
+            }
+
+            export interface Results {
+                foo: foo.Foo;
+            }
+
+        }
+
+        export namespace baz {
+            export interface Baz {
+                x: number;
+            }
+
+            export namespace get_baz {
+                export interface Params {
+                }
+
+                export interface Results {
+                    baz: foo.bar.baz.Baz;
+                }
+
+            }
+
+            export namespace get_bar {
+                export interface Params {
+                }
+
+                export interface Results {
+                    bar: foo.bar.Bar;
+                }
+
+            }
+
+            export namespace get_foo {
+                export interface Params {
+                }
+
+                export interface Results {
+                    foo: foo.Foo;
+                }
+
+            }
+
+            export namespace deepest {
+                export namespace get_baz {
+                    export interface Params {
+                    }
+
+                    export interface Results {
+                        baz: foo.bar.baz.Baz;
+                    }
+
+                }
+
+                export namespace get_bar {
+                    export interface Params {
+                    }
+
+                    export interface Results {
+                        bar: foo.bar.Bar;
+                    }
+
+                }
+
+                export namespace get_foo {
+                    export interface Params {
+                    }
+
+                    export interface Results {
+                        foo: foo.Foo;
+                    }
+
+                }
+
+            }
+
+        }
+
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async getBaz(params: get_baz.Params = {}): Promise<get_baz.Results> {
+        return await this.caller.call("get_baz", params);
+    }
+
+    async getBar(params: get_bar.Params = {}): Promise<get_bar.Results> {
+        return await this.caller.call("get_bar", params);
+    }
+
+    async getFoo(params: get_foo.Params = {}): Promise<get_foo.Results> {
+        return await this.caller.call("get_foo", params);
+    }
+
+    async fooGetBaz(params: foo.get_baz.Params = {}): Promise<foo.get_baz.Results> {
+        return await this.caller.call("foo.get_baz", params);
+    }
+
+    async fooGetBar(params: foo.get_bar.Params = {}): Promise<foo.get_bar.Results> {
+        return await this.caller.call("foo.get_bar", params);
+    }
+
+    async fooGetFoo(params: foo.get_foo.Params = {}): Promise<foo.get_foo.Results> {
+        return await this.caller.call("foo.get_foo", params);
+    }
+
+    async fooBarGetBaz(params: foo.bar.get_baz.Params = {}): Promise<foo.bar.get_baz.Results> {
+        return await this.caller.call("foo.bar.get_baz", params);
+    }
+
+    async fooBarGetBar(params: foo.bar.get_bar.Params = {}): Promise<foo.bar.get_bar.Results> {
+        return await this.caller.call("foo.bar.get_bar", params);
+    }
+
+    async fooBarGetFoo(params: foo.bar.get_foo.Params = {}): Promise<foo.bar.get_foo.Results> {
+        return await this.caller.call("foo.bar.get_foo", params);
+    }
+
+    async fooBarBazGetBaz(params: foo.bar.baz.get_baz.Params = {}): Promise<foo.bar.baz.get_baz.Results> {
+        return await this.caller.call("foo.bar.baz.get_baz", params);
+    }
+
+    async fooBarBazGetBar(params: foo.bar.baz.get_bar.Params = {}): Promise<foo.bar.baz.get_bar.Results> {
+        return await this.caller.call("foo.bar.baz.get_bar", params);
+    }
+
+    async fooBarBazGetFoo(params: foo.bar.baz.get_foo.Params = {}): Promise<foo.bar.baz.get_foo.Results> {
+        return await this.caller.call("foo.bar.baz.get_foo", params);
+    }
+
+    async fooBarBazDeepestGetBaz(params: foo.bar.baz.deepest.get_baz.Params = {}): Promise<foo.bar.baz.deepest.get_baz.Results> {
+        return await this.caller.call("foo.bar.baz.deepest.get_baz", params);
+    }
+
+    async fooBarBazDeepestGetBar(params: foo.bar.baz.deepest.get_bar.Params = {}): Promise<foo.bar.baz.deepest.get_bar.Results> {
+        return await this.caller.call("foo.bar.baz.deepest.get_bar", params);
+    }
+
+    async fooBarBazDeepestGetFoo(params: foo.bar.baz.deepest.get_foo.Params = {}): Promise<foo.bar.baz.deepest.get_foo.Results> {
+        return await this.caller.call("foo.bar.baz.deepest.get_foo", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,78 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export namespace anon_session {
+    export interface Params {
+    }
+
+    export interface Results {
+        session: session.Session;
+    }
+
+}
+
+export namespace session {
+    export interface Session {
+        username: string;
+        display_name: string;
+    }
+
+    export namespace login {
+        export interface Params {
+            username: string;
+            password: string;
+        }
+
+        export interface Results {
+            session: session.Session;
+        }
+
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async anonSession(params: anon_session.Params = {}): Promise<anon_session.Results> {
+        return await this.caller.call("anon_session", params);
+    }
+
+    async sessionLogin(params: session.login.Params): Promise<session.login.Results> {
+        return await this.caller.call("session.login", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,71 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export interface Session {
+    login_type: LoginType;
+    level: Level;
+}
+
+export enum LoginType {
+    Anonymous = "anonymous",
+    Password = "password",
+}
+
+export enum Level {
+    Guest = -1,
+    User = 0,
+    Admin = 100,
+}
+
+export namespace login {
+    export interface Params {
+        login_type: LoginType;
+    }
+
+    export interface Results {
+        session: Session;
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async login(params: login.Params): Promise<login.Results> {
+        return await this.caller.call("login", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,158 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+/**
+ * A user of the service.
+ * 
+ * Second paragraph.
+ */
+export interface User {
+    /**
+     * Their login
+     */
+    name: string;
+    display_name?: string;
+    tags: Array<string>;
+    meta: { [key: string]: number };
+    avatar: Uint8Array;
+    joined_at: Date;
+}
+
+/**
+ * Settings of a user. Doc comments keep going past blank lines,
+ * and lose their indentation:
+ * 
+ * settings.emails = true
+ * (still about Settings)
+ */
+export interface Settings {
+    /**
+     * Whether to send emails
+     * (still about emails)
+     */
+    emails: boolean;
+}
+
+export enum Mood {
+    Happy = 0,
+    /**
+     * comment
+     */
+    Sad = 1,
+    VeryConfused = 2,
+}
+
+export enum Code {
+    Ok = 200,
+    NotFound = 404,
+}
+
+export namespace ping {
+    export interface Params {
+    }
+
+    export interface Results {
+    }
+
+}
+
+export namespace ask {
+    export interface Params {
+        question: string;
+    }
+
+    export interface Results {
+        answer: string;
+        mood: Mood;
+    }
+
+}
+
+export namespace session {
+    export interface Token {
+        value: string;
+    }
+
+    export namespace login {
+        export interface Params {
+            name: string;
+        }
+
+        export interface Results {
+            token: session.Token;
+            user: User;
+        }
+
+    }
+
+    export namespace notify_logout {
+        export interface Params {
+            reason: string;
+        }
+
+        export interface Results {
+        }
+
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async ping(params: ping.Params = {}): Promise<ping.Results> {
+        return await this.caller.call("ping", params);
+    }
+
+    async sessionLogin(params: session.login.Params): Promise<session.login.Results> {
+        return await this.caller.call("session.login", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Registers the implementation of `ask`.
+     */
+    onAsk(f: (client: Client, params: ask.Params) => Promise<ask.Results>) {
+        this.slots["ask"] = (caller, params) => f(new Client(caller), params);
+    }
+
+    /**
+     * Registers the implementation of `session.notify_logout`.
+     */
+    onSessionNotifyLogout(f: (client: Client, params: session.notify_logout.Params) => Promise<session.notify_logout.Results>) {
+        this.slots["session.notify_logout"] = (caller, params) => f(new Client(caller), params);
+    }
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
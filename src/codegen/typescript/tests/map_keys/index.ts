@@ -0,0 +1,71 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export interface Palette {
+    by_name: { [key: string]: Color };
+    by_index: { [key: number]: Color };
+    names: Map<Color, string>;
+    shades: Map<Shade, Map<boolean, Color>>;
+}
+
+export enum Color {
+    Red = 0,
+    Green = 1,
+}
+
+export enum Shade {
+    Light = "light",
+    Dark = "dark",
+}
+
+export namespace paint {
+    export interface Params {
+        names: Map<Color, Palette>;
+    }
+
+    export interface Results {
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async paint(params: paint.Params): Promise<paint.Results> {
+        return await this.caller.call("paint", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,111 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export interface LogMessage {
+    msg: string;
+    level: number;
+}
+
+export namespace log {
+    export interface Params {
+        message: LogMessage;
+    }
+
+    export interface Results {
+    }
+
+}
+
+export namespace progress {
+    export namespace download {
+        export interface Params {
+            url: string;
+        }
+
+        export interface Results {
+            size: number;
+        }
+
+        export namespace update {
+            export interface Params {
+                downloaded: number;
+                total: number;
+            }
+
+            export interface Results {
+            }
+
+        }
+
+    }
+
+    export namespace done {
+        export interface Params {
+        }
+
+        export interface Results {
+        }
+
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async log(params: log.Params): Promise<void> {
+        await this.caller.notify("log", params);
+    }
+
+    async progressDownload(params: progress.download.Params): Promise<progress.download.Results> {
+        return await this.caller.call("progress.download", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Registers the implementation of `progress.download.update`.
+     */
+    onProgressDownloadUpdate(f: (client: Client, params: progress.download.update.Params) => Promise<void>) {
+        this.slots["progress.download.update"] = (caller, params) => f(new Client(caller), params);
+    }
+
+    /**
+     * Registers the implementation of `progress.done`.
+     */
+    onProgressDone(f: (client: Client, params: progress.done.Params) => Promise<void>) {
+        this.slots["progress.done"] = (caller, params) => f(new Client(caller), params);
+    }
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,69 @@
+// Code generated by lavish. DO NOT EDIT.
+// https://github.com/fasterthanlime/lavish
+
+/**
+ * Caller is implemented by the lavish runtime, and lets
+ * clients send requests and notifications to a peer.
+ */
+export interface Caller {
+    call(method: string, params: any): Promise<any>;
+    notify(method: string, params: any): Promise<void>;
+}
+
+type Slot = (caller: Caller, params: any) => Promise<any>;
+
+export namespace get_timestamp {
+    export interface Params {
+    }
+
+    export interface Results {
+        value: Date;
+    }
+
+}
+
+export namespace subtract_week {
+    export interface Params {
+        value: Date;
+    }
+
+    export interface Results {
+        value: Date;
+    }
+
+}
+
+/**
+ * Calls functions implemented by the server.
+ */
+export class Client {
+    constructor(private caller: Caller) {}
+
+    async getTimestamp(params: get_timestamp.Params = {}): Promise<get_timestamp.Results> {
+        return await this.caller.call("get_timestamp", params);
+    }
+
+    async subtractWeek(params: subtract_week.Params): Promise<subtract_week.Results> {
+        return await this.caller.call("subtract_week", params);
+    }
+
+}
+
+/**
+ * Routes calls made by the server to implementations
+ * of client functions.
+ */
+export class Handler {
+    private slots: { [method: string]: Slot } = {};
+
+    /**
+     * Called by the lavish runtime for every incoming call.
+     */
+    async handle(caller: Caller, method: string, params: any): Promise<any> {
+        let slot = this.slots[method];
+        if (!slot) {
+            throw new Error(`lavish: method unimplemented: ${method}`);
+        }
+        return await slot(caller, params);
+    }
+}
@@ -0,0 +1,755 @@
+use crate::ast;
+use crate::parser::{Position, Span};
+use colored::*;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::fmt;
+
+/// Whether a change can be deployed before every peer has the new
+/// schema. Values are sent by position: structs as arrays of exactly
+/// as many fields as they declare, and functions, union members and
+/// enum variants without explicit values as their index. So adding,
+/// removing or reordering fields is breaking, and so is anything that
+/// moves an item to another index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compatibility {
+    Compatible,
+    Breaking,
+}
+
+impl Compatibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "breaking",
+        }
+    }
+}
+
+/// A difference between two versions of a schema
+pub struct Change {
+    pub compatibility: Compatibility,
+    /// What changed, like `removed_function` or `changed_field_type`
+    pub kind: &'static str,
+    /// Full name of the function or type that changed
+    pub path: String,
+    pub message: String,
+    /// Where the change is: in the new schema, or in the old one for
+    /// things that were removed.
+    pub pos: Position,
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+
+    /// Lines and columns start at one, like in diagnostics.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "compatibility": self.compatibility.as_str(),
+            "kind": self.kind,
+            "path": self.path,
+            "message": self.message,
+            "file": self.pos.span.source.name(),
+            "line": self.pos.line + 1,
+            "column": self.pos.column + 1,
+        })
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let loc = format!(
+            "{}:{}:{}:",
+            self.pos.span.source.name(),
+            self.pos.line + 1,
+            self.pos.column + 1
+        );
+        let label = match self.compatibility {
+            Compatibility::Compatible => "compatible".green(),
+            Compatibility::Breaking => "breaking".red(),
+        };
+        writeln!(
+            f,
+            "{} {}: {} [{}]",
+            loc.bold(),
+            label.bold(),
+            self.message,
+            self.kind
+        )
+    }
+}
+
+/// Returns how `new` differs from `old`, in the order of `old`, then
+/// additions in the order of `new`.
+pub fn diff(old: &ast::Schema, new: &ast::Schema) -> Vec<Change> {
+    let old = Index::new(old);
+    let new = Index::new(new);
+    let mut d = Differ {
+        changes: Vec::new(),
+    };
+
+    d.diff_map(&old.functions, &new.functions, |d, path, old, new| {
+        d.diff_function(path, old, new)
+    });
+    for kind in &[ast::Kind::Request, ast::Kind::Notification] {
+        let names = |index: &Index| -> Vec<(String, Span)> {
+            let functions = index
+                .functions
+                .iter()
+                .filter(|(_, f)| f.inner.kind == *kind);
+            functions
+                .map(|(path, f)| (path.clone(), f.inner.name.span.clone()))
+                .collect()
+        };
+        d.diff_positions("moved_function", "function", "", &names(&old), &names(&new));
+    }
+    d.diff_map(&old.structs, &new.structs, |d, path, old, new| {
+        d.diff_fields(path, Flow::Both, old, new)
+    });
+    d.diff_map(&old.enums, &new.enums, |d, path, old, new| {
+        d.diff_enum(path, old, new)
+    });
+    d.diff_map(&old.unions, &new.unions, |d, path, old, new| {
+        d.diff_union(path, old, new)
+    });
+    d.changes
+}
+
+/// What a struct holds, for messages
+#[derive(Clone, Copy, PartialEq)]
+enum Flow {
+    Params,
+    Results,
+    /// Fields of a struct declared by the schema
+    Both,
+}
+
+impl Flow {
+    fn noun(self) -> &'static str {
+        match self {
+            Flow::Params => "parameter",
+            Flow::Results => "result",
+            Flow::Both => "field",
+        }
+    }
+}
+
+/// A declaration, along with the stack its types are looked up from
+struct Decl<'a, T> {
+    inner: &'a T,
+    stack: ast::Stack<'a>,
+}
+
+impl<'a> Decl<'a, ast::FunctionDecl> {
+    fn params(&self) -> Decl<'a, ast::StructDecl> {
+        Decl {
+            inner: &self.inner.params,
+            stack: self.stack.clone(),
+        }
+    }
+
+    fn results(&self) -> Decl<'a, ast::StructDecl> {
+        Decl {
+            inner: &self.inner.results,
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+trait Declared {
+    fn noun(&self) -> &'static str;
+    fn name(&self) -> &ast::Identifier;
+}
+
+impl Declared for ast::FunctionDecl {
+    fn noun(&self) -> &'static str {
+        match self.kind {
+            ast::Kind::Request => "function",
+            ast::Kind::Notification => "notification",
+        }
+    }
+
+    fn name(&self) -> &ast::Identifier {
+        &self.name
+    }
+}
+
+impl Declared for ast::StructDecl {
+    fn noun(&self) -> &'static str {
+        "struct"
+    }
+
+    fn name(&self) -> &ast::Identifier {
+        &self.name
+    }
+}
+
+impl Declared for ast::EnumDecl {
+    fn noun(&self) -> &'static str {
+        "enum"
+    }
+
+    fn name(&self) -> &ast::Identifier {
+        &self.name
+    }
+}
+
+impl Declared for ast::UnionDecl {
+    fn noun(&self) -> &'static str {
+        "union"
+    }
+
+    fn name(&self) -> &ast::Identifier {
+        &self.name
+    }
+}
+
+/// Functions and types of a schema, by full name
+struct Index<'a> {
+    functions: IndexMap<String, Decl<'a, ast::FunctionDecl>>,
+    structs: IndexMap<String, Decl<'a, ast::StructDecl>>,
+    enums: IndexMap<String, Decl<'a, ast::EnumDecl>>,
+    unions: IndexMap<String, Decl<'a, ast::UnionDecl>>,
+}
+
+impl<'a> Index<'a> {
+    fn new(schema: &'a ast::Schema) -> Self {
+        let mut index = Self {
+            functions: IndexMap::new(),
+            structs: IndexMap::new(),
+            enums: IndexMap::new(),
+            unions: IndexMap::new(),
+        };
+        index.collect(&ast::Stack::new(schema), &schema.body);
+        index
+    }
+
+    fn collect(&mut self, stack: &ast::Stack<'a>, body: &'a ast::NamespaceBody) {
+        let path = |name: &'a ast::Identifier| stack.names_and(name.text()).join(".");
+        for st in &body.structs {
+            let decl = Decl {
+                inner: st,
+                stack: stack.clone(),
+            };
+            self.structs.insert(path(&st.name), decl);
+        }
+        for en in &body.enums {
+            let decl = Decl {
+                inner: en,
+                stack: stack.clone(),
+            };
+            self.enums.insert(path(&en.name), decl);
+        }
+        for un in &body.unions {
+            let decl = Decl {
+                inner: un,
+                stack: stack.clone(),
+            };
+            self.unions.insert(path(&un.name), decl);
+        }
+        for f in &body.functions {
+            // params and results are looked up from inside the function
+            let inner = stack.push(f);
+            // functions are indexed like atoms are generated, with
+            // those of a body before the function itself.
+            if let Some(body) = f.body.as_ref() {
+                self.collect(&inner, body);
+            }
+            self.functions.insert(
+                path(&f.name),
+                Decl {
+                    inner: f,
+                    stack: inner.clone(),
+                },
+            );
+        }
+        for ns in &body.namespaces {
+            self.collect(&stack.push(ns), &ns.body);
+        }
+    }
+}
+
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(
+        &mut self,
+        compatibility: Compatibility,
+        kind: &'static str,
+        path: &str,
+        span: &Span,
+        message: String,
+    ) {
+        self.changes.push(Change {
+            compatibility,
+            kind,
+            path: path.into(),
+            message,
+            pos: span.position(),
+        });
+    }
+
+    /// Reports declarations that were removed or added, and calls `f`
+    /// for those in both versions.
+    fn diff_map<'a, T, F>(
+        &mut self,
+        old: &IndexMap<String, Decl<'a, T>>,
+        new: &IndexMap<String, Decl<'a, T>>,
+        f: F,
+    ) where
+        T: Declared,
+        F: Fn(&mut Self, &str, &Decl<'a, T>, &Decl<'a, T>),
+    {
+        for (path, old_decl) in old {
+            let decl = old_decl.inner;
+            match new.get(path) {
+                Some(new_decl) => f(self, path, old_decl, new_decl),
+                None => self.push(
+                    Compatibility::Breaking,
+                    removed_kind(decl.noun()),
+                    path,
+                    &decl.name().span,
+                    format!("{} `{}` was removed", decl.noun(), path),
+                ),
+            }
+        }
+        for (path, new_decl) in new {
+            let decl = new_decl.inner;
+            if !old.contains_key(path) {
+                self.push(
+                    Compatibility::Compatible,
+                    added_kind(decl.noun()),
+                    path,
+                    &decl.name().span,
+                    format!("{} `{}` was added", decl.noun(), path),
+                );
+            }
+        }
+    }
+
+    fn diff_function(
+        &mut self,
+        path: &str,
+        old: &Decl<ast::FunctionDecl>,
+        new: &Decl<ast::FunctionDecl>,
+    ) {
+        let span = &new.inner.name.span;
+        if old.inner.side != new.inner.side {
+            self.push(
+                Compatibility::Breaking,
+                "changed_side",
+                path,
+                span,
+                format!(
+                    "`{}` is now implemented by the {}, it was implemented by the {}",
+                    path, new.inner.side, old.inner.side
+                ),
+            );
+        }
+        if old.inner.kind != new.inner.kind {
+            self.push(
+                Compatibility::Breaking,
+                "changed_kind",
+                path,
+                span,
+                format!(
+                    "`{}` is now a {}, it was a {}",
+                    path,
+                    new.inner.noun(),
+                    old.inner.noun()
+                ),
+            );
+        }
+
+        self.diff_fields(path, Flow::Params, &old.params(), &new.params());
+        self.diff_fields(path, Flow::Results, &old.results(), &new.results());
+    }
+
+    fn diff_fields<'a>(
+        &mut self,
+        path: &str,
+        flow: Flow,
+        old: &Decl<'a, ast::StructDecl>,
+        new: &Decl<'a, ast::StructDecl>,
+    ) {
+        let noun = flow.noun();
+        for old_field in &old.inner.fields {
+            let name = old_field.name.text();
+            match new.inner.fields.iter().find(|f| f.name.text() == name) {
+                Some(new_field) => {
+                    let old_type = canonical(&old.stack, &old_field.typ);
+                    let new_type = canonical(&new.stack, &new_field.typ);
+                    if old_type != new_type {
+                        self.push(
+                            Compatibility::Breaking,
+                            "changed_field_type",
+                            path,
+                            &new_field.typ.span,
+                            format!(
+                                "{} `{}` of `{}` is now `{}`, it was `{}`",
+                                noun, name, path, new_type, old_type
+                            ),
+                        );
+                    }
+                }
+                None => {
+                    self.push(
+                        Compatibility::Breaking,
+                        "removed_field",
+                        path,
+                        &old_field.name.span,
+                        format!("{} `{}` of `{}` was removed", noun, name, path),
+                    );
+                }
+            }
+        }
+
+        for new_field in &new.inner.fields {
+            let name = new_field.name.text();
+            if old.inner.fields.iter().any(|f| f.name.text() == name) {
+                continue;
+            }
+            // even optional fields change how many values peers read
+            let (kind, adjective) = if is_optional(&new_field.typ) {
+                ("added_field", "optional ")
+            } else {
+                ("new_required_field", "required ")
+            };
+            self.push(
+                Compatibility::Breaking,
+                kind,
+                path,
+                &new_field.name.span,
+                format!(
+                    "new {}{} `{}: {}` in `{}`",
+                    adjective,
+                    noun,
+                    name,
+                    canonical(&new.stack, &new_field.typ),
+                    path
+                ),
+            );
+        }
+
+        // fields both versions have, in the order of each
+        let kept = |from: &'a ast::StructDecl, other: &ast::StructDecl| -> Vec<&'a ast::Field> {
+            let fields = from.fields.iter();
+            fields
+                .filter(|f| other.fields.iter().any(|o| o.name.text() == f.name.text()))
+                .collect()
+        };
+        let mut pairs = kept(new.inner, old.inner)
+            .into_iter()
+            .zip(kept(old.inner, new.inner));
+        let moved = pairs.find(|(n, o)| n.name.text() != o.name.text());
+        if let Some((field, _)) = moved {
+            self.push(
+                Compatibility::Breaking,
+                "reordered_fields",
+                path,
+                &field.name.span,
+                format!(
+                    "{}s of `{}` were reordered, starting with `{}`",
+                    noun,
+                    path,
+                    field.name.text()
+                ),
+            );
+        }
+    }
+
+    /// Reports items that both versions have, but at different indexes:
+    /// peers with the old schema would take them for other items.
+    fn diff_positions(
+        &mut self,
+        kind: &'static str,
+        noun: &str,
+        path: &str,
+        old: &[(String, Span)],
+        new: &[(String, Span)],
+    ) {
+        for (new_index, (name, span)) in new.iter().enumerate() {
+            let old_index = match old.iter().position(|(n, _)| n == name) {
+                Some(i) => i,
+                None => continue,
+            };
+            if old_index != new_index {
+                let (path, of) = if path.is_empty() {
+                    (name.as_str(), String::new())
+                } else {
+                    (path, format!(" of `{}`", path))
+                };
+                self.push(
+                    Compatibility::Breaking,
+                    kind,
+                    path,
+                    span,
+                    format!(
+                        "{} `{}`{} moved from index {} to {}",
+                        noun, name, of, old_index, new_index
+                    ),
+                );
+            }
+        }
+    }
+
+    fn diff_enum(&mut self, path: &str, old: &Decl<ast::EnumDecl>, new: &Decl<ast::EnumDecl>) {
+        for old_variant in &old.inner.variants {
+            let name = old_variant.name.text();
+            match new.inner.variants.iter().find(|v| v.name.text() == name) {
+                Some(new_variant) => {
+                    let old_value = old_variant.value.as_ref().map(|v| v.to_string());
+                    let new_value = new_variant.value.as_ref().map(|v| v.to_string());
+                    if old_value != new_value {
+                        let show = |value: Option<String>| value.unwrap_or_else(|| "unset".into());
+                        self.push(
+                            Compatibility::Breaking,
+                            "changed_enum_value",
+                            path,
+                            &new_variant.name.span,
+                            format!(
+                                "value of variant `{}` of `{}` is now {}, it was {}",
+                                name,
+                                path,
+                                show(new_value),
+                                show(old_value)
+                            ),
+                        );
+                    }
+                }
+                None => self.push(
+                    Compatibility::Breaking,
+                    "removed_enum_variant",
+                    path,
+                    &old_variant.name.span,
+                    format!("variant `{}` of `{}` was removed", name, path),
+                ),
+            }
+        }
+
+        for new_variant in &new.inner.variants {
+            let name = new_variant.name.text();
+            if !old.inner.variants.iter().any(|v| v.name.text() == name) {
+                self.push(
+                    Compatibility::Compatible,
+                    "added_enum_variant",
+                    path,
+                    &new_variant.name.span,
+                    format!("variant `{}` of `{}` was added", name, path),
+                );
+            }
+        }
+
+        // explicit values are sent as-is, wherever variants are
+        if old.inner.repr() == ast::EnumRepr::Index && new.inner.repr() == ast::EnumRepr::Index {
+            let names = |en: &ast::EnumDecl| -> Vec<(String, Span)> {
+                let variants = en.variants.iter();
+                variants
+                    .map(|v| (v.name.text().to_string(), v.name.span.clone()))
+                    .collect()
+            };
+            self.diff_positions(
+                "moved_enum_variant",
+                "variant",
+                path,
+                &names(old.inner),
+                &names(new.inner),
+            );
+        }
+    }
+
+    fn diff_union(&mut self, path: &str, old: &Decl<ast::UnionDecl>, new: &Decl<ast::UnionDecl>) {
+        let old_members: Vec<_> = old
+            .inner
+            .members
+            .iter()
+            .map(|m| (canonical(&old.stack, &m.typ), m))
+            .collect();
+        let new_members: Vec<_> = new
+            .inner
+            .members
+            .iter()
+            .map(|m| (canonical(&new.stack, &m.typ), m))
+            .collect();
+
+        for (typ, member) in &old_members {
+            if !new_members.iter().any(|(t, _)| t == typ) {
+                self.push(
+                    Compatibility::Breaking,
+                    "removed_union_member",
+                    path,
+                    &member.typ.span,
+                    format!("member `{}` of `{}` was removed", typ, path),
+                );
+            }
+        }
+        for (typ, member) in &new_members {
+            if !old_members.iter().any(|(t, _)| t == typ) {
+                self.push(
+                    Compatibility::Compatible,
+                    "added_union_member",
+                    path,
+                    &member.typ.span,
+                    format!("member `{}` of `{}` was added", typ, path),
+                );
+            }
+        }
+
+        let names = |members: &[(String, &ast::UnionMember)]| -> Vec<(String, Span)> {
+            let members = members.iter();
+            members
+                .map(|(typ, m)| (typ.clone(), m.typ.span.clone()))
+                .collect()
+        };
+        self.diff_positions(
+            "moved_union_member",
+            "member",
+            path,
+            &names(&old_members),
+            &names(&new_members),
+        );
+    }
+}
+
+fn removed_kind(noun: &str) -> &'static str {
+    match noun {
+        "function" | "notification" => "removed_function",
+        _ => "removed_type",
+    }
+}
+
+fn added_kind(noun: &str) -> &'static str {
+    match noun {
+        "function" | "notification" => "added_function",
+        _ => "added_type",
+    }
+}
+
+fn is_optional(typ: &ast::Type) -> bool {
+    matches!(typ.kind, ast::TypeKind::Option(_))
+}
+
+/// Spells a type out with user types as full names, so that `Person`
+/// and `people.Person` compare equal when they're the same type.
+fn canonical(stack: &ast::Stack, typ: &ast::Type) -> String {
+    match &typ.kind {
+        ast::TypeKind::Base(_) => typ.text().to_string(),
+        ast::TypeKind::User => {
+            let down: Vec<&str> = typ.text().split('.').collect();
+            match stack.lookup_struct(ast::LookupMode::Relaxed, &down[..]) {
+                Some(path) => stack.absolute_names(&path).join("."),
+                // from an import, most likely
+                None => typ.text().to_string(),
+            }
+        }
+        ast::TypeKind::Array(arr) => format!("array<{}>", canonical(stack, &arr.inner)),
+        ast::TypeKind::Option(opt) => format!("option<{}>", canonical(stack, &opt.inner)),
+        ast::TypeKind::Map(map) => format!(
+            "map<{}, {}>",
+            canonical(stack, &map.keys),
+            canonical(stack, &map.values)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use std::path::Path;
+
+    fn parse(name: &str) -> ast::Schema {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/diff/tests")
+            .join(name);
+        parser::parse_schema(parser::Source::from_path(path).unwrap()).unwrap()
+    }
+
+    fn changes(old: &str, new: &str) -> Vec<(Compatibility, &'static str, String)> {
+        diff(&parse(old), &parse(new))
+            .into_iter()
+            .map(|c| (c.compatibility, c.kind, c.path))
+            .collect()
+    }
+
+    fn expect(
+        expected: Vec<(Compatibility, &'static str, &str)>,
+    ) -> Vec<(Compatibility, &'static str, String)> {
+        expected
+            .into_iter()
+            .map(|(c, kind, path)| (c, kind, path.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_changes() {
+        use Compatibility::*;
+        let expected = expect(vec![
+            (Breaking, "removed_function", "people.forget"),
+            (Breaking, "changed_side", "people.greet"),
+            (Breaking, "new_required_field", "people.greet"),
+            (Breaking, "added_field", "people.greet"),
+            (Breaking, "changed_kind", "people.wave"),
+            (Breaking, "removed_field", "people.wave"),
+            (Breaking, "removed_field", "people.lookup"),
+            (Breaking, "removed_field", "people.lookup"),
+            (Breaking, "new_required_field", "people.lookup"),
+            (Compatible, "added_function", "people.poke"),
+            (Breaking, "moved_function", "people.greet"),
+            (Breaking, "moved_function", "people.lookup"),
+            (Breaking, "changed_field_type", "people.Person"),
+            (Breaking, "added_field", "people.Person"),
+            (Breaking, "new_required_field", "people.Person"),
+            (Breaking, "reordered_fields", "people.Person"),
+            (Compatible, "added_type", "people.Group"),
+            (Breaking, "changed_enum_value", "people.Mood"),
+            (Breaking, "removed_enum_variant", "people.Mood"),
+            (Compatible, "added_enum_variant", "people.Mood"),
+            (Breaking, "removed_union_member", "people.Either"),
+            (Compatible, "added_union_member", "people.Either"),
+        ]);
+        assert_eq!(changes("old.lavish", "new.lavish"), expected);
+    }
+
+    #[test]
+    fn reordering_is_breaking() {
+        use Compatibility::*;
+        let expected = expect(vec![
+            (Compatible, "added_function", "shapes.erase"),
+            (Compatible, "added_function", "shapes.undo"),
+            (Breaking, "moved_function", "shapes.draw"),
+            (Breaking, "moved_function", "shapes.clear"),
+            (Breaking, "reordered_fields", "shapes.Point"),
+            (Compatible, "added_enum_variant", "shapes.Color"),
+            (Breaking, "moved_enum_variant", "shapes.Color"),
+            (Breaking, "moved_enum_variant", "shapes.Color"),
+            (Breaking, "moved_union_member", "shapes.Shape"),
+            (Breaking, "moved_union_member", "shapes.Shape"),
+        ]);
+        assert_eq!(
+            changes("reordered_old.lavish", "reordered_new.lavish"),
+            expected
+        );
+    }
+
+    #[test]
+    fn new_optional_fields_are_breaking() {
+        use Compatibility::*;
+        let expected = expect(vec![
+            (Breaking, "added_field", "measure"),
+            (Breaking, "added_field", "measure"),
+            (Breaking, "added_field", "Point"),
+        ]);
+        assert_eq!(changes("arity_old.lavish", "arity_new.lavish"), expected);
+    }
+
+    #[test]
+    fn same_schema_has_no_changes() {
+        let old = parse("old.lavish");
+        assert!(diff(&old, &parse("old.lavish")).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{ast, parser};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::Result;
+
+/// A vendored schema, as it will be recorded in `lavish-lock`
+pub struct Locked {
+    pub name: String,
+    pub from: String,
+    pub revision: Option<String>,
+    pub sha256: String,
+}
+
+/// Reads the workspace's `lavish-lock`, if it has one.
+pub fn read(workspace_dir: &Path) -> Result<Option<ast::Lockfile>> {
+    let path = workspace_dir.join(ast::LOCK_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let source = parser::Source::from_path(&path)?;
+    Ok(Some(parser::parse_lockfile(source)?))
+}
+
+pub fn write(workspace_dir: &Path, entries: &[Locked]) -> Result<()> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// This file is generated by `lavish fetch`, do not edit it by hand."
+    )?;
+    for entry in entries {
+        writeln!(out)?;
+        writeln!(out, "lock {} from {:?} {{", entry.name, entry.from)?;
+        if let Some(revision) = entry.revision.as_ref() {
+            writeln!(out, "    revision {:?}", revision)?;
+        }
+        writeln!(out, "    sha256 {:?}", entry.sha256)?;
+        writeln!(out, "}}")?;
+    }
+
+    fs::write(workspace_dir.join(ast::LOCK_FILE), out)?;
+    Ok(())
+}
+
+/// Returns the hex-encoded SHA-256 of a file's contents.
+pub fn hash(path: &Path) -> Result<String> {
+    let contents = fs::read(path)?;
+    Ok(format!("{:x}", Sha256::digest(&contents)))
+}
+
+/// Makes sure a vendored schema still matches the hash recorded
+/// in `lavish-lock`. Prints a diagnostic and returns false if not.
+pub fn check(
+    lockfile: &ast::Lockfile,
+    name: &ast::Identifier,
+    from: &ast::FromDirective,
+    path: &Path,
+) -> Result<bool> {
+    if !path.exists() {
+        // resolving it will fail with a clearer error.
+        return Ok(true);
+    }
+
+    match lockfile.find(name.text(), from) {
+        None => {
+            name.span
+                .position()
+                .diag_err(format!(
+                    "{} is not in {}. Try running `lavish fetch`",
+                    name.text(),
+                    ast::LOCK_FILE
+                ))
+                .code("not_locked")
+                .print();
+            Ok(false)
+        }
+        Some(entry) => {
+            if hash(path)? == entry.sha256.value {
+                return Ok(true);
+            }
+
+            entry
+                .sha256
+                .loc
+                .position()
+                .diag_err(format!(
+                    "{:?} does not match its locked hash. Try running `lavish fetch`, or `lavish fetch --update` to accept its new contents",
+                    path,
+                ))
+                .code("hash_mismatch")
+                .note(name.span.position(), format!("{} is used here", name.text()))
+                .print();
+            Ok(false)
+        }
+    }
+}
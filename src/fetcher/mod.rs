@@ -0,0 +1,759 @@
+use crate::{ast, parser};
+use log::*;
+use simple_error::SimpleError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub mod lock;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const FILE_SCHEME: &str = "file://";
+
+/// Where the schema of a non-local build can be found.
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    /// A schema file, a directory or a git repository (bare or not)
+    /// on disk, given either as a `file://` URL or as a path that
+    /// exists relative to the workspace.
+    Local(PathBuf),
+    /// `host/user/project`, cloned over https, then ssh.
+    Remote { host: String, project: String },
+}
+
+impl Source {
+    pub fn parse(workspace_dir: &Path, spec: &str) -> Result<Self> {
+        if let Some(path) = spec.strip_prefix(FILE_SCHEME) {
+            return Ok(Source::Local(workspace_dir.join(path)));
+        }
+
+        let path = workspace_dir.join(spec);
+        if path.exists() {
+            return Ok(Source::Local(path));
+        }
+
+        let mut tokens = spec.splitn(2, '/');
+        match (tokens.next(), tokens.next()) {
+            (Some(host), Some(project)) if !host.is_empty() && !project.is_empty() => {
+                Ok(Source::Remote {
+                    host: host.into(),
+                    project: project.trim_end_matches(".git").into(),
+                })
+            }
+            _ => Err(Box::new(SimpleError::new(format!(
+                "{:?}: not a file:// URL, an existing path, or host/user/project",
+                spec
+            )))),
+        }
+    }
+
+    /// Returns the URLs `git clone` should try, in order.
+    pub fn clone_urls(&self) -> Vec<String> {
+        match self {
+            Source::Local(path) => vec![path.to_string_lossy().into()],
+            Source::Remote { host, project } => vec![
+                format!("https://{}/{}.git", host, project),
+                format!("git@{}:{}.git", host, project),
+            ],
+        }
+    }
+}
+
+/// Vendors the schema of every non-local build in `rules`, and of
+/// everything they import, into the workspace's `lavish-vendor`
+/// directory, and records them in `lavish-lock`.
+///
+/// Sources that are already locked are fetched at their locked revision,
+/// and must still match their locked hash, unless `update` is set.
+pub fn fetch(
+    opts: &crate::Opts,
+    workspace_dir: &Path,
+    rules: &ast::Rules,
+    update: bool,
+) -> Result<()> {
+    let mut fetcher = Fetcher {
+        opts,
+        workspace: ast::Workspace {
+            dir: workspace_dir.into(),
+            rules: rules.clone(),
+            members: BTreeMap::new(),
+        },
+        lockfile: if update {
+            None
+        } else {
+            lock::read(workspace_dir)?
+        },
+        locked: Vec::new(),
+        visited: Vec::new(),
+    };
+
+    for build in &rules.builds {
+        fetcher.visit(workspace_dir, &build.name, build.from.as_ref())?;
+    }
+
+    lock::write(workspace_dir, &fetcher.locked)?;
+    Ok(())
+}
+
+struct Fetcher<'a> {
+    opts: &'a crate::Opts,
+    workspace: ast::Workspace,
+    lockfile: Option<ast::Lockfile>,
+    locked: Vec<lock::Locked>,
+    /// Schemas whose imports were already visited
+    visited: Vec<PathBuf>,
+}
+
+impl<'a> Fetcher<'a> {
+    /// Vendors a build or an import if it isn't local, then does the
+    /// same for everything its schema imports.
+    fn visit(
+        &mut self,
+        base_dir: &Path,
+        name: &ast::Identifier,
+        from: Option<&ast::FromDirective>,
+    ) -> Result<()> {
+        if let Some(from) = from {
+            if !from.is_local() && !self.locked.iter().any(|l| l.name == name.text()) {
+                self.fetch_one(name.text(), from)?;
+            }
+        }
+
+        // local sources resolve whether they exist or not
+        let resolved = self.workspace.resolve_source(base_dir, name, from);
+        let path = match resolved.ok().filter(|path| path.exists()) {
+            Some(path) => path.canonicalize()?,
+            None => {
+                self.report_unresolved(base_dir, name);
+                return Err(Box::new(SimpleError::new(format!(
+                    "{}: not found",
+                    name.text()
+                ))));
+            }
+        };
+        if self.visited.contains(&path) {
+            return Ok(());
+        }
+        self.visited.push(path.clone());
+
+        let source = parser::Source::from_path(&path)?;
+        let schema = parser::parse_schema(source)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for import in &schema.imports {
+            self.visit(base_dir, &import.name, import.from.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Only the schema of a remote source is vendored, so whatever it
+    /// imports from its own repository can't be resolved: say so.
+    fn report_unresolved(&self, base_dir: &Path, name: &ast::Identifier) {
+        let vendor_dir = self.workspace.dir.join(ast::VENDOR_DIR);
+        let vendored = match (base_dir.canonicalize(), vendor_dir.canonicalize()) {
+            (Ok(base_dir), Ok(vendor_dir)) => base_dir == vendor_dir,
+            _ => false,
+        };
+        let message = if vendored {
+            format!(
+                "{} not found: only the schema of a remote source is vendored, not the schemas it imports from its own repository",
+                name.text()
+            )
+        } else {
+            format!("{} not found", name.text())
+        };
+        name.span
+            .position()
+            .diag_err(message)
+            .code("unresolved_import")
+            .print();
+    }
+
+    fn fetch_one(&mut self, name: &str, from: &ast::FromDirective) -> Result<()> {
+        let workspace_dir = &self.workspace.dir;
+        let vendor_dir = workspace_dir.join(ast::VENDOR_DIR);
+        let pinned = self.lockfile.as_ref().and_then(|l| l.find(name, from));
+
+        println!("Fetching {} from {}", name, from.path.value);
+        let source = Source::parse(workspace_dir, &from.path.value)?;
+        let checkout = Checkout::new(self.opts, name, &source)?;
+        let revision = match pinned.and_then(|e| e.revision.as_ref()) {
+            Some(revision) => {
+                checkout.checkout(&revision.value)?;
+                Some(revision.value.clone())
+            }
+            None => checkout.revision()?,
+        };
+        let schema_path = find_schema(&checkout.dir, name)?;
+        let sha256 = lock::hash(&schema_path)?;
+
+        if let Some(entry) = pinned {
+            if entry.sha256.value != sha256 {
+                entry
+                    .sha256
+                    .loc
+                    .position()
+                    .diag_err(format!(
+                        "{} has changed since it was locked. Run `lavish fetch --update` to accept its new contents",
+                        name,
+                    ))
+                    .code("hash_mismatch")
+                    .print();
+                return Err(Box::new(SimpleError::new(format!(
+                    "{}: locked hash mismatch",
+                    name
+                ))));
+            }
+        }
+
+        let vendor_path = vendor_dir.join(format!("{}{}", name, ast::LAVISH_EXT));
+        if self.opts.verbose {
+            println!("Vendoring {:?} as {:?}", schema_path, vendor_path);
+        }
+        fs::create_dir_all(&vendor_dir)?;
+        fs::copy(&schema_path, &vendor_path)?;
+
+        self.locked.push(lock::Locked {
+            name: name.into(),
+            from: from.path.value.clone(),
+            revision,
+            sha256,
+        });
+        Ok(())
+    }
+}
+
+/// A directory (or file) holding the contents of a source. Clones
+/// live in a temporary directory, which is removed on drop.
+struct Checkout {
+    dir: PathBuf,
+    cloned: bool,
+}
+
+impl Checkout {
+    fn new(opts: &crate::Opts, name: &str, source: &Source) -> Result<Self> {
+        if let Source::Local(path) = source {
+            if !path.exists() {
+                return Err(Box::new(SimpleError::new(format!(
+                    "{:?}: no such file or directory",
+                    path
+                ))));
+            }
+            if !is_git_repo(path) {
+                return Ok(Self {
+                    dir: path.clone(),
+                    cloned: false,
+                });
+            }
+        }
+
+        let dir =
+            std::env::temp_dir().join(format!("lavish-fetch-{}-{}", std::process::id(), name));
+        let checkout = Self { dir, cloned: true };
+
+        let mut errors = Vec::new();
+        for url in source.clone_urls() {
+            if checkout.dir.exists() {
+                fs::remove_dir_all(&checkout.dir)?;
+            }
+
+            if opts.verbose {
+                println!("Cloning {}", url);
+            }
+            let output = Command::new("git")
+                .arg("clone")
+                .arg("--quiet")
+                .arg(&url)
+                .arg(&checkout.dir)
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .output()?;
+            if output.status.success() {
+                return Ok(checkout);
+            }
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            debug!("Cloning {} failed: {}", url, stderr);
+            errors.push(format!("{}: {}", url, stderr.trim()));
+        }
+
+        Err(Box::new(SimpleError::new(format!(
+            "could not clone {}:\n{}",
+            name,
+            errors.join("\n")
+        ))))
+    }
+
+    /// Returns the revision that was checked out, for git sources.
+    fn revision(&self) -> Result<Option<String>> {
+        if !self.cloned {
+            return Ok(None);
+        }
+        Ok(Some(self.git(&["rev-parse", "HEAD"])?))
+    }
+
+    /// Checks out a specific revision, for git sources.
+    fn checkout(&self, revision: &str) -> Result<()> {
+        if !self.cloned {
+            return Err(Box::new(SimpleError::new(format!(
+                "{:?} is not a git repository, cannot check out revision {}",
+                self.dir, revision
+            ))));
+        }
+        self.git(&["checkout", "--quiet", revision])?;
+        Ok(())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()?;
+        if !output.status.success() {
+            return Err(Box::new(SimpleError::new(format!(
+                "git {}: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().into())
+    }
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        if self.cloned && self.dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&self.dir) {
+                warn!("Could not remove {:?}: {}", self.dir, e);
+            }
+        }
+    }
+}
+
+fn is_git_repo(path: &Path) -> bool {
+    // non-bare repos have a `.git` folder, bare ones are the `.git` folder.
+    path.join(".git").exists() || (path.join("HEAD").is_file() && path.join("objects").is_dir())
+}
+
+/// Finds the schema for `name` in a checkout. If it has a top-level
+/// `lavish-rules` with exports, that's what `name` refers to. Otherwise,
+/// it's `name.lavish` closest to the root, or the only schema there is
+/// if no file is named after the build.
+fn find_schema(dir: &Path, name: &str) -> Result<PathBuf> {
+    if dir.is_file() {
+        return Ok(dir.into());
+    }
+
+    let rules_path = dir.join(ast::RULES_FILE);
+    if rules_path.exists() {
+        let rules = parser::parse_rules(parser::Source::from_path(&rules_path)?)?;
+        if !rules.exports.is_empty() {
+            return match rules.find_export(name) {
+                Some(export) => Ok(dir.join(&export.path.value)),
+                None => Err(Box::new(SimpleError::new(format!(
+                    "{} is not exported by {:?} (it exports: {})",
+                    name,
+                    rules_path,
+                    rules
+                        .exports
+                        .iter()
+                        .map(|e| e.name.text())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )))),
+            };
+        }
+    }
+
+    let mut schemas = Vec::new();
+    collect_schemas(dir, &mut schemas)?;
+    schemas.sort_by_key(|path| (path.components().count(), path.clone()));
+
+    let file_name = format!("{}{}", name, ast::LAVISH_EXT);
+    if let Some(path) = schemas
+        .iter()
+        .find(|path| path.file_name().map(|n| n == file_name.as_str()) == Some(true))
+    {
+        return Ok(path.clone());
+    }
+
+    match schemas.len() {
+        1 => Ok(schemas.remove(0)),
+        0 => Err(Box::new(SimpleError::new(format!(
+            "no schema found for {} (looked for {:?})",
+            name, file_name
+        )))),
+        _ => Err(Box::new(SimpleError::new(format!(
+            "no {:?} found, and several candidate schemas for {}: {:?}",
+            file_name, name, schemas
+        )))),
+    }
+}
+
+fn collect_schemas(dir: &Path, schemas: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() {
+            // skip `.git`, and other people's vendored schemas
+            if file_name.starts_with('.') || file_name == ast::VENDOR_DIR {
+                continue;
+            }
+            collect_schemas(&path, schemas)?;
+        } else if file_name.ends_with(ast::LAVISH_EXT) {
+            schemas.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_parse() -> Result<()> {
+        let dir = Path::new("/workspace");
+
+        assert_eq!(
+            Source::parse(dir, "github.com/fasterthanlime/clock")?,
+            Source::Remote {
+                host: "github.com".into(),
+                project: "fasterthanlime/clock".into(),
+            }
+        );
+        assert_eq!(
+            Source::parse(dir, "file:///srv/git/clock.git")?,
+            Source::Local("/srv/git/clock.git".into()),
+        );
+        assert_eq!(
+            Source::parse(dir, "file://../clock")?,
+            Source::Local("/workspace/../clock".into()),
+        );
+        assert!(Source::parse(dir, "clock").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn source_clone_urls() -> Result<()> {
+        let source = Source::parse(Path::new("."), "github.com/fasterthanlime/clock.git")?;
+        assert_eq!(
+            source.clone_urls(),
+            vec![
+                "https://github.com/fasterthanlime/clock.git",
+                "git@github.com:fasterthanlime/clock.git",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_schema_exports() -> Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("fetcher")
+            .join("tests")
+            .join("exports");
+
+        assert_eq!(
+            find_schema(&dir, "calendar")?,
+            dir.join("./services/calendar.lavish")
+        );
+        // without exports, there would be two candidates
+        assert!(find_schema(&dir, "weather").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_schema_single_export() -> Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("fetcher")
+            .join("tests")
+            .join("single_export");
+
+        assert_eq!(
+            find_schema(&dir, "clock")?,
+            dir.join("./services/clock.lavish")
+        );
+        // the only export is not a stand-in for other names
+        let err = find_schema(&dir, "calendar").unwrap_err().to_string();
+        assert!(err.starts_with("calendar is not exported by"), "{}", err);
+        assert!(err.ends_with("(it exports: clock)"), "{}", err);
+
+        Ok(())
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=lavish",
+                "-c",
+                "user.email=lavish@example.org",
+            ])
+            .args(args)
+            .output()?;
+        if !output.status.success() {
+            return Err(Box::new(SimpleError::new(format!(
+                "git {}: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().into())
+    }
+
+    /// A bare git repository holding `{name}.lavish`, and a workspace
+    /// that builds `name` from it, in a temporary directory that's
+    /// removed on drop. Tests run in parallel, so each needs its own name.
+    struct Remote {
+        name: String,
+        dir: PathBuf,
+    }
+
+    impl Remote {
+        fn new(name: &str, schema: &str) -> Result<Self> {
+            let dir = std::env::temp_dir().join(format!(
+                "lavish-fetch-test-{}-{}",
+                std::process::id(),
+                name
+            ));
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            fs::create_dir_all(dir.join("workspace"))?;
+            let remote = Self {
+                name: name.into(),
+                dir,
+            };
+
+            let repo = format!("{}.git", name);
+            git(&remote.dir, &["init", "--quiet", "--bare", &repo])?;
+            git(&remote.dir, &["clone", "--quiet", &repo, "work"])?;
+            remote.commit(schema)?;
+
+            fs::write(
+                remote.workspace().join(ast::RULES_FILE),
+                format!(
+                    "target rust\n\nbuild {} from \"file://../{}\"\n",
+                    name, repo
+                ),
+            )?;
+            Ok(remote)
+        }
+
+        fn workspace(&self) -> PathBuf {
+            self.dir.join("workspace")
+        }
+
+        fn vendored(&self) -> Result<String> {
+            let path = self.workspace().join(ast::VENDOR_DIR).join(format!(
+                "{}{}",
+                self.name,
+                ast::LAVISH_EXT
+            ));
+            Ok(fs::read_to_string(path)?)
+        }
+
+        /// Commits a new version of the schema, and returns its revision
+        fn commit(&self, schema: &str) -> Result<String> {
+            let work = self.dir.join("work");
+            fs::write(
+                work.join(format!("{}{}", self.name, ast::LAVISH_EXT)),
+                schema,
+            )?;
+            git(&work, &["add", "."])?;
+            git(&work, &["commit", "--quiet", "-m", "Update schema"])?;
+            git(&work, &["push", "--quiet", "origin", "HEAD"])?;
+            git(&work, &["rev-parse", "HEAD"])
+        }
+
+        fn fetch(&self, update: bool) -> Result<()> {
+            let opts = crate::Opts { verbose: false };
+            let rules = crate::workspace::parse_rules(&self.workspace())?;
+            fetch(&opts, &self.workspace(), &rules, update)
+        }
+    }
+
+    impl Drop for Remote {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.dir).ok();
+        }
+    }
+
+    const CLOCK_V1: &str = "server fn current_time() -> (time: timestamp)\n";
+
+    #[test]
+    fn fetch_from_bare_repo() -> Result<()> {
+        let remote = Remote::new("clock", CLOCK_V1)?;
+        remote.fetch(false)?;
+        assert_eq!(remote.vendored()?, CLOCK_V1);
+
+        let lockfile = lock::read(&remote.workspace())?.expect("lavish-lock to be written");
+        assert_eq!(lockfile.entries.len(), 1);
+        let entry = &lockfile.entries[0];
+        assert_eq!(entry.name.text(), "clock");
+        assert_eq!(entry.from.path.value, "file://../clock.git");
+        let head = git(&remote.dir.join("work"), &["rev-parse", "HEAD"])?;
+        assert_eq!(
+            entry.revision.as_ref().map(|r| r.value.as_str()),
+            Some(head.as_str())
+        );
+
+        // the build picks up the vendored schema
+        let opts = crate::Opts { verbose: false };
+        let workspace = crate::workspace::parse_workspace(&opts, &remote.workspace())?;
+        assert!(workspace.members["clock"].schema.is_some());
+
+        Ok(())
+    }
+
+    const CLOCK_V2: &str = "server fn current_time() -> (time: timestamp, zone: string)\n";
+
+    fn locked_revision(remote: &Remote) -> Result<String> {
+        let lockfile = lock::read(&remote.workspace())?.expect("lavish-lock to be written");
+        Ok(lockfile.entries[0].revision.as_ref().unwrap().value.clone())
+    }
+
+    #[test]
+    fn fetch_keeps_locked_revision() -> Result<()> {
+        let remote = Remote::new("moved_clock", CLOCK_V1)?;
+        remote.fetch(false)?;
+        let v1 = locked_revision(&remote)?;
+
+        // the branch moves on, but the lock doesn't
+        let v2 = remote.commit(CLOCK_V2)?;
+        remote.fetch(false)?;
+        assert_eq!(remote.vendored()?, CLOCK_V1);
+        assert_eq!(locked_revision(&remote)?, v1);
+
+        remote.fetch(true)?;
+        assert_eq!(remote.vendored()?, CLOCK_V2);
+        assert_eq!(locked_revision(&remote)?, v2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_rejects_rewritten_history() -> Result<()> {
+        let remote = Remote::new("rewritten_clock", CLOCK_V1)?;
+        remote.fetch(false)?;
+
+        // the locked revision is gone from the repository
+        let work = remote.dir.join("work");
+        fs::write(work.join("rewritten_clock.lavish"), CLOCK_V2)?;
+        git(
+            &work,
+            &["commit", "--quiet", "--all", "--amend", "-m", "Rewrite"],
+        )?;
+        git(&work, &["push", "--quiet", "--force", "origin", "HEAD"])?;
+        git(
+            &remote.dir.join("rewritten_clock.git"),
+            &["gc", "--quiet", "--prune=now"],
+        )?;
+
+        let err = remote.fetch(false).unwrap_err().to_string();
+        assert!(err.starts_with("git checkout"), "{}", err);
+        assert_eq!(remote.vendored()?, CLOCK_V1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_rejects_hash_mismatch() -> Result<()> {
+        use crate::parser::sink;
+
+        let remote = Remote::new("tampered_clock", CLOCK_V1)?;
+        remote.fetch(false)?;
+
+        // the locked revision doesn't have the locked contents
+        let lock_path = remote.workspace().join(ast::LOCK_FILE);
+        let hash = lock::hash(&remote.dir.join("work").join("tampered_clock.lavish"))?;
+        let contents = fs::read_to_string(&lock_path)?;
+        fs::write(&lock_path, contents.replace(&hash, &"0".repeat(64)))?;
+
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = remote.fetch(false);
+        sink::set(Box::new(sink::Colored));
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "tampered_clock: locked hash mismatch"
+        );
+
+        let records = collector.take();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].code, Some("hash_mismatch"));
+        assert_eq!(records[0].file, lock_path.to_string_lossy());
+        assert_eq!(records[0].start.line, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_rejects_unresolved_imports() -> Result<()> {
+        use crate::parser::sink;
+
+        let remote = Remote::new(
+            "zoned_clock",
+            "import tz from \"./tz.lavish\"\n\nserver fn current_time() -> (time: timestamp)\n",
+        )?;
+
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = remote.fetch(false);
+        sink::set(Box::new(sink::Colored));
+        assert_eq!(res.unwrap_err().to_string(), "tz: not found");
+
+        let records = collector.take();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].code, Some("unresolved_import"));
+        assert!(records[0].file.ends_with("zoned_clock.lavish"));
+        assert_eq!(records[0].start.line, 0);
+        assert!(
+            records[0]
+                .message
+                .starts_with("tz not found: only the schema"),
+            "{}",
+            records[0].message
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_rejects_hash_mismatch() -> Result<()> {
+        use crate::parser::sink;
+
+        let remote = Remote::new("edited_clock", CLOCK_V1)?;
+        remote.fetch(false)?;
+
+        // someone edits the vendored schema by hand
+        let vendor_path = remote
+            .workspace()
+            .join(ast::VENDOR_DIR)
+            .join("edited_clock.lavish");
+        fs::write(&vendor_path, CLOCK_V2)?;
+
+        let opts = crate::Opts { verbose: false };
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = crate::workspace::parse_workspace(&opts, &remote.workspace());
+        sink::set(Box::new(sink::Colored));
+        assert!(res.is_err());
+
+        let records = collector.take();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].code, Some("hash_mismatch"));
+        assert_eq!(records[0].notes[0].message, "edited_clock is used here");
+
+        Ok(())
+    }
+}
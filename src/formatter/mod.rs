@@ -0,0 +1,348 @@
+use crate::ast;
+use crate::codegen::{self, Scope};
+use crate::parser;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Reprints a schema in the canonical style: four spaces of indentation,
+/// one item, field or variant per line with trailing commas, and at most
+/// one blank line in a row. Comments are kept where they were.
+pub fn schema(schema: &ast::Schema) -> String {
+    let mut output = String::new();
+    {
+        let mut s = Scope::new(&mut output);
+        for import in &schema.imports {
+            s.write("import ").write(import.name.text());
+            if let Some(from) = import.from.as_ref() {
+                s.write(" from ").write(string(&from.path));
+            }
+            s.lf();
+        }
+        if !schema.imports.is_empty() && !trim(&schema.body.layout, is_blank_item).is_empty() {
+            s.lf();
+        }
+        write_body(&mut s, &schema.body);
+    }
+    output
+}
+
+/// Reprints a `lavish-rules` file: its target first, then directives
+/// in their original order, along with comments and blank lines.
+pub fn rules(rules: &ast::Rules) -> String {
+    let mut output = String::new();
+    {
+        let mut s = Scope::new(&mut output);
+        if let Some(target) = rules.target.as_ref() {
+            write_target(&mut s, target);
+        }
+        let layout = trim(&rules.layout, is_blank_directive);
+        if rules.target.is_some() && !layout.is_empty() {
+            s.lf();
+        }
+        for (i, entry) in layout.iter().enumerate() {
+            match entry {
+                ast::RulesLayout::Build(j) => {
+                    let build = &rules.builds[*j];
+                    s.write("build ").write(build.name.text());
+                    if let Some(from) = build.from.as_ref() {
+                        s.write(" from ").write(string(&from.path));
+                    }
+                    s.lf();
+                }
+                ast::RulesLayout::Export(j) => {
+                    let export = &rules.exports[*j];
+                    s.line(format!(
+                        "export {} as {}",
+                        string(&export.path),
+                        export.name.text()
+                    ));
+                }
+                ast::RulesLayout::Lint(j) => {
+                    let lint = &rules.lints[*j];
+                    s.line(format!("{} {}", lint.severity, lint.id.text()));
+                }
+                ast::RulesLayout::Trivia(trivia) => {
+                    write_trivia(&mut s, trivia, i > 0 && is_blank_directive(&layout[i - 1]))
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Formats a schema or rules file in place, or in check mode, prints
+/// how it differs from its canonical form.
+pub fn format_file(path: &Path, files: &codegen::Files) -> Result<()> {
+    let source = parser::Source::from_path(path)?;
+    let formatted = if path.file_name() == Some(ast::RULES_FILE.as_ref()) {
+        rules(&parser::parse_rules(source.clone())?)
+    } else {
+        schema(&parser::parse_schema_syntax(source.clone())?)
+    };
+    if formatted != source.input {
+        files.save(path, &formatted)?;
+    }
+    Ok(())
+}
+
+/// Returns the files `lavish fmt` formats for `path`: the path itself
+/// if it's a file, or every schema and rules file it contains.
+pub fn collect_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut res = Vec::new();
+    if path.is_dir() {
+        collect_dir(path, &mut res)?;
+        res.sort();
+    } else {
+        res.push(path.into());
+    }
+    Ok(res)
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() {
+            // skip `.git`, and other people's vendored schemas
+            if file_name.starts_with('.') || file_name == ast::VENDOR_DIR {
+                continue;
+            }
+            collect_dir(&path, files)?;
+        } else if file_name.ends_with(ast::LAVISH_EXT) || file_name == ast::RULES_FILE {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn write_target(s: &mut Scope, target: &ast::Target) {
+    match target {
+        ast::Target::Rust(rust) => {
+            s.write("target rust");
+            match rust.wrapper {
+                // the default, no need to spell it out
+                ast::RustTargetWrapper::Mod => s.lf(),
+                ast::RustTargetWrapper::Lib => s.in_block(|s| s.line("wrapper = lib")),
+                ast::RustTargetWrapper::None => s.in_block(|s| s.line("wrapper = none")),
+            }
+        }
+        ast::Target::Go(_) => s.line("target go"),
+        ast::Target::TypeScript(_) => s.line("target typescript"),
+    }
+}
+
+fn write_body(s: &mut Scope, body: &ast::NamespaceBody) {
+    use ast::NamespaceLayout as L;
+
+    let layout = trim(&body.layout, is_blank_item);
+    for (i, entry) in layout.iter().enumerate() {
+        match entry {
+            L::Function(j) => write_function(s, &body.functions[*j]),
+            L::Struct(j) => write_struct(s, &body.structs[*j]),
+            L::Enum(j) => write_enum(s, &body.enums[*j]),
+            L::Union(j) => write_union(s, &body.unions[*j]),
+            L::Namespace(j) => {
+                let ns = &body.namespaces[*j];
+                write_comment(s, &ns.comment);
+                s.write("namespace ").write(ns.name.text());
+                write_block(s, &ns.body);
+            }
+            L::Trivia(trivia) => write_trivia(s, trivia, i > 0 && is_blank_item(&layout[i - 1])),
+        }
+    }
+}
+
+/// Writes a ` { ... }` block for a namespace or function body, or
+/// ` {}` if it's empty.
+fn write_block(s: &mut Scope, body: &ast::NamespaceBody) {
+    if trim(&body.layout, is_blank_item).is_empty() {
+        s.line(" {}");
+    } else {
+        s.in_block(|s| write_body(s, body));
+    }
+}
+
+fn write_trivia(s: &mut Scope, trivia: &ast::Trivia, after_blank: bool) {
+    match trivia {
+        ast::Trivia::Comment(comment) => write_comment(s, &Some(comment.clone())),
+        ast::Trivia::BlankLine if !after_blank => s.lf(),
+        ast::Trivia::BlankLine => {}
+    }
+}
+
+fn write_comment(s: &mut Scope, comment: &Option<ast::Comment>) {
+    if let Some(comment) = comment.as_ref() {
+        for (i, line) in comment.lines.iter().enumerate() {
+            if comment.breaks.contains(&i) {
+                s.lf();
+            }
+            if line.is_empty() {
+                s.line("//");
+            } else {
+                s.line(format!("// {}", line));
+            }
+        }
+        if comment.breaks.contains(&comment.lines.len()) {
+            s.lf();
+        }
+    }
+}
+
+fn write_function(s: &mut Scope, f: &ast::FunctionDecl) {
+    write_comment(s, &f.comment);
+    let keyword = match f.kind {
+        ast::Kind::Request => "fn",
+        ast::Kind::Notification => "nf",
+    };
+    s.write(format!("{} {} {}", f.side, keyword, f.name.text()));
+    write_fields(s, &f.params.fields);
+    if !f.results.fields.is_empty() {
+        s.write(" -> ");
+        write_fields(s, &f.results.fields);
+    }
+    match f.body.as_ref() {
+        Some(body) => write_block(s, body),
+        None => s.lf(),
+    }
+}
+
+/// Writes a parameter or result list on a single line, unless one of
+/// the fields has a comment.
+fn write_fields(s: &mut Scope, fields: &[ast::Field]) {
+    if fields.iter().any(|f| f.comment.is_some()) {
+        s.line("(");
+        s.in_scope(|s| {
+            for field in fields {
+                write_field(s, field);
+            }
+        });
+        s.write(")");
+    } else {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|f| format!("{}: {}", f.name.text(), typ(&f.typ)))
+            .collect();
+        s.write(format!("({})", fields.join(", ")));
+    }
+}
+
+fn write_field(s: &mut Scope, field: &ast::Field) {
+    write_comment(s, &field.comment);
+    s.line(format!("{}: {},", field.name.text(), typ(&field.typ)));
+}
+
+fn write_struct(s: &mut Scope, st: &ast::StructDecl) {
+    write_comment(s, &st.comment);
+    s.write("struct ").write(st.name.text());
+    if st.fields.is_empty() {
+        s.line(" {}");
+    } else {
+        s.in_block(|s| {
+            for field in &st.fields {
+                write_field(s, field);
+            }
+        });
+    }
+}
+
+fn write_enum(s: &mut Scope, en: &ast::EnumDecl) {
+    write_comment(s, &en.comment);
+    s.write("enum ").write(en.name.text());
+    if en.variants.is_empty() {
+        s.line(" {}");
+    } else {
+        s.in_block(|s| {
+            for variant in &en.variants {
+                write_comment(s, &variant.comment);
+                s.write(variant.name.text());
+                if let Some(value) = variant.value.as_ref() {
+                    s.write(" = ").write(value);
+                }
+                s.line(",");
+            }
+        });
+    }
+}
+
+fn write_union(s: &mut Scope, un: &ast::UnionDecl) {
+    write_comment(s, &un.comment);
+    s.write("union ").write(un.name.text());
+    if un.members.is_empty() {
+        s.line(" {}");
+    } else {
+        s.in_block(|s| {
+            for member in &un.members {
+                write_comment(s, &member.comment);
+                s.line(format!("{},", member.typ.text()));
+            }
+        });
+    }
+}
+
+/// Reprints a type, normalizing the whitespace inside of it, so
+/// `map < string,i32 >` becomes `map<string, i32>`.
+fn typ(t: &ast::Type) -> String {
+    match &t.kind {
+        ast::TypeKind::Base(_) | ast::TypeKind::User => t.text().to_string(),
+        ast::TypeKind::Array(arr) => format!("array<{}>", typ(&arr.inner)),
+        ast::TypeKind::Option(opt) => format!("option<{}>", typ(&opt.inner)),
+        ast::TypeKind::Map(map) => format!("map<{}, {}>", typ(&map.keys), typ(&map.values)),
+    }
+}
+
+fn string(lit: &ast::StringLiteral) -> String {
+    format!("{:?}", lit.value)
+}
+
+fn is_blank_item(entry: &ast::NamespaceLayout) -> bool {
+    matches!(entry, ast::NamespaceLayout::Trivia(ast::Trivia::BlankLine))
+}
+
+fn is_blank_directive(entry: &ast::RulesLayout) -> bool {
+    matches!(entry, ast::RulesLayout::Trivia(ast::Trivia::BlankLine))
+}
+
+/// Drops blank lines at the start and at the end of a body
+fn trim<T>(entries: &[T], is_blank: fn(&T) -> bool) -> &[T] {
+    let start = entries.iter().position(|e| !is_blank(e)).unwrap_or(0);
+    let end = entries
+        .iter()
+        .rposition(|e| !is_blank(e))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    &entries[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_fixture(name: &str, format: fn(std::rc::Rc<parser::Source>) -> String) {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/formatter/tests");
+        let input = parser::Source::from_path(dir.join(format!("{}.lavish", name))).unwrap();
+        let expected = fs::read_to_string(dir.join(format!("{}.expected.lavish", name))).unwrap();
+
+        let formatted = format(input);
+        assert_eq!(formatted, expected);
+
+        // formatting is idempotent
+        let again = format(parser::Source::from_string(formatted.clone()));
+        assert_eq!(again, expected);
+    }
+
+    #[test]
+    fn format_schema() {
+        format_fixture("schema", |source| {
+            schema(&parser::parse_schema_syntax(source).unwrap())
+        });
+    }
+
+    #[test]
+    fn format_rules() {
+        format_fixture("rules", |source| {
+            rules(&parser::parse_rules(source).unwrap())
+        });
+    }
+}
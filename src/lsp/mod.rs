@@ -0,0 +1,562 @@
+use crate::ast;
+use crate::checker;
+use crate::parser::{self, sink, Source, Span};
+use serde_json::{json, Value};
+use simple_error::SimpleError;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+mod query;
+mod transport;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Error code for requests the server doesn't handle
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Serves the Language Server Protocol over `input` and `output`,
+/// until the client asks to exit. Documents are checked on their own:
+/// types from imported schemas aren't resolved.
+pub fn run<R: BufRead, W: Write>(mut input: R, output: W) -> Result<()> {
+    let collector = sink::Collector::default();
+    sink::set(Box::new(collector.clone()));
+
+    let mut server = Server {
+        output,
+        collector,
+        documents: HashMap::new(),
+        shut_down: false,
+    };
+    while let Some(message) = transport::read(&mut input)? {
+        if !server.handle(message)? {
+            break;
+        }
+    }
+
+    if server.shut_down {
+        Ok(())
+    } else {
+        Err(Box::new(SimpleError::new(
+            "the client exited without shutting the server down",
+        )))
+    }
+}
+
+struct Server<W> {
+    output: W,
+    /// Where diagnostics go while checking documents
+    collector: sink::Collector,
+    /// Open documents by URI, as last sent by the client
+    documents: HashMap<String, Rc<Source>>,
+    shut_down: bool,
+}
+
+impl<W: Write> Server<W> {
+    /// Handles a message, and returns false once the client asks to exit
+    fn handle(&mut self, message: Value) -> Result<bool> {
+        let params = &message["params"];
+        let method = match message["method"].as_str() {
+            Some(method) => method,
+            // a response, but we never send requests
+            None => return Ok(true),
+        };
+
+        let id = match message.get("id") {
+            Some(id) => id,
+            None => {
+                match method {
+                    "exit" => return Ok(false),
+                    "textDocument/didOpen" => {
+                        let doc = &params["textDocument"];
+                        self.update(doc["uri"].as_str(), doc["text"].as_str())?
+                    }
+                    "textDocument/didChange" => {
+                        // only full syncs are advertised, the last change has everything
+                        let text = params["contentChanges"]
+                            .as_array()
+                            .and_then(|changes| changes.last())
+                            .and_then(|change| change["text"].as_str());
+                        self.update(params["textDocument"]["uri"].as_str(), text)?
+                    }
+                    "textDocument/didClose" => {
+                        if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                            self.documents.remove(uri);
+                            self.publish(uri, Vec::new())?;
+                        }
+                    }
+                    _ => {}
+                }
+                return Ok(true);
+            }
+        };
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": { "triggerCharacters": ["."] },
+                },
+                "serverInfo": { "name": "lavish", "version": crate::VERSION },
+            })),
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => Ok(self.definition(params)),
+            "textDocument/hover" => Ok(self.hover(params)),
+            "textDocument/completion" => Ok(self.completion(params)),
+            _ => Err(format!("unsupported method {:?}", method)),
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(message) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": message },
+            }),
+        };
+        transport::write(&mut self.output, &response)?;
+        Ok(true)
+    }
+
+    /// Stores a new version of a document, and publishes its diagnostics
+    fn update(&mut self, uri: Option<&str>, text: Option<&str>) -> Result<()> {
+        let (uri, text) = match (uri, text) {
+            (Some(uri), Some(text)) => (uri, text),
+            _ => return Ok(()),
+        };
+        let source = Source::new(uri_to_path(uri), text.into());
+        self.documents.insert(uri.into(), source.clone());
+
+        let diagnostics = self
+            .check(source.clone())
+            .iter()
+            .map(|record| diagnostic(&source, uri, record))
+            .collect();
+        self.publish(uri, diagnostics)
+    }
+
+    fn publish(&mut self, uri: &str, diagnostics: Vec<Value>) -> Result<()> {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        });
+        Ok(transport::write(&mut self.output, &notification)?)
+    }
+
+    /// Returns the diagnostics of the parser and the checker, and of
+    /// lints, if the document belongs to a workspace.
+    fn check(&self, source: Rc<Source>) -> Vec<sink::Record> {
+        let path = PathBuf::from(source.name());
+        if path.file_name() == Some(ast::RULES_FILE.as_ref()) {
+            if let Err(parser::Error::Source(errors)) = parser::parse_rules(source.clone()) {
+                for e in errors {
+                    e.diagnostic().print();
+                }
+            }
+        } else {
+            match parser::parse_schema(source.clone()) {
+                Ok(schema) => {
+                    if let Some(levels) = workspace_levels(&path) {
+                        checker::lint(&schema, &levels).ok();
+                    }
+                }
+                Err(parser::Error::Source(errors))
+                | Err(parser::Error::Schema { syntax: errors, .. }) => {
+                    for e in errors {
+                        e.diagnostic().print();
+                    }
+                }
+                // the checker reported its errors already
+                Err(_) => {}
+            }
+        }
+
+        let mut records = self.collector.take();
+        // like those of a lavish-rules file with unknown lints
+        records.retain(|record| record.file == source.name());
+        records
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        self.query(params, |uri, schema, offset| {
+            let (_, symbol) = query::reference_at(schema, offset)?;
+            let name = query::Item::Symbol(symbol).name();
+            Some(json!({ "uri": uri, "range": range(&name.span) }))
+        })
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        self.query(params, |_, schema, offset| {
+            let (span, item) = match query::reference_at(schema, offset) {
+                Some((span, symbol)) => (span, query::Item::Symbol(symbol)),
+                None => {
+                    let item = query::declaration_at(schema, offset)?;
+                    (item.name().span.clone(), item)
+                }
+            };
+            let mut text = format!("```lavish\n{}\n```", item.describe());
+            if let Some(comment) = item.comment() {
+                text.push_str("\n\n");
+                text.push_str(&comment.doc_lines().collect::<Vec<_>>().join("\n"));
+            }
+            Some(json!({
+                "contents": { "kind": "markdown", "value": text },
+                "range": range(&span),
+            }))
+        })
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        self.query(params, |_, schema, offset| {
+            let items: Vec<Value> = query::completions(schema, offset)
+                .into_iter()
+                .map(|completion| {
+                    let (kind, detail, comment) = match completion.item.as_ref() {
+                        None => (COMPLETION_KEYWORD, "built-in type".to_string(), None),
+                        Some(item) => (completion_kind(item), item.describe(), item.comment()),
+                    };
+                    let mut res = json!({
+                        "label": completion.label,
+                        "kind": kind,
+                        "detail": detail,
+                    });
+                    if let Some(comment) = comment {
+                        res["documentation"] =
+                            comment.doc_lines().collect::<Vec<_>>().join("\n").into();
+                    }
+                    res
+                })
+                .collect();
+            Some(items.into())
+        })
+    }
+
+    /// Runs `f` on the document and offset a request points at. The
+    /// schema may be partial, if the document has syntax errors.
+    fn query<F>(&self, params: &Value, f: F) -> Value
+    where
+        F: Fn(&str, &ast::Schema, usize) -> Option<Value>,
+    {
+        let uri = match params["textDocument"]["uri"].as_str() {
+            Some(uri) => uri,
+            None => return Value::Null,
+        };
+        let source = match self.documents.get(uri) {
+            Some(source) => source.clone(),
+            None => return Value::Null,
+        };
+        if Path::new(source.name()).file_name() == Some(ast::RULES_FILE.as_ref()) {
+            return Value::Null;
+        }
+        let offset = match offset(&source, &params["position"]) {
+            Some(offset) => offset,
+            None => return Value::Null,
+        };
+        match parser::parse_schema_recovering(source) {
+            Ok((schema, _)) => f(uri, &schema, offset).unwrap_or(Value::Null),
+            Err(_) => Value::Null,
+        }
+    }
+}
+
+/// Completion item kinds, from the specification
+const COMPLETION_CLASS: i64 = 7;
+const COMPLETION_MODULE: i64 = 9;
+const COMPLETION_KEYWORD: i64 = 14;
+const COMPLETION_ENUM: i64 = 13;
+const COMPLETION_STRUCT: i64 = 22;
+
+fn completion_kind(item: &query::Item) -> i64 {
+    match item {
+        query::Item::Symbol(ast::Symbol::Struct(_)) => COMPLETION_STRUCT,
+        query::Item::Symbol(ast::Symbol::Enum(_)) => COMPLETION_ENUM,
+        query::Item::Symbol(ast::Symbol::Union(_)) => COMPLETION_CLASS,
+        _ => COMPLETION_MODULE,
+    }
+}
+
+/// Finds the `lavish-rules` of the workspace a schema is in, if any,
+/// to lint it with the same levels as `lavish check` would.
+fn workspace_levels(path: &Path) -> Option<checker::Levels> {
+    let dir = path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join(ast::RULES_FILE).is_file())?;
+    let source = Source::from_path(dir.join(ast::RULES_FILE)).ok()?;
+    let rules = parser::parse_rules(source).ok()?;
+    checker::Levels::new(&rules).ok()
+}
+
+fn diagnostic(source: &Source, uri: &str, record: &sink::Record) -> Value {
+    let severity = match record.level {
+        parser::Level::Error => 1,
+        parser::Level::Warning => 2,
+        parser::Level::Info => 3,
+    };
+    let related: Vec<Value> = record
+        .notes
+        .iter()
+        .map(|note| {
+            let (uri, source) = if note.file == record.file {
+                (uri.to_string(), Some(source))
+            } else {
+                (path_to_uri(&note.file), None)
+            };
+            json!({
+                "location": {
+                    "uri": uri,
+                    "range": {
+                        "start": position(source, note.start),
+                        "end": position(source, note.end),
+                    },
+                },
+                "message": note.message,
+            })
+        })
+        .collect();
+    json!({
+        "range": {
+            "start": position(Some(source), record.start),
+            "end": position(Some(source), record.end),
+        },
+        "severity": severity,
+        "code": record.code,
+        "source": "lavish",
+        "message": record.message,
+        "relatedInformation": related,
+    })
+}
+
+fn range(span: &Span) -> Value {
+    let source = &span.source;
+    let end = Span {
+        source: source.clone(),
+        offset: span.offset + span.len,
+        len: 0,
+    };
+    json!({
+        "start": position(Some(source), (&span.position()).into()),
+        "end": position(Some(source), (&end.position()).into()),
+    })
+}
+
+/// Converts a location to an LSP position, which counts columns in
+/// UTF-16 code units, when the text of the line is known.
+fn position(source: Option<&Source>, loc: sink::Location) -> Value {
+    let character = source
+        .and_then(|source| source.lines.get(loc.line))
+        .and_then(|line| line.get(..loc.column))
+        .map(|prefix| prefix.encode_utf16().count())
+        .unwrap_or(loc.column);
+    json!({ "line": loc.line, "character": character })
+}
+
+/// Converts an LSP position to a byte offset into `source`
+fn offset(source: &Source, position: &Value) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+
+    let start: usize = source.lines.iter().take(line).map(|l| l.len() + 1).sum();
+    let text = match source.lines.get(line) {
+        Some(text) => text,
+        // the line after a final newline
+        None if line == source.lines.len() => return Some(start.min(source.input.len())),
+        None => return None,
+    };
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units >= character {
+            return Some(start + i);
+        }
+        units += c.len_utf16();
+    }
+    Some(start + text.len())
+}
+
+/// Returns the path of a `file://` URI, or the URI itself, so that
+/// documents that aren't files still have a name.
+fn uri_to_path(uri: &str) -> String {
+    match uri.strip_prefix("file://") {
+        Some(path) => percent_decode(path),
+        None => uri.to_string(),
+    }
+}
+
+fn path_to_uri(path: &str) -> String {
+    format!("file://{}", path.replace('%', "%25").replace(' ', "%20"))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = s
+            .get(i + 1..i + 3)
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                res.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                res.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&res).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "file:///tmp/lavish-lsp-test/people.lavish";
+
+    const SCHEMA: &str = r#"namespace people {
+    // Someone to greet
+    struct Person {
+        name: string,
+        mood: Mood,
+    }
+
+    enum Mood {
+        Happy,
+        Sad,
+    }
+
+    server fn greet(person: Person) -> (greeting: Greeting)
+}
+"#;
+
+    /// Plays the client: sends `requests`, and returns what the server
+    /// sent back, in order.
+    fn script(requests: Vec<Value>) -> Vec<Value> {
+        let mut input = Vec::new();
+        for request in &requests {
+            transport::write(&mut input, request).unwrap();
+        }
+        let mut output = Vec::new();
+        run(&input[..], &mut output).unwrap();
+
+        let mut output = &output[..];
+        let mut responses = Vec::new();
+        while let Some(response) = transport::read(&mut output).unwrap() {
+            responses.push(response);
+        }
+        responses
+    }
+
+    fn request(id: i64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn notification(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "method": method, "params": params })
+    }
+
+    fn at(line: u64, character: u64) -> Value {
+        json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        })
+    }
+
+    #[test]
+    fn scripted_session() {
+        let responses = script(vec![
+            request(1, "initialize", json!({ "capabilities": {} })),
+            notification("initialized", json!({})),
+            notification(
+                "textDocument/didOpen",
+                json!({
+                    "textDocument": {
+                        "uri": URI,
+                        "languageId": "lavish",
+                        "version": 1,
+                        "text": SCHEMA,
+                    },
+                }),
+            ),
+            // `Person` in `person: Person`
+            request(2, "textDocument/definition", at(12, 30)),
+            request(3, "textDocument/hover", at(12, 30)),
+            // after `name: `
+            request(4, "textDocument/completion", at(3, 14)),
+            request(5, "shutdown", Value::Null),
+            notification("exit", Value::Null),
+        ]);
+        assert_eq!(responses.len(), 6);
+
+        let init = &responses[0];
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["capabilities"]["hoverProvider"], true);
+
+        let diagnostics = &responses[1];
+        assert_eq!(diagnostics["method"], "textDocument/publishDiagnostics");
+        let diagnostics = diagnostics["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["code"], "unknown_type");
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(
+            diagnostics[0]["range"]["start"],
+            json!({ "line": 12, "character": 50 })
+        );
+
+        let definition = &responses[2]["result"];
+        assert_eq!(definition["uri"], URI);
+        assert_eq!(
+            definition["range"],
+            json!({
+                "start": { "line": 2, "character": 11 },
+                "end": { "line": 2, "character": 17 },
+            })
+        );
+
+        let hover = responses[3]["result"]["contents"]["value"]
+            .as_str()
+            .unwrap();
+        assert!(hover.contains("struct Person"), "{}", hover);
+        assert!(hover.contains("Someone to greet"), "{}", hover);
+
+        let labels: Vec<&str> = responses[4]["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+        for label in &["i32", "timestamp", "Person", "Mood", "people"] {
+            assert!(labels.contains(label), "{} not in {:?}", label, labels);
+        }
+
+        assert_eq!(responses[5]["id"], 5);
+        assert_eq!(responses[5]["result"], Value::Null);
+    }
+
+    #[test]
+    fn completes_namespace_paths() {
+        let text = "namespace a {\n    struct B {}\n}\n\nstruct C {\n    b: a.\n}\n";
+        let source = Source::new("test.lavish".into(), text.into());
+        let (schema, _) = parser::parse_schema_recovering(source.clone()).unwrap();
+        let offset = offset(&source, &json!({ "line": 5, "character": 9 })).unwrap();
+        let labels: Vec<String> = query::completions(&schema, offset)
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert_eq!(labels, vec!["B"]);
+    }
+
+    #[test]
+    fn exit_without_shutdown() {
+        let mut input = Vec::new();
+        transport::write(&mut input, &notification("exit", Value::Null)).unwrap();
+        assert!(run(&input[..], Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use crate::ast;
+use crate::parser::Span;
+use std::collections::HashSet;
+
+/// Names of built-in types, and of the generic ones
+pub const BUILTINS: &[&str] = &[
+    "bool",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "f32",
+    "f64",
+    "string",
+    "data",
+    "timestamp",
+    "array",
+    "option",
+    "map",
+];
+
+/// Something the cursor can point at: a type or namespace, either
+/// by name or at its declaration, or a function.
+pub enum Item<'a> {
+    Symbol(ast::Symbol<'a>),
+    Function(&'a ast::FunctionDecl),
+}
+
+impl<'a> Item<'a> {
+    pub fn name(&self) -> &'a ast::Identifier {
+        match self {
+            Item::Symbol(ast::Symbol::Struct(st)) => &st.name,
+            Item::Symbol(ast::Symbol::Enum(en)) => &en.name,
+            Item::Symbol(ast::Symbol::Union(un)) => &un.name,
+            Item::Symbol(ast::Symbol::Namespace(ns)) => &ns.name,
+            Item::Symbol(ast::Symbol::Import(imp)) => &imp.name,
+            Item::Function(f) => &f.name,
+        }
+    }
+
+    pub fn comment(&self) -> Option<&'a ast::Comment> {
+        match self {
+            Item::Symbol(ast::Symbol::Struct(st)) => st.comment.as_ref(),
+            Item::Symbol(ast::Symbol::Enum(en)) => en.comment.as_ref(),
+            Item::Symbol(ast::Symbol::Union(un)) => un.comment.as_ref(),
+            Item::Symbol(ast::Symbol::Namespace(ns)) => ns.comment.as_ref(),
+            Item::Symbol(ast::Symbol::Import(_)) => None,
+            Item::Function(f) => f.comment.as_ref(),
+        }
+    }
+
+    /// What the item is, like `struct Person` or `server fn greet`
+    pub fn describe(&self) -> String {
+        let name = self.name().text();
+        match self {
+            Item::Symbol(ast::Symbol::Struct(_)) => format!("struct {}", name),
+            Item::Symbol(ast::Symbol::Enum(_)) => format!("enum {}", name),
+            Item::Symbol(ast::Symbol::Union(_)) => format!("union {}", name),
+            Item::Symbol(ast::Symbol::Namespace(_)) => format!("namespace {}", name),
+            Item::Symbol(ast::Symbol::Import(imp)) => match imp.from.as_ref() {
+                Some(from) => format!("import {} from {:?}", name, from.path.value),
+                None => format!("import {}", name),
+            },
+            Item::Function(f) => match f.kind {
+                ast::Kind::Request => format!("{} fn {}", f.side, name),
+                ast::Kind::Notification => format!("{} nf {}", f.side, name),
+            },
+        }
+    }
+}
+
+/// A name that can be typed at the cursor. Built-in types have no item.
+pub struct Completion<'a> {
+    pub label: String,
+    pub item: Option<Item<'a>>,
+}
+
+/// Resolves the type under the cursor, returning the span of the part
+/// of its path that's pointed at: in `a.b.C`, pointing at `b` resolves
+/// the namespace `a.b`.
+pub fn reference_at(schema: &ast::Schema, offset: usize) -> Option<(Span, ast::Symbol<'_>)> {
+    let mut types = Vec::new();
+    collect_types(&ast::Stack::new(schema), &schema.body, &mut types);
+
+    for (stack, typ) in types {
+        let typ = match user_type_at(typ, offset) {
+            Some(typ) => typ,
+            None => continue,
+        };
+
+        let mut down = Vec::new();
+        let mut start = typ.span.offset;
+        for part in typ.text().split('.') {
+            down.push(part);
+            if offset <= start + part.len() {
+                let span = Span {
+                    source: typ.span.source.clone(),
+                    offset: start,
+                    len: part.len(),
+                };
+                let path = stack.lookup_struct(ast::LookupMode::Relaxed, &down[..])?;
+                return Some((span, path.symbol));
+            }
+            // 1 accounts for the '.'
+            start += part.len() + 1;
+        }
+    }
+    None
+}
+
+/// Returns the item whose name is under the cursor, where it's declared.
+pub fn declaration_at(schema: &ast::Schema, offset: usize) -> Option<Item<'_>> {
+    for imp in &schema.imports {
+        if contains(&imp.name.span, offset) {
+            return Some(Item::Symbol(ast::Symbol::Import(imp)));
+        }
+    }
+    declaration_in(&schema.body, offset)
+}
+
+fn declaration_in(body: &ast::NamespaceBody, offset: usize) -> Option<Item<'_>> {
+    for st in &body.structs {
+        if contains(&st.name.span, offset) {
+            return Some(Item::Symbol(ast::Symbol::Struct(st)));
+        }
+    }
+    for en in &body.enums {
+        if contains(&en.name.span, offset) {
+            return Some(Item::Symbol(ast::Symbol::Enum(en)));
+        }
+    }
+    for un in &body.unions {
+        if contains(&un.name.span, offset) {
+            return Some(Item::Symbol(ast::Symbol::Union(un)));
+        }
+    }
+    for ns in &body.namespaces {
+        if contains(&ns.name.span, offset) {
+            return Some(Item::Symbol(ast::Symbol::Namespace(ns)));
+        }
+        if let Some(item) = declaration_in(&ns.body, offset) {
+            return Some(item);
+        }
+    }
+    for f in &body.functions {
+        if contains(&f.name.span, offset) {
+            return Some(Item::Function(f));
+        }
+        if let Some(item) = f.body.as_ref().and_then(|b| declaration_in(b, offset)) {
+            return Some(item);
+        }
+    }
+    None
+}
+
+/// Returns what can be typed at the cursor: after a path like `a.b.`,
+/// the members of `a.b`, otherwise built-in types along with everything
+/// in scope, innermost first.
+pub fn completions(schema: &ast::Schema, offset: usize) -> Vec<Completion<'_>> {
+    let stack = scope_at(schema, offset);
+    let input = &schema.loc.source.input[..offset];
+    let word_start = input
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let word = &input[word_start..];
+
+    let mut res = Vec::new();
+    if let Some(dot) = word.rfind('.') {
+        let down: Vec<&str> = word[..dot].split('.').collect();
+        let body = match stack.lookup_struct(ast::LookupMode::Relaxed, &down[..]) {
+            Some(ast::RelativePath {
+                symbol: ast::Symbol::Namespace(ns),
+                ..
+            }) => Some(&ns.body),
+            Some(ast::RelativePath {
+                symbol: ast::Symbol::Import(imp),
+                ..
+            }) => imp.schema.as_ref().map(|s| &s.body),
+            _ => None,
+        };
+        if let Some(body) = body {
+            members(body, &mut HashSet::new(), &mut res);
+        }
+        return res;
+    }
+
+    for name in BUILTINS {
+        res.push(Completion {
+            label: name.to_string(),
+            item: None,
+        });
+    }
+    let mut seen = HashSet::new();
+    for frame in stack.frames.iter().rev() {
+        if let Some(body) = frame.body() {
+            members(body, &mut seen, &mut res);
+        }
+        for imp in frame.imports() {
+            if seen.insert(imp.name.text()) {
+                res.push(Completion {
+                    label: imp.name.text().to_string(),
+                    item: Some(Item::Symbol(ast::Symbol::Import(imp))),
+                });
+            }
+        }
+    }
+    res
+}
+
+/// Adds the types and namespaces of `body`, unless an inner scope
+/// already had something by the same name.
+fn members<'a>(
+    body: &'a ast::NamespaceBody,
+    seen: &mut HashSet<&'a str>,
+    res: &mut Vec<Completion<'a>>,
+) {
+    let symbols = body
+        .structs
+        .iter()
+        .map(ast::Symbol::Struct)
+        .chain(body.enums.iter().map(ast::Symbol::Enum))
+        .chain(body.unions.iter().map(ast::Symbol::Union))
+        .chain(body.namespaces.iter().map(ast::Symbol::Namespace));
+    for symbol in symbols {
+        let item = Item::Symbol(symbol);
+        if seen.insert(item.name().text()) {
+            res.push(Completion {
+                label: item.name().text().to_string(),
+                item: Some(item),
+            });
+        }
+    }
+}
+
+/// Returns the stack types are looked up from at the cursor: that of
+/// the innermost namespace or function body around it.
+fn scope_at(schema: &ast::Schema, offset: usize) -> ast::Stack<'_> {
+    let mut stack = ast::Stack::new(schema);
+    let mut body = &schema.body;
+    'descend: loop {
+        for ns in &body.namespaces {
+            if contains(&ns.body.span, offset) {
+                stack = stack.push(ns);
+                body = &ns.body;
+                continue 'descend;
+            }
+        }
+        for f in &body.functions {
+            if let Some(fbody) = f.body.as_ref() {
+                if contains(&fbody.span, offset) {
+                    stack = stack.push(f);
+                    body = fbody;
+                    continue 'descend;
+                }
+            }
+        }
+        return stack;
+    }
+}
+
+/// Collects every type used by fields, params, results and union
+/// members, along with the stack it's looked up from.
+fn collect_types<'a>(
+    stack: &ast::Stack<'a>,
+    body: &'a ast::NamespaceBody,
+    out: &mut Vec<(ast::Stack<'a>, &'a ast::Type)>,
+) {
+    for st in &body.structs {
+        out.extend(st.fields.iter().map(|f| (stack.clone(), &f.typ)));
+    }
+    for un in &body.unions {
+        out.extend(un.members.iter().map(|m| (stack.clone(), &m.typ)));
+    }
+    for f in &body.functions {
+        let stack = stack.push(f);
+        let fields = f.params.fields.iter().chain(f.results.fields.iter());
+        out.extend(fields.map(|field| (stack.clone(), &field.typ)));
+        if let Some(body) = f.body.as_ref() {
+            collect_types(&stack, body, out);
+        }
+    }
+    for ns in &body.namespaces {
+        collect_types(&stack.push(ns), &ns.body, out);
+    }
+}
+
+/// Returns the user type under the cursor, looking inside of
+/// arrays, options and maps.
+fn user_type_at(typ: &ast::Type, offset: usize) -> Option<&ast::Type> {
+    match &typ.kind {
+        ast::TypeKind::Base(_) => None,
+        ast::TypeKind::User if contains(&typ.span, offset) => Some(typ),
+        ast::TypeKind::User => None,
+        ast::TypeKind::Array(arr) => user_type_at(&arr.inner, offset),
+        ast::TypeKind::Option(opt) => user_type_at(&opt.inner, offset),
+        ast::TypeKind::Map(map) => {
+            user_type_at(&map.keys, offset).or_else(|| user_type_at(&map.values, offset))
+        }
+    }
+}
+
+/// Returns true if `offset` is within `span`, or right after it, where
+/// the cursor is once a name has been typed.
+fn contains(span: &Span, offset: usize) -> bool {
+    offset >= span.offset && offset <= span.offset + span.len
+}
@@ -0,0 +1,43 @@
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// Reads a message framed by a `Content-Length` header, or returns
+/// `None` once the client has closed the stream.
+pub fn read<R: BufRead>(input: &mut R) -> io::Result<Option<Value>> {
+    let mut len = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        // other headers, like `Content-Type`, don't matter
+        let mut tokens = line.splitn(2, ':');
+        if let (Some(name), Some(value)) = (tokens.next(), tokens.next()) {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                len = Some(value.trim().parse::<usize>().map_err(invalid)?);
+            }
+        }
+    }
+
+    let len = len.ok_or_else(|| invalid("missing Content-Length header"))?;
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(invalid)
+}
+
+pub fn write<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn invalid<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
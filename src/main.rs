@@ -1,13 +1,17 @@
 #![warn(clippy::all)]
 
 use clap::{App, Arg, SubCommand};
-use std::collections::HashMap;
 use std::path::Path;
 
 pub(crate) mod ast;
 pub(crate) mod checker;
 pub(crate) mod codegen;
+pub(crate) mod diff;
+pub(crate) mod fetcher;
+pub(crate) mod formatter;
+pub(crate) mod lsp;
 pub(crate) mod parser;
+pub(crate) mod workspace;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -20,12 +24,82 @@ fn main() {
         .about("A service definition file compiler")
         .arg(Arg::with_name("verbose").short("v").long("verbose"))
         .subcommand(
-            SubCommand::with_name("build").arg(
-                Arg::with_name("workspace")
-                    .help("The workspace to compile")
-                    .required(true)
-                    .index(1),
-            ),
+            SubCommand::with_name("build")
+                .arg(
+                    Arg::with_name("workspace")
+                        .help("The workspace to compile")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("check")
+                        .help("Compare generated code with what's on disk instead of writing it, and fail if it's out of date")
+                        .long("check"),
+                )
+                .arg(message_format()),
+        )
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Checks a workspace for errors, without generating anything")
+                .arg(
+                    Arg::with_name("workspace")
+                        .help("The workspace to check")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(message_format()),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compares two versions of a schema, and fails if the changes are breaking")
+                .arg(
+                    Arg::with_name("old")
+                        .help("The schema as deployed")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("new")
+                        .help("The schema to deploy")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(message_format()),
+        )
+        .subcommand(
+            SubCommand::with_name("fetch")
+                .arg(
+                    Arg::with_name("workspace")
+                        .help("The workspace to fetch sources for")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("update")
+                        .help("Ignore lavish-lock and fetch the latest version of every source")
+                        .long("update"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .about("Reprints schemas and lavish-rules files in the canonical style")
+                .arg(
+                    Arg::with_name("paths")
+                        .help("Files to format, or directories to look for them in")
+                        .required(true)
+                        .multiple(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("check")
+                        .help("Print how files differ from their canonical form instead of rewriting them, and fail if any does")
+                        .long("check"),
+                )
+                .arg(message_format()),
+        )
+        .subcommand(
+            SubCommand::with_name("lsp")
+                .about("Runs a language server for schemas, over stdin and stdout"),
         )
         .subcommand(
             SubCommand::with_name("print").arg(
@@ -43,9 +117,112 @@ fn main() {
 
     match matches.subcommand() {
         ("build", Some(cmd)) => {
+            set_message_format(cmd);
+            let workspace_path = Path::new(cmd.value_of("workspace").unwrap());
+            let workspace = workspace::parse_workspace(&opts, workspace_path).unwrap_or_else(fail);
+            let mode = if cmd.is_present("check") {
+                codegen::Mode::Check
+            } else {
+                codegen::Mode::Write
+            };
+            if let Err(e) = codegen::codegen(&opts, &workspace, mode) {
+                fail::<()>(Box::new(e));
+            }
+        }
+        ("check", Some(cmd)) => {
+            set_message_format(cmd);
+            let workspace_path = Path::new(cmd.value_of("workspace").unwrap());
+            let workspace = workspace::parse_workspace(&opts, workspace_path).unwrap_or_else(fail);
+            if let Err(e) = codegen::target(&workspace) {
+                fail::<()>(Box::new(e));
+            }
+            if opts.verbose {
+                println!("Checked {} members", workspace.members.len());
+            }
+        }
+        ("diff", Some(cmd)) => {
+            set_message_format(cmd);
+            let parse = |arg| {
+                let source = parser::Source::from_path(cmd.value_of(arg).unwrap())?;
+                Ok(parser::parse_schema(source)?)
+            };
+            let old = parse("old").unwrap_or_else(fail);
+            let new = parse("new").unwrap_or_else(fail);
+
+            let changes = diff::diff(&old, &new);
+            let json = cmd.value_of("message-format") == Some("json");
+            for change in &changes {
+                if json {
+                    println!("{}", change.to_json());
+                } else {
+                    print!("{}", change);
+                }
+            }
+            let breaking = changes.iter().filter(|c| c.is_breaking()).count();
+            if !json {
+                println!(
+                    "{} breaking changes, {} compatible changes",
+                    breaking,
+                    changes.len() - breaking
+                );
+            }
+            if breaking > 0 {
+                std::process::exit(1);
+            }
+        }
+        ("fetch", Some(cmd)) => {
             let workspace_path = Path::new(cmd.value_of("workspace").unwrap());
-            let workspace = parse_workspace(&opts, workspace_path).unwrap();
-            codegen::codegen(&opts, &workspace).unwrap();
+            let rules = workspace::parse_rules(workspace_path).unwrap_or_else(fail);
+            fetcher::fetch(&opts, workspace_path, &rules, cmd.is_present("update"))
+                .unwrap_or_else(fail);
+        }
+        ("fmt", Some(cmd)) => {
+            set_message_format(cmd);
+            let mode = if cmd.is_present("check") {
+                codegen::Mode::Check
+            } else {
+                codegen::Mode::Write
+            };
+            let files = codegen::Files::new(mode);
+            // one broken file shouldn't keep the others from being formatted
+            let mut failed = 0;
+            for path in cmd.values_of("paths").unwrap() {
+                let paths = match formatter::collect_files(Path::new(path)) {
+                    Ok(paths) => paths,
+                    Err(e) => {
+                        report(e);
+                        failed += 1;
+                        continue;
+                    }
+                };
+                for file in paths {
+                    if let Err(e) = formatter::format_file(&file, &files) {
+                        report(e);
+                        failed += 1;
+                    }
+                }
+            }
+            let stale = files.stale();
+            if !stale.is_empty() {
+                eprintln!(
+                    "{} files aren't formatted: run `lavish fmt` to fix them",
+                    stale.len()
+                );
+            }
+            if failed > 0 {
+                eprintln!("{} files couldn't be formatted", failed);
+            }
+            if !stale.is_empty() || failed > 0 {
+                std::process::exit(1);
+            }
+        }
+        ("lsp", Some(_)) => {
+            let stdin = std::io::stdin();
+            let stdout = std::io::stdout();
+            if let Err(e) = lsp::run(stdin.lock(), stdout.lock()) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
         }
         ("print", Some(cmd)) => {
             let schema_path = Path::new(cmd.value_of("schema").unwrap());
@@ -60,57 +237,43 @@ fn main() {
     };
 }
 
-use simple_error::SimpleError;
-
-pub struct Opts {
-    verbose: bool,
+fn message_format() -> Arg<'static, 'static> {
+    Arg::with_name("message-format")
+        .help("How to print diagnostics: in color, or as one JSON object per line")
+        .long("message-format")
+        .takes_value(true)
+        .possible_values(&["human", "json"])
+        .default_value("human")
 }
 
-fn parse_workspace(
-    opts: &Opts,
-    workspace_path: &Path,
-) -> Result<ast::Workspace, Box<dyn std::error::Error>> {
-    let rules_path = workspace_path.join("lavish-rules");
-    if !rules_path.exists() {
-        return Err(Box::new(SimpleError::new(format!(
-            "{:?}: not a workspace (does not contain a 'lavish-rules' file)",
-            workspace_path
-        ))));
+fn set_message_format(cmd: &clap::ArgMatches) {
+    if cmd.value_of("message-format") == Some("json") {
+        parser::sink::set(Box::new(parser::sink::Json));
     }
+}
 
-    let rules = {
-        let source = parser::Source::from_path(&rules_path)?;
-        parser::parse_rules(source)?
-    };
-
-    let mut workspace = ast::Workspace {
-        dir: workspace_path.into(),
-        rules,
-        members: HashMap::new(),
-    };
+/// Reports `e`, then exits.
+fn fail<T>(e: Box<dyn std::error::Error>) -> T {
+    report(e);
+    std::process::exit(1)
+}
 
-    if opts.verbose {
-        println!("Building {} modules", workspace.rules.builds.len());
-    }
-    for build in &workspace.rules.builds {
-        let name = build.name.text().to_string();
-        let source_path = workspace.resolve(&build)?;
-        if opts.verbose {
-            println!("Parsing {} from {:?}", name, source_path);
+/// Reports syntax errors like other diagnostics, since they're only
+/// returned. Other errors have been reported already, or aren't
+/// about a source file.
+fn report(e: Box<dyn std::error::Error>) {
+    match e.downcast_ref::<parser::Error>() {
+        Some(parser::Error::Source(errors))
+        | Some(parser::Error::Schema { syntax: errors, .. }) => {
+            for e in errors {
+                e.diagnostic().print();
+            }
         }
-        let source = parser::Source::from_path(&source_path)?;
-        let schema = parser::parse_schema(source)?;
-
-        workspace.members.insert(
-            name.clone(),
-            ast::WorkspaceMember {
-                name,
-                build: Some(build.clone()),
-                imports: Vec::new(),
-                schema: Some(schema),
-            },
-        );
+        Some(parser::Error::Checker(e)) => eprintln!("{}", e),
+        _ => eprintln!("{}", e),
     }
+}
 
-    Ok(workspace)
+pub struct Opts {
+    verbose: bool,
 }
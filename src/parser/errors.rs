@@ -1,7 +1,7 @@
 use colored::*;
 use nom::{
     error::{VerboseError, VerboseErrorKind},
-    Err,
+    Err, InputTake,
 };
 use std::fmt;
 use std::iter::repeat;
@@ -9,14 +9,22 @@ use std::path::Path;
 use std::rc::Rc;
 
 use crate::{ast, checker, parser};
+use parser::sink;
 use parser::Span;
 
 /// A parsing, checking, or emitting error
 #[derive(Debug)]
 pub enum Error {
     IO(std::io::Error),
-    Source(SourceError),
+    /// One or more syntax errors, in source order
+    Source(Vec<SourceError>),
     Checker(checker::Error),
+    /// Syntax errors, along with the errors the checker found in
+    /// whatever did parse, and reported already
+    Schema {
+        syntax: Vec<SourceError>,
+        checker: checker::Error,
+    },
     Unknown(UnknownError),
 
     // Used for tests
@@ -28,8 +36,12 @@ impl<'a> fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::IO(e) => write!(f, "{}", e),
-            Error::Source(e) => write!(f, "{:#?}", e),
+            Error::Source(errors) => write_syntax_errors(f, errors),
             Error::Checker(e) => write!(f, "{:#?}", e),
+            Error::Schema { syntax, checker } => {
+                write_syntax_errors(f, syntax)?;
+                write!(f, "{:#?}", checker)
+            }
             Error::UnexpectedSourceError(e) => write!(f, "{:#?}", e),
             Error::Unknown(_) => write!(f, "unknown error"),
         }
@@ -38,13 +50,66 @@ impl<'a> fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+fn write_syntax_errors(f: &mut fmt::Formatter, errors: &[SourceError]) -> fmt::Result {
+    for e in errors {
+        write!(f, "{:#?}", e)?;
+    }
+    if errors.len() > 1 {
+        write!(f, "{} syntax errors found", errors.len())?;
+    }
+    Ok(())
+}
+
 pub struct SourceError {
     inner: VerboseError<parser::Span>,
 }
 
+impl SourceError {
+    /// Returns the syntax error as a diagnostic: the outermost parser
+    /// error, with whatever was being parsed at the time as notes.
+    pub fn diagnostic(&self) -> Diagnostic<'static> {
+        let errors = &self.inner.errors;
+        let main = errors
+            .iter()
+            .rposition(|(_, kind)| !matches!(kind, VerboseErrorKind::Context(_)))
+            .unwrap_or(0);
+
+        let message = |span: &Span, kind: &VerboseErrorKind| match kind {
+            VerboseErrorKind::Char(c) => format!(
+                "expected '{}', found {}",
+                c,
+                span.chars().next().unwrap_or_else(|| '\0')
+            ),
+            VerboseErrorKind::Context(s) => format!("In {}", s),
+            VerboseErrorKind::Nom(ek) => {
+                format!("parsing error: {}", format!("{:#?}", ek).red().bold())
+            }
+        };
+
+        // parser errors hold the rest of the input: only point
+        // at where it starts.
+        let position = |span: &Span| {
+            let len = span.chars().next().map(char::len_utf8).unwrap_or(0);
+            span.take(len).position()
+        };
+
+        let (span, kind) = &errors[main];
+        let mut diag = position(span)
+            .diag_err(message(span, kind))
+            .code("syntax_error");
+        for (i, (span, kind)) in errors.iter().enumerate() {
+            if i != main {
+                diag = diag.note(position(span), message(span, kind));
+            }
+        }
+        diag.build()
+    }
+}
+
 impl<'a> fmt::Debug for SourceError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        print_errors(f, &self.inner)
+        writeln!(f)?;
+        self.diagnostic().write(f)
     }
 }
 
@@ -141,7 +206,9 @@ where
     };
     let res = p(span);
     match res {
-        Err(Err::Error(e)) | Err(Err::Failure(e)) => Err(Error::Source(SourceError { inner: e })),
+        Err(Err::Error(e)) | Err(Err::Failure(e)) => {
+            Err(Error::Source(vec![SourceError { inner: e }]))
+        }
         Err(_) => Err(Error::Unknown(UnknownError {
             source: source.clone(),
         })),
@@ -151,27 +218,101 @@ where
 
 // FIXME: this surely doesn't belong in errors.rs
 pub fn parse_schema(source: Rc<Source>) -> Result<ast::Schema, Error> {
-    let schema = parse(source, parser::schema::<VerboseError<parser::Span>>)?;
+    let (schema, errors) = parse_schema_recovering(source)?;
+    if !errors.is_empty() {
+        // still check what did parse, the checker prints its own diagnostics
+        return Err(match checker::check(&schema) {
+            Ok(()) => Error::Source(errors),
+            Err(checker) => Error::Schema {
+                syntax: errors,
+                checker,
+            },
+        });
+    }
+
     checker::check(&schema)?;
     Ok(schema)
 }
 
+/// Parses a schema without checking it, for tools that only care
+/// about its syntax, like the formatter. Any syntax error is fatal.
+pub fn parse_schema_syntax(source: Rc<Source>) -> Result<ast::Schema, Error> {
+    let (schema, errors) = parse_schema_recovering(source)?;
+    if !errors.is_empty() {
+        return Err(Error::Source(errors));
+    }
+    Ok(schema)
+}
+
+/// Returns a possibly partial schema, along with the syntax errors
+/// that were recovered from, without checking it.
+pub fn parse_schema_recovering(
+    source: Rc<Source>,
+) -> Result<(ast::Schema, Vec<SourceError>), Error> {
+    let recovered = parser::Recovered::default();
+    let schema = parse(source, |i| {
+        parser::schema::<VerboseError<parser::Span>>(&recovered, i)
+    })?;
+    let errors = recovered
+        .into_errors()
+        .into_iter()
+        .map(|inner| SourceError { inner })
+        .collect();
+    Ok((schema, errors))
+}
+
 pub fn parse_rules(source: Rc<Source>) -> Result<ast::Rules, Error> {
     parse(source, parser::rules::<VerboseError<parser::Span>>)
 }
 
+pub fn parse_lockfile(source: Rc<Source>) -> Result<ast::Lockfile, Error> {
+    parse(source, parser::lockfile::<VerboseError<parser::Span>>)
+}
+
+/// How bad a diagnostic is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info => "info",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Level::Error => Color::Red,
+            Level::Warning => Color::Yellow,
+            Level::Info => Color::Blue,
+        }
+    }
+}
+
+/// A message about a span of source, along with related notes,
+/// such as where something was first defined.
 pub struct Diagnostic<'a> {
     pos: Position,
-    caret_color: Color,
+    level: Level,
+    code: Option<&'static str>,
     prefix: &'a str,
     message: String,
+    notes: Vec<Diagnostic<'a>>,
 }
 
 pub struct DiagnosticBuilder<'a> {
     pos: Position,
-    caret_color: Color,
+    level: Level,
+    code: Option<&'static str>,
     prefix: &'a str,
     message: Option<String>,
+    notes: Vec<Diagnostic<'a>>,
 }
 
 const EMPTY_PREFIX: &str = "";
@@ -180,14 +321,23 @@ impl<'a> DiagnosticBuilder<'a> {
     pub fn new(pos: Position) -> Self {
         Self {
             pos: pos.clone(),
-            caret_color: Color::Blue,
+            level: Level::Info,
+            code: None,
             prefix: EMPTY_PREFIX,
             message: None,
+            notes: Vec::new(),
         }
     }
 
-    pub fn caret_color(mut self, caret_color: Color) -> Self {
-        self.caret_color = caret_color;
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets a short identifier for the kind of problem, like
+    /// `unknown_type`, or the name of a lint.
+    pub fn code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
         self
     }
 
@@ -201,42 +351,79 @@ impl<'a> DiagnosticBuilder<'a> {
         self
     }
 
+    /// Adds an informational note about another position
+    pub fn note(mut self, pos: Position, message: String) -> Self {
+        self.notes.push(pos.diag_info(message).build());
+        self
+    }
+
     pub fn build(self) -> Diagnostic<'a> {
         Diagnostic {
             pos: self.pos,
-            caret_color: self.caret_color,
+            level: self.level,
+            code: self.code,
             prefix: self.prefix,
             message: self.message.unwrap_or_else(|| "".into()),
+            notes: self.notes,
         }
     }
 }
 
 impl<'a> Diagnostic<'a> {
+    /// Sends the diagnostic to the current sink, which prints
+    /// it in color by default.
     pub fn print(&self) {
-        print!("{}", self)
+        sink::emit(self)
     }
 
     pub fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self)
     }
+
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    pub fn code(&self) -> Option<&'static str> {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn notes(&self) -> &[Diagnostic<'a>] {
+        &self.notes
+    }
+
+    /// Where the diagnostic starts
+    pub fn start(&self) -> &Position {
+        &self.pos
+    }
+
+    /// Where the diagnostic ends (exclusive)
+    pub fn end(&self) -> Position {
+        let span = &self.pos.span;
+        Span {
+            source: span.source.clone(),
+            offset: span.offset + span.len,
+            len: 0,
+        }
+        .position()
+    }
 }
 
 impl<'a> DiagnosticBuilder<'a> {
     pub fn print(self) {
         self.build().print()
     }
-
-    pub fn write(self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.build().write(f)
-    }
 }
 
 impl<'a> fmt::Display for Diagnostic<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let pos = &self.pos;
-        let caret_color = self.caret_color;
+        let caret_color = self.level.color();
         let prefix = self.prefix;
-        let message = &self.message;
 
         let loc = format!(
             "{}:{}:{}:",
@@ -244,7 +431,23 @@ impl<'a> fmt::Display for Diagnostic<'a> {
             pos.line + 1,
             pos.column + 1
         );
-        writeln!(f, "{}{} {}", prefix, loc.bold(), message)?;
+        let label = match self.level {
+            Level::Info => "".normal(),
+            level => format!("{}: ", level.as_str()).color(caret_color).bold(),
+        };
+        let code = match self.code {
+            Some(code) => format!(" [{}]", code),
+            None => "".into(),
+        };
+        writeln!(
+            f,
+            "{}{} {}{}{}",
+            prefix,
+            loc.bold(),
+            label,
+            self.message,
+            code
+        )?;
         let text_line = &pos.span.source.lines[pos.line];
         writeln!(f, "{}{}", prefix, text_line.dimmed())?;
 
@@ -266,6 +469,9 @@ impl<'a> fmt::Display for Diagnostic<'a> {
                 .color(caret_color)
                 .bold()
         )?;
+        for note in &self.notes {
+            write!(f, "{}", note)?;
+        }
         Ok(())
     }
 }
@@ -283,43 +489,14 @@ impl Position {
     }
 
     pub fn diag_info<'a>(&self, message: String) -> DiagnosticBuilder<'a> {
-        self.diag(message).caret_color(Color::Blue)
+        self.diag(message).level(Level::Info)
     }
 
     pub fn diag_err<'a>(&self, message: String) -> DiagnosticBuilder<'a> {
-        self.diag(message).caret_color(Color::Red)
+        self.diag(message).level(Level::Error)
     }
-}
-
-pub fn print_errors(f: &mut fmt::Formatter, e: &VerboseError<Span>) -> fmt::Result {
-    let mut errors = e.errors.clone();
-    errors.reverse();
-
-    writeln!(f)?;
-    for (span, kind) in errors.iter() {
-        let pos = span.position();
 
-        match kind {
-            VerboseErrorKind::Char(c) => {
-                pos.diag_err(format!(
-                    "expected '{}', found {}",
-                    c,
-                    span.chars().next().unwrap_or_else(|| '\0')
-                ))
-                .write(f)?;
-            }
-            VerboseErrorKind::Context(s) => {
-                pos.diag_info(format!("In {}", s)).write(f)?;
-            }
-            VerboseErrorKind::Nom(ek) => {
-                pos.diag_err(format!(
-                    "parsing error: {}",
-                    format!("{:#?}", ek).red().bold()
-                ))
-                .write(f)?;
-            }
-        }
+    pub fn diag_warn<'a>(&self, message: String) -> DiagnosticBuilder<'a> {
+        self.diag(message).level(Level::Warning)
     }
-
-    Ok(())
 }
@@ -2,44 +2,105 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take_until, take_while, take_while1},
     character::complete::char,
-    combinator::{all_consuming, cut, map, opt},
-    error::{context, ParseError},
-    multi::{many0, many1, separated_list},
-    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    combinator::{all_consuming, cut, map, map_res, opt, peek, verify},
+    error::{context, ErrorKind, ParseError},
+    multi::{many0, separated_list},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult, InputTake,
 };
 
 mod errors;
+mod recovery;
+pub mod sink;
 mod span;
 
 use super::ast::*;
 pub use errors::*;
+pub use recovery::Recovered;
+use recovery::*;
 pub use span::*;
 
-/// Parses an entire lavish schema
-pub fn schema<E: ParseError<Span>>(i: Span) -> IResult<Span, Schema, E> {
+/// Parses an entire lavish schema. Broken items are skipped, and their
+/// errors collected in `recovered`: the schema is then only partial.
+pub fn schema<E: ParseError<Span>>(recovered: &Recovered<E>, i: Span) -> IResult<Span, Schema, E> {
+    let (i, loc) = loc(i)?;
+    let (mut i, imports) = imports(recovered, i)?;
+    let start = i.clone();
+
+    let mut items = Vec::new();
+    let i = loop {
+        let (rest, mut parsed) = nsitems(recovered, i)?;
+        items.append(&mut parsed);
+        if rest.len() == 0 {
+            break rest;
+        }
+        // only a closing brace stops a namespace body
+        recovered.push(E::from_error_kind(rest.clone(), ErrorKind::Eof));
+        i = skip_char(&rest);
+    };
+
+    let span = start.take(i.offset - start.offset);
+    let mut schema = Schema::new(loc, imports, NamespaceBody::new(span, items));
+    schema.broken = recovered.names();
+    Ok((i, schema))
+}
+
+/// Parses a `lavish-rules` files.
+pub fn rules<E: ParseError<Span>>(i: Span) -> IResult<Span, Rules, E> {
     let (i, loc) = loc(i)?;
 
     all_consuming(terminated(
-        map(tuple((imports, spaced(nsbody))), move |(imports, body)| {
-            Schema::new(loc.clone(), imports, body)
-        }),
+        map(
+            tuple((spaced(opt(target)), spaced(rules_items))),
+            move |(target, items)| Rules::new(loc.clone(), target, items),
+        ),
         spaced(many0(spaced(comment_line))),
     ))(i)
 }
 
-/// Parses a `lavish-rules` files.
-pub fn rules<E: ParseError<Span>>(i: Span) -> IResult<Span, Rules, E> {
+/// Parses a `lavish-lock` file.
+pub fn lockfile<E: ParseError<Span>>(i: Span) -> IResult<Span, Lockfile, E> {
     let (i, loc) = loc(i)?;
 
     all_consuming(terminated(
-        map(tuple((target, spaced(builds))), move |(target, builds)| {
-            Rules::new(loc.clone(), target, builds)
+        map(many0(spaced(lock_entry)), move |entries| Lockfile {
+            loc: loc.clone(),
+            entries,
         }),
         spaced(many0(spaced(comment_line))),
     ))(i)
 }
 
+/// In lockfile: `lock name from "source" { revision "..." sha256 "..." }`
+pub fn lock_entry<E: ParseError<Span>>(i: Span) -> IResult<Span, LockEntry, E> {
+    let (i, _) = many0(spaced(comment_line))(i)?;
+    let (i, _) = spaced(tag("lock"))(i)?;
+
+    context(
+        "lock entry",
+        cut(map(
+            tuple((
+                spaced(id),
+                spaced(from),
+                delimited(
+                    spaced(char('{')),
+                    tuple((
+                        opt(preceded(spaced(tag("revision")), spaced(stringlit))),
+                        preceded(spaced(tag("sha256")), spaced(stringlit)),
+                    )),
+                    spaced(char('}')),
+                ),
+            )),
+            |(name, from, (revision, sha256))| LockEntry {
+                name,
+                from,
+                revision,
+                sha256,
+            },
+        )),
+    )(i)
+}
+
 /// In rules file: `target {rust,go,typescript}`, with an optional body
 pub fn target<E: ParseError<Span>>(i: Span) -> IResult<Span, Target, E> {
     let (i, _) = spaced(tag("target"))(i)?;
@@ -109,25 +170,70 @@ pub fn ts_target<E: ParseError<Span>>(i: Span) -> IResult<Span, TypeScriptTarget
     Ok((i, TypeScriptTarget {}))
 }
 
-/// In rules: list of build directives
-pub fn builds<E: ParseError<Span>>(i: Span) -> IResult<Span, Vec<Build>, E> {
-    many0(spaced(build))(i)
+/// In rules: 0+ build, export or lint directives, along with
+/// comments and blank lines
+pub fn rules_items<E: ParseError<Span>>(i: Span) -> IResult<Span, Vec<RulesItem>, E> {
+    many0(alt((
+        map(blank_lines, |_| RulesItem::Trivia(Trivia::BlankLine)),
+        preceded(
+            sp,
+            alt((
+                map(build, RulesItem::Build),
+                map(export, RulesItem::Export),
+                map(lint, RulesItem::Lint),
+                map(comment, |c| RulesItem::Trivia(Trivia::Comment(c))),
+            )),
+        ),
+    )))(i)
 }
 
 /// In rules: `build X [from Y]`
 pub fn build<E: ParseError<Span>>(i: Span) -> IResult<Span, Build, E> {
-    let (i, _) = many0(spaced(comment_line))(i)?;
     let (i, _) = spaced(tag("build"))(i)?;
 
     context(
         "build directive",
+        cut(map(tuple((id, opt(preceded(sp, from)))), |(name, from)| {
+            Build { name, from }
+        })),
+    )(i)
+}
+
+/// In rules: `export "path" as X`
+pub fn export<E: ParseError<Span>>(i: Span) -> IResult<Span, Export, E> {
+    let (i, _) = spaced(tag("export"))(i)?;
+
+    context(
+        "export directive",
         cut(map(
-            tuple((spaced(id), spaced(opt(from)))),
-            |(name, from)| Build { name, from },
+            tuple((stringlit, preceded(spaced(tag("as")), id))),
+            |(path, name)| Export { path, name },
         )),
     )(i)
 }
 
+/// In rules: `allow X`, `warn X` or `deny X`
+pub fn lint<E: ParseError<Span>>(i: Span) -> IResult<Span, LintDirective, E> {
+    let (i, severity) = spaced(severity)(i)?;
+
+    context(
+        "lint directive",
+        cut(map(id, move |id| LintDirective { severity, id })),
+    )(i)
+}
+
+fn severity<E: ParseError<Span>>(i: Span) -> IResult<Span, Severity, E> {
+    // a whole word, so that `warnings` isn't read as `warn ings`
+    let (rest, word) = id(i.clone())?;
+    let severity = match word.text() {
+        "allow" => Severity::Allow,
+        "warn" => Severity::Warn,
+        "deny" => Severity::Deny,
+        _ => return Err(nom::Err::Error(E::from_error_kind(i, ErrorKind::Tag))),
+    };
+    Ok((rest, severity))
+}
+
 /// From directive, used for `build` (rules) and `import` (schemas)
 pub fn from<E: ParseError<Span>>(i: Span) -> IResult<Span, FromDirective, E> {
     let (i, _) = loc(i)?;
@@ -140,8 +246,11 @@ pub fn from<E: ParseError<Span>>(i: Span) -> IResult<Span, FromDirective, E> {
 }
 
 /// In schema: 0+ import directives
-pub fn imports<E: ParseError<Span>>(i: Span) -> IResult<Span, Vec<Import>, E> {
-    many0(spaced(import))(i)
+pub fn imports<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    i: Span,
+) -> IResult<Span, Vec<Import>, E> {
+    recover_many0(recovered, spaced(import))(i)
 }
 
 /// In schema: single import directive
@@ -152,7 +261,11 @@ pub fn import<E: ParseError<Span>>(i: Span) -> IResult<Span, Import, E> {
         "import",
         cut(map(
             tuple((spaced(id), spaced(opt(from)))),
-            |(name, from)| Import { name, from },
+            |(name, from)| Import {
+                name,
+                from,
+                schema: None,
+            },
         )),
     )(i)
 }
@@ -165,6 +278,18 @@ where
     terminated(preceded(sp, f), sp)
 }
 
+/// Runs `f`, and also returns the span of what it consumed
+fn spanned<O, E: ParseError<Span>, F>(f: F) -> impl Fn(Span) -> IResult<Span, (Span, O), E>
+where
+    F: Fn(Span) -> IResult<Span, O, E>,
+{
+    move |i: Span| {
+        let (rest, o) = f(i.clone())?;
+        let len = rest.offset - i.offset;
+        Ok((rest, (i.take(len), o)))
+    }
+}
+
 /// All whitespace (including newlines)
 fn sp<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
     let chars = " \t\r\n";
@@ -172,13 +297,6 @@ fn sp<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
     take_while(move |c| chars.contains(c))(i)
 }
 
-/// Whitespace excluding newlines
-fn linesp<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
-    let chars = " \t";
-
-    take_while(move |c| chars.contains(c))(i)
-}
-
 /// Identifier
 fn id<E: ParseError<Span>>(i: Span) -> IResult<Span, Identifier, E> {
     let chars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
@@ -196,7 +314,7 @@ pub fn stringlit<E: ParseError<Span>>(i: Span) -> IResult<Span, StringLiteral, E
     // TODO: use escaped_transform instead
     let (i, loc) = loc(i)?;
 
-    let chars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-./";
+    let chars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-./:@";
 
     map(
         delimited(
@@ -214,6 +332,24 @@ pub fn stringlit<E: ParseError<Span>>(i: Span) -> IResult<Span, StringLiteral, E
     )(i)
 }
 
+/// Integer literal, in decimal, optionally negative
+pub fn intlit<E: ParseError<Span>>(i: Span) -> IResult<Span, IntegerLiteral, E> {
+    let (i, loc) = loc(i)?;
+
+    let chars = "-0123456789";
+
+    map_res(
+        take_while1(move |c| chars.contains(c)),
+        move |span: Span| {
+            let value: String = span.into();
+            value.parse().map(|value| IntegerLiteral {
+                loc: loc.clone(),
+                value,
+            })
+        },
+    )(i)
+}
+
 /// Builtin lavish types
 fn basetyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
     map(
@@ -242,14 +378,13 @@ fn basetyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
 
 /// Array type: Array<T>
 fn arraytyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
-    let span = i.clone();
     map(
-        preceded(
+        spanned(preceded(
             terminated(spaced(tag("array")), spaced(char('<'))),
-            terminated(typ, spaced(char('>'))),
-        ),
-        move |t| Type {
-            span: span.clone(),
+            terminated(typ, preceded(sp, char('>'))),
+        )),
+        |(span, t)| Type {
+            span,
             kind: TypeKind::Array(ArrayType { inner: Box::new(t) }),
         },
     )(i)
@@ -257,14 +392,13 @@ fn arraytyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
 
 /// Option type: Option<T>
 fn optiontyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
-    let span = i.clone();
     map(
-        preceded(
+        spanned(preceded(
             terminated(spaced(tag("option")), spaced(char('<'))),
-            terminated(typ, spaced(char('>'))),
-        ),
-        move |t| Type {
-            span: span.clone(),
+            terminated(typ, preceded(sp, char('>'))),
+        )),
+        |(span, t)| Type {
+            span,
             kind: TypeKind::Option(OptionType { inner: Box::new(t) }),
         },
     )(i)
@@ -272,17 +406,16 @@ fn optiontyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
 
 /// Map type: Map<K, V>
 fn maptyp<E: ParseError<Span>>(i: Span) -> IResult<Span, Type, E> {
-    let span = i.clone();
     map(
-        preceded(
+        spanned(preceded(
             terminated(spaced(tag("map")), spaced(char('<'))),
             terminated(
                 separated_pair(spaced(typ), char(','), spaced(typ)),
-                spaced(char('>')),
+                preceded(sp, char('>')),
             ),
-        ),
-        move |(k, v)| Type {
-            span: span.clone(),
+        )),
+        |(span, (k, v))| Type {
+            span,
             kind: TypeKind::Map(MapType {
                 keys: Box::new(k),
                 values: Box::new(v),
@@ -314,7 +447,7 @@ fn loc<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
 
 // Field declaration: `name: type`, prefixed by optional comment
 fn field<E: ParseError<Span>>(i: Span) -> IResult<Span, Field, E> {
-    let (i, comment) = opt(comment)(i)?;
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, loc) = spaced(loc)(i)?;
     let (i, name) = spaced(id)(i)?;
     let ctx = spaced(context(
@@ -359,14 +492,27 @@ fn named_struct<E: ParseError<Span>, S: Into<String>>(
 }
 
 fn enum_variant<E: ParseError<Span>>(i: Span) -> IResult<Span, EnumVariant, E> {
-    let (i, comment) = opt(comment)(i)?;
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, loc) = spaced(loc)(i)?;
 
-    map(spaced(id), move |name| EnumVariant {
-        loc: loc.clone(),
-        comment: comment.clone(),
-        name,
-    })(i)
+    map(
+        tuple((
+            spaced(id),
+            opt(preceded(
+                spaced(char('=')),
+                cut(spaced(alt((
+                    map(stringlit, EnumValue::String),
+                    map(intlit, EnumValue::Integer),
+                )))),
+            )),
+        )),
+        move |(name, value)| EnumVariant {
+            loc: loc.clone(),
+            comment: comment.clone(),
+            name,
+            value,
+        },
+    )(i)
 }
 
 // Field list: field declarations separated by commas
@@ -400,8 +546,11 @@ fn results<E: ParseError<Span>>(i: Span) -> IResult<Span, StructDecl, E> {
 }
 
 // Function declaration
-fn fndecl<E: ParseError<Span>>(i: Span) -> IResult<Span, FunctionDecl, E> {
-    let (i, comment) = opt(comment)(i)?;
+fn fndecl<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    i: Span,
+) -> IResult<Span, FunctionDecl, E> {
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, side) = side(i)?;
     let (i, _) = spaced(tag("fn"))(i)?;
     let (i, loc) = spaced(loc)(i)?;
@@ -419,7 +568,7 @@ fn fndecl<E: ParseError<Span>>(i: Span) -> IResult<Span, FunctionDecl, E> {
                     ),
                 ),
                 opt(results),
-                opt(fnbody),
+                opt(|i| fnbody(recovered, i)),
             )),
             move |(name, params, results, body)| FunctionDecl {
                 loc: loc.clone(),
@@ -448,15 +597,24 @@ fn default_results(loc: Span) -> StructDecl {
 }
 
 // Function body (nested functions)
-fn fnbody<E: ParseError<Span>>(i: Span) -> IResult<Span, NamespaceBody, E> {
+fn fnbody<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    i: Span,
+) -> IResult<Span, NamespaceBody, E> {
     let (i, _) = spaced(char('{'))(i)?;
 
-    context("function body", cut(terminated(nsbody, spaced(char('}')))))(i)
+    context(
+        "function body",
+        cut(terminated(
+            |i| nsbody(recovered, i),
+            preceded(sp, char('}')),
+        )),
+    )(i)
 }
 
 // Notification declaration: like function, but no results and no body
 fn notifdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, FunctionDecl, E> {
-    let (i, comment) = opt(comment)(i)?;
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, side) = side(i)?;
     let (i, _) = spaced(tag("nf"))(i)?;
     let (i, loc) = spaced(loc)(i)?;
@@ -490,7 +648,7 @@ fn notifdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, FunctionDecl, E> {
 
 // Struct declaration
 fn structdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, StructDecl, E> {
-    let (i, comment) = opt(comment)(i)?;
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, _) = preceded(sp, tag("struct"))(i)?;
     let (i, loc) = spaced(loc)(i)?;
 
@@ -513,7 +671,7 @@ fn structdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, StructDecl, E> {
 
 // Enum declaration
 fn enumdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, EnumDecl, E> {
-    let (i, comment) = opt(comment)(i)?;
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, _) = preceded(sp, tag("enum"))(i)?;
     let (i, loc) = spaced(loc)(i)?;
 
@@ -537,49 +695,163 @@ fn enumdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, EnumDecl, E> {
     )(i)
 }
 
+// Union declaration
+fn uniondecl<E: ParseError<Span>>(i: Span) -> IResult<Span, UnionDecl, E> {
+    let (i, comment) = opt(doc_comment)(i)?;
+    let (i, _) = preceded(sp, tag("union"))(i)?;
+    let (i, loc) = spaced(loc)(i)?;
+
+    context(
+        "union declaration",
+        cut(map(
+            tuple((
+                preceded(sp, id),
+                preceded(
+                    sp,
+                    delimited(char('{'), union_members, preceded(sp, char('}'))),
+                ),
+            )),
+            move |(name, members)| UnionDecl {
+                loc: loc.clone(),
+                comment: comment.clone(),
+                name,
+                members,
+            },
+        )),
+    )(i)
+}
+
+fn union_member<E: ParseError<Span>>(i: Span) -> IResult<Span, UnionMember, E> {
+    let (i, comment) = opt(doc_comment)(i)?;
+
+    map(spaced(usertyp), move |typ| UnionMember {
+        comment: comment.clone(),
+        typ,
+    })(i)
+}
+
+// Member list: user types separated by commas
+fn union_members<E: ParseError<Span>>(i: Span) -> IResult<Span, Vec<UnionMember>, E> {
+    terminated(
+        separated_list(spaced(char(',')), union_member),
+        opt(spaced(char(','))),
+    )(i)
+}
+
 // A single comment-line
 fn comment_line<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
-    preceded(sp, preceded(tag("//"), preceded(linesp, take_until("\n"))))(i)
+    preceded(sp, comment_text)(i)
+}
+
+// The text of a comment, after `//` and one optional space
+fn comment_text<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
+    preceded(tag("//"), preceded(opt(char(' ')), take_until("\n")))(i)
 }
 
-// A comment block, made of 1+ comment lines. Use with opt
+// A comment block, made of 1+ comment lines. Use with opt. Blank
+// lines don't end the block, but are remembered for the formatter.
 fn comment<E: ParseError<Span>>(i: Span) -> IResult<Span, Comment, E> {
-    map(many1(comment_line), |lines| Comment {
-        lines: lines.iter().map(|x| x.clone().into()).collect(),
-    })(i)
+    map(
+        pair(comment_line, many0(pair(sp, comment_text))),
+        |(first, rest)| {
+            let mut comment = Comment {
+                lines: vec![first.into()],
+                breaks: Vec::new(),
+            };
+            for (space, text) in rest {
+                if space.slice().matches('\n').count() >= 2 {
+                    comment.breaks.push(comment.lines.len());
+                }
+                comment.lines.push(text.into());
+            }
+            comment
+        },
+    )(i)
+}
+
+// The comment block of an item. A blank line between the block and
+// the item doesn't detach them, but is remembered for the formatter.
+fn doc_comment<E: ParseError<Span>>(i: Span) -> IResult<Span, Comment, E> {
+    map(
+        pair(comment, opt(peek(blank_lines))),
+        |(mut comment, blank)| {
+            if blank.is_some() {
+                comment.breaks.push(comment.lines.len());
+            }
+            comment
+        },
+    )(i)
 }
 
-// Any namespace item, will be Some() if we found something other
-// then a comment line
-fn nsitem<E: ParseError<Span>>(i: Span) -> IResult<Span, Option<NamespaceItem>, E> {
+// Whitespace that contains at least one empty line
+fn blank_lines<E: ParseError<Span>>(i: Span) -> IResult<Span, Span, E> {
+    verify(sp, |s: &Span| s.slice().matches('\n').count() >= 2)(i)
+}
+
+// Any namespace item, or comments and blank lines between items
+fn nsitem<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    i: Span,
+) -> IResult<Span, NamespaceItem, E> {
     alt((
-        map(
+        map(blank_lines, |_| NamespaceItem::Trivia(Trivia::BlankLine)),
+        preceded(
+            sp,
             alt((
-                map(fndecl, NamespaceItem::Function),
+                map(|i| fndecl(recovered, i), NamespaceItem::Function),
                 map(notifdecl, NamespaceItem::Function),
                 map(structdecl, NamespaceItem::Struct),
                 map(enumdecl, NamespaceItem::Enum),
-                map(nsdecl, NamespaceItem::Namespace),
+                map(uniondecl, NamespaceItem::Union),
+                map(|i| nsdecl(recovered, i), NamespaceItem::Namespace),
+                map(comment, |c| NamespaceItem::Trivia(Trivia::Comment(c))),
             )),
-            Some,
         ),
-        map(comment_line, |_| None),
     ))(i)
 }
 
+// Items of a namespace or function body, up until a closing brace
+// or the end of the input. Anything else is reported and skipped.
+fn nsitems<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    mut i: Span,
+) -> IResult<Span, Vec<NamespaceItem>, E> {
+    let mut items = Vec::new();
+    loop {
+        let (rest, mut parsed) = recover_many0(recovered, |i| nsitem(recovered, i))(i)?;
+        items.append(&mut parsed);
+        let (rest, _) = sp(rest)?;
+        if rest.len() == 0 || rest.slice().starts_with('}') {
+            return Ok((rest, items));
+        }
+
+        i = recovered.skip(
+            E::add_context(
+                rest.clone(),
+                "namespace item",
+                E::from_error_kind(rest.clone(), ErrorKind::Alt),
+            ),
+            &rest,
+        );
+    }
+}
+
 // A namespace body, but also a function body.
-fn nsbody<E: ParseError<Span>>(i: Span) -> IResult<Span, NamespaceBody, E> {
-    terminated(
-        map(many0(spaced(nsitem)), |mut x| {
-            NamespaceBody::new(x.drain(..).filter_map(|x| x).collect())
-        }),
-        spaced(many0(comment_line)),
-    )(i)
+fn nsbody<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    i: Span,
+) -> IResult<Span, NamespaceBody, E> {
+    map(spanned(|i| nsitems(recovered, i)), |(span, items)| {
+        NamespaceBody::new(span, items)
+    })(i)
 }
 
 // A namespace declaration: `namespace X { nsbody }`
-fn nsdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, NamespaceDecl, E> {
-    let (i, comment) = opt(comment)(i)?;
+fn nsdecl<E: ParseError<Span>>(
+    recovered: &Recovered<E>,
+    i: Span,
+) -> IResult<Span, NamespaceDecl, E> {
+    let (i, comment) = opt(doc_comment)(i)?;
     let (i, _) = terminated(preceded(sp, tag("namespace")), sp)(i)?;
     let (i, loc) = spaced(loc)(i)?;
 
@@ -588,7 +860,11 @@ fn nsdecl<E: ParseError<Span>>(i: Span) -> IResult<Span, NamespaceDecl, E> {
         cut(map(
             tuple((
                 spaced(id),
-                delimited(spaced(char('{')), nsbody, spaced(char('}'))),
+                delimited(
+                    spaced(char('{')),
+                    |i| nsbody(recovered, i),
+                    preceded(sp, char('}')),
+                ),
             )),
             move |(name, items)| NamespaceDecl::new(name, loc.clone(), comment.clone(), items),
         )),
@@ -669,6 +945,30 @@ mod tests {
 
     rules_passing!(build_local);
     rules_passing!(build_remote);
+    rules_passing!(build_file_url);
+
+    rules_passing!(export_only);
+    rules_passing!(export_and_build);
+    rules_failing!(export_no_name, "parsing error: Tag");
+
+    rules_passing!(lints);
+    rules_failing!(lint_no_boundary, "parsing error: Eof");
+    rules_failing!(lint_no_id, "parsing error: TakeWhile1");
+
+    macro_rules! lockfile_passing {
+        ($name: ident) => {
+            parse_passing!(parse_lockfile, $name);
+        };
+    }
+
+    macro_rules! lockfile_failing {
+        ($name: ident, $needle: expr) => {
+            parse_failing!(parse_lockfile, $name, $needle);
+        };
+    }
+
+    lockfile_passing!(lock_simple);
+    lockfile_failing!(lock_no_hash, "parsing error: Tag");
 
     schema_passing!(struct_cookie);
     schema_passing!(struct_comments);
@@ -680,4 +980,121 @@ mod tests {
     schema_passing!(nf_simple);
 
     schema_passing!(enums);
+    schema_passing!(enum_values);
+
+    schema_failing!(unknown_types, "num_errors: 4");
+    schema_failing!(redefinitions, "num_errors: 4");
+    schema_failing!(name_clashes, "num_errors: 4");
+    schema_failing!(wrong_side, "num_errors: 3");
+
+    schema_passing!(unions);
+    schema_failing!(union_unknown_member, "num_errors: 1");
+    schema_failing!(union_duplicate_member, "num_errors: 1");
+    schema_passing!(recursive_types);
+    schema_failing!(recursive_types_infinite, "num_errors: 5");
+    schema_passing!(map_keys);
+    schema_failing!(map_keys_invalid, "num_errors: 6");
+    schema_failing!(enum_value_missing, "parsing error: Alt");
+    schema_failing!(enum_values_mixed, "num_errors: 1");
+
+    #[test]
+    fn lint_directives() {
+        use crate::ast::Severity;
+
+        let rules = parse_rules(Source::from_string(include_str!("tests/lints.lavish"))).unwrap();
+        let lints: Vec<_> = rules
+            .lints
+            .iter()
+            .map(|l| (l.severity, l.id.text()))
+            .collect();
+        assert_eq!(
+            lints,
+            vec![
+                (Severity::Allow, "non_camel_case_types"),
+                (Severity::Deny, "non_snake_case"),
+                (Severity::Warn, "leading_digit"),
+            ]
+        );
+    }
+
+    #[test]
+    fn recovery() {
+        use super::sink;
+
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = parse_schema(Source::from_string(include_str!("tests/recovery.lavish")));
+        sink::set(Box::new(sink::Colored));
+
+        let (errors, checker) = match res {
+            Err(Error::Schema { syntax, checker }) => (syntax, checker),
+            res => panic!(
+                "expected syntax and checker errors, got {:?}",
+                res.map(|_| ())
+            ),
+        };
+        assert_eq!(checker.num_errors, 1);
+        let locations: Vec<(usize, usize)> = errors
+            .iter()
+            .map(|e| {
+                let record = sink::Record::new(&e.diagnostic());
+                assert_eq!(record.code, Some("syntax_error"));
+                (record.start.line, record.start.column)
+            })
+            .collect();
+        assert_eq!(locations, vec![(2, 4), (18, 24), (26, 12)]);
+
+        // the checker still ran on what did parse, but only reports
+        // types that were never declared, not the broken ones.
+        let records = collector.take();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].code, Some("unknown_type"));
+        assert_eq!(
+            records[0].message,
+            "unknown type \"Missing\": not a built-in, and not in scope either"
+        );
+        assert_eq!(
+            records[0].start,
+            sink::Location {
+                line: 13,
+                column: 13
+            }
+        );
+    }
+
+    #[test]
+    fn diagnostics_sink() {
+        use super::sink;
+
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = parse_schema(Source::from_string(include_str!(
+            "tests/redefinitions.lavish"
+        )));
+        sink::set(Box::new(sink::Colored));
+        assert!(res.is_err());
+
+        let records = collector.take();
+        assert_eq!(records.len(), 4);
+        let first = &records[0];
+        assert_eq!(first.level, Level::Error);
+        assert_eq!(first.code, Some("redefinition"));
+        assert_eq!(first.message, "struct Session redefined");
+        assert_eq!(first.start, sink::Location { line: 4, column: 7 });
+        assert_eq!(
+            first.end,
+            sink::Location {
+                line: 4,
+                column: 14
+            }
+        );
+        assert_eq!(first.notes.len(), 1);
+        assert_eq!(first.notes[0].level, Level::Info);
+        assert_eq!(first.notes[0].message, "first definition was here");
+
+        let json = first.to_json();
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["range"]["start"]["line"], 5);
+        assert_eq!(json["notes"][0]["range"]["start"]["column"], 8);
+    }
 }
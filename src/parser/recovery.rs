@@ -0,0 +1,233 @@
+use super::Span;
+use nom::{error::ParseError, Err, IResult, InputTake};
+use std::cell::RefCell;
+
+/// Errors the schema parser recovered from, in the order they were
+/// found. Once an item is known to be broken, its error goes here, and
+/// parsing resumes at the next item so that every syntax error of a
+/// schema can be reported in a single run.
+pub struct Recovered<E> {
+    errors: RefCell<Vec<E>>,
+    /// Names of the items that were skipped, when they got that far
+    names: RefCell<Vec<String>>,
+}
+
+impl<E> Default for Recovered<E> {
+    fn default() -> Self {
+        Self {
+            errors: RefCell::new(Vec::new()),
+            names: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<E> Recovered<E> {
+    pub fn push(&self, e: E) {
+        self.errors.borrow_mut().push(e);
+    }
+
+    /// Records the error of the item starting at `i`, and skips it.
+    pub fn skip(&self, e: E, i: &Span) -> Span {
+        self.push(e);
+        if let Some(name) = item_name(i) {
+            self.names.borrow_mut().push(name);
+        }
+        skip_item(i)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.names.borrow().clone()
+    }
+
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors.into_inner()
+    }
+}
+
+/// Keywords that come before the name of an item
+const ITEM_KEYWORDS: &[&str] = &[
+    "import",
+    "server",
+    "client",
+    "fn",
+    "nf",
+    "struct",
+    "enum",
+    "union",
+    "namespace",
+];
+
+/// Keywords that start an item, at the beginning of a line
+const BOUNDARIES: &[&str] = &[
+    "import",
+    "server",
+    "client",
+    "struct",
+    "enum",
+    "union",
+    "namespace",
+    "//",
+];
+
+/// Like `many0`, but when `f` fails after committing to an item (with
+/// `cut`), the error is recorded and the item skipped.
+pub fn recover_many0<'a, O, E, F>(
+    recovered: &'a Recovered<E>,
+    f: F,
+) -> impl Fn(Span) -> IResult<Span, Vec<O>, E> + 'a
+where
+    E: ParseError<Span>,
+    F: Fn(Span) -> IResult<Span, O, E> + 'a,
+{
+    move |mut i: Span| {
+        let mut items = Vec::new();
+        loop {
+            match f(i.clone()) {
+                Ok((rest, o)) => {
+                    if rest.offset == i.offset {
+                        return Ok((i, items));
+                    }
+                    items.push(o);
+                    i = rest;
+                }
+                Err(Err::Failure(e)) => {
+                    i = recovered.skip(e, &i);
+                }
+                Err(Err::Error(_)) => return Ok((i, items)),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Skips a broken item, returning what's left after it. The item ends
+/// at the closing brace that matches its first opening brace, before a
+/// closing brace that belongs to its parent, or before the next line
+/// that starts with an item keyword, either outside of braces, or not
+/// indented more than the broken item (in case it was never closed).
+pub fn skip_item(i: &Span) -> Span {
+    let text = i.slice();
+    let bytes = text.as_bytes();
+
+    // leading whitespace and doc comments belong to the broken item
+    let start = text.len() - skip_trivia(text).len();
+    if start == bytes.len() {
+        return rest(i, bytes.len());
+    }
+    let item_column = column(i, start);
+
+    let mut depth = 0;
+    let mut line_start = false;
+    let mut j = start + 1;
+    while j < bytes.len() {
+        let c = bytes[j] as char;
+        if c == '\n' {
+            line_start = true;
+            j += 1;
+            continue;
+        }
+        if bytes[j].is_ascii_whitespace() {
+            j += 1;
+            continue;
+        }
+
+        if line_start {
+            line_start = false;
+            let is_boundary = BOUNDARIES.iter().any(|kw| starts_with_word(&text[j..], kw));
+            if is_boundary && (depth == 0 || column(i, j) <= item_column) {
+                return rest(i, j);
+            }
+        }
+
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return rest(i, j),
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return rest(i, j + 1);
+                }
+            }
+            // braces in comments and strings don't count
+            '/' if text[j..].starts_with("//") => {
+                j += text[j..].find('\n').unwrap_or(text.len() - j);
+                continue;
+            }
+            '"' => {
+                j += 1;
+                while j < bytes.len() && bytes[j] != b'"' && bytes[j] != b'\n' {
+                    j += 1;
+                }
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    rest(i, bytes.len())
+}
+
+/// Returns the name of the item starting at `i`, like `Foo` for
+/// `struct Foo {`, if it got that far.
+fn item_name(i: &Span) -> Option<String> {
+    let mut text = skip_trivia(i.slice());
+    let mut keywords = 0;
+    loop {
+        let end = text
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(text.len());
+        let word = &text[..end];
+        if ITEM_KEYWORDS.contains(&word) {
+            keywords += 1;
+            text = text[end..].trim_start();
+        } else if keywords > 0 && !word.is_empty() {
+            return Some(word.into());
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Skips leading whitespace and comments
+fn skip_trivia(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        if text.starts_with("//") {
+            text = &text[text.find('\n').unwrap_or(text.len())..];
+        } else {
+            return text;
+        }
+    }
+}
+
+/// Skips a single character, for tokens that can't start an item
+pub fn skip_char(i: &Span) -> Span {
+    let len = i.slice().chars().next().map(char::len_utf8).unwrap_or(0);
+    rest(i, len)
+}
+
+/// What's left of `i` after its first `index` bytes
+fn rest(i: &Span, index: usize) -> Span {
+    i.take_split(index).0
+}
+
+fn starts_with_word(text: &str, word: &str) -> bool {
+    if !text.starts_with(word) {
+        return false;
+    }
+    if word == "//" {
+        return true;
+    }
+    match text[word.len()..].chars().next() {
+        Some(c) => !(c.is_alphanumeric() || c == '_'),
+        None => true,
+    }
+}
+
+/// Column of the byte at `index` in `i`
+fn column(i: &Span, index: usize) -> usize {
+    let input = &i.source.input[..i.offset + index];
+    match input.rfind('\n') {
+        Some(newline) => input.len() - newline - 1,
+        None => input.len(),
+    }
+}
@@ -0,0 +1,143 @@
+use super::{Diagnostic, Level, Position};
+use serde_json::json;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Where diagnostics go once reported. Checkers report as they find
+/// problems, so the sink is per-thread rather than passed around.
+pub trait Sink {
+    fn emit(&mut self, diag: &Diagnostic);
+}
+
+/// Prints diagnostics in color, for humans. This is the default.
+pub struct Colored;
+
+impl Sink for Colored {
+    fn emit(&mut self, diag: &Diagnostic) {
+        print!("{}", diag)
+    }
+}
+
+/// Prints diagnostics as JSON, one per line, for editors and CI.
+pub struct Json;
+
+impl Sink for Json {
+    fn emit(&mut self, diag: &Diagnostic) {
+        println!("{}", Record::new(diag).to_json())
+    }
+}
+
+/// Keeps diagnostics around so they can be inspected later.
+#[derive(Clone, Default)]
+pub struct Collector {
+    records: Rc<RefCell<Vec<Record>>>,
+}
+
+impl Collector {
+    /// Returns the diagnostics collected so far, and forgets them.
+    pub fn take(&self) -> Vec<Record> {
+        self.records.replace(Vec::new())
+    }
+}
+
+impl Sink for Collector {
+    fn emit(&mut self, diag: &Diagnostic) {
+        self.records.borrow_mut().push(Record::new(diag))
+    }
+}
+
+thread_local! {
+    static SINK: RefCell<Box<dyn Sink>> = RefCell::new(Box::new(Colored));
+}
+
+/// Sends diagnostics reported by this thread to `sink` from now on.
+pub fn set(sink: Box<dyn Sink>) {
+    SINK.with(|s| *s.borrow_mut() = sink)
+}
+
+pub fn emit(diag: &Diagnostic) {
+    SINK.with(|s| s.borrow_mut().emit(diag))
+}
+
+/// A line and a column, both starting at zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<&Position> for Location {
+    fn from(pos: &Position) -> Self {
+        Self {
+            line: pos.line,
+            column: pos.column,
+        }
+    }
+}
+
+/// A diagnostic that owns everything it needs, without colors.
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub level: Level,
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub file: String,
+    pub start: Location,
+    pub end: Location,
+    pub notes: Vec<Record>,
+}
+
+impl Record {
+    pub fn new(diag: &Diagnostic) -> Self {
+        Self {
+            level: diag.level(),
+            code: diag.code(),
+            message: strip_colors(diag.message()),
+            file: diag.start().span.source.name().to_string(),
+            start: diag.start().into(),
+            end: (&diag.end()).into(),
+            notes: diag.notes().iter().map(Record::new).collect(),
+        }
+    }
+
+    /// Lines and columns start at one here, like in the colored output.
+    pub fn to_json(&self) -> serde_json::Value {
+        let location = |loc: &Location| {
+            json!({
+                "line": loc.line + 1,
+                "column": loc.column + 1,
+            })
+        };
+        json!({
+            "severity": self.level.as_str(),
+            "code": self.code,
+            "message": self.message,
+            "file": self.file,
+            "range": {
+                "start": location(&self.start),
+                "end": location(&self.end),
+            },
+            "notes": self.notes.iter().map(Record::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Removes ANSI escape sequences, which some messages use to
+/// highlight parts of themselves.
+fn strip_colors(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // skip until the end of the sequence, like `m` in `\x1b[1;31m`
+            for c in &mut chars {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
@@ -54,10 +54,12 @@ impl Span {
 
     pub fn position(&self) -> Position {
         let mut offset = self.offset;
-        let mut line = 0;
-        let mut column = 0;
+        // past the last line, for the end of input
+        let lines = &self.source.lines;
+        let mut line = lines.len().saturating_sub(1);
+        let mut column = lines.last().map(|l| l.len()).unwrap_or(0);
 
-        for (j, l) in self.source.lines.iter().enumerate() {
+        for (j, l) in lines.iter().enumerate() {
             if offset <= l.len() {
                 line = j;
                 column = offset;
@@ -0,0 +1,594 @@
+use crate::{ast, checker, codegen, fetcher, parser};
+use indexmap::IndexMap;
+use simple_error::SimpleError;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+pub fn parse_rules(workspace_path: &Path) -> Result<ast::Rules> {
+    let rules_path = workspace_path.join(ast::RULES_FILE);
+    if !rules_path.exists() {
+        return Err(Box::new(SimpleError::new(format!(
+            "{:?}: not a workspace (does not contain a 'lavish-rules' file)",
+            workspace_path
+        ))));
+    }
+
+    let source = parser::Source::from_path(&rules_path)?;
+    Ok(parser::parse_rules(source)?)
+}
+
+/// Parses the rules of a workspace, the schemas it builds, and
+/// everything they import. Imported schemas become members of
+/// the workspace too, so that builds share their types.
+///
+/// Every member is checked, even if an earlier one has errors: the
+/// returned error then counts all of them.
+pub fn parse_workspace(opts: &crate::Opts, workspace_path: &Path) -> Result<ast::Workspace> {
+    let rules = parse_rules(workspace_path)?;
+
+    let mut workspace = ast::Workspace {
+        dir: workspace_path.into(),
+        rules,
+        members: BTreeMap::new(),
+    };
+
+    if opts.verbose {
+        println!("Building {} modules", workspace.rules.builds.len());
+    }
+    let (builds, imported) = {
+        let mut loader = Loader::new(opts, &workspace)?;
+        let mut builds = Vec::new();
+        for build in &workspace.rules.builds {
+            if !loader.check_build_name(&build.name) {
+                loader.member_errors += 1;
+            }
+            match loader.load_source(&workspace.dir, &build.name, build.from.as_ref()) {
+                Ok(schema) => builds.push((build.clone(), schema)),
+                Err(e) => loader.count_errors(e)?,
+            }
+        }
+        (builds, loader.finish()?)
+    };
+
+    for (build, schema) in builds {
+        let name = build.name.text().to_string();
+        workspace.members.insert(
+            name.clone(),
+            ast::WorkspaceMember {
+                name,
+                build: Some(build),
+                imports: schema.imports.clone(),
+                schema: Some(schema),
+            },
+        );
+    }
+
+    for (name, schema) in imported {
+        // builds are generated anyway, whatever they're imported as
+        let built = workspace
+            .members
+            .values()
+            .any(|m| m.schema.as_ref().map(|s| Rc::ptr_eq(s, &schema)) == Some(true));
+        if built || workspace.members.contains_key(&name) {
+            continue;
+        }
+        workspace.members.insert(
+            name.clone(),
+            ast::WorkspaceMember {
+                name,
+                build: None,
+                imports: schema.imports.clone(),
+                schema: Some(schema),
+            },
+        );
+    }
+
+    Ok(workspace)
+}
+
+struct Loader<'a> {
+    opts: &'a crate::Opts,
+    workspace: &'a ast::Workspace,
+    lockfile: Option<ast::Lockfile>,
+    levels: checker::Levels,
+    /// Reserved words of the target, if there's one
+    reserved: Option<Box<dyn codegen::ReservedWords>>,
+
+    /// Schemas that were already parsed, by canonical path
+    parsed: HashMap<PathBuf, Rc<ast::Schema>>,
+    /// Schemas currently being loaded, to detect import cycles
+    loading: Vec<PathBuf>,
+    /// Schemas that failed to load, and were reported already
+    broken: HashSet<PathBuf>,
+    /// Every imported schema, by import name
+    imported: IndexMap<String, Rc<ast::Schema>>,
+    /// Where each name was first built or imported from
+    sites: HashMap<String, Site>,
+
+    /// Errors found while resolving imports
+    num_errors: i64,
+    /// Errors found in members, which are reported as they're loaded
+    member_errors: i64,
+}
+
+impl<'a> Loader<'a> {
+    fn new(opts: &'a crate::Opts, workspace: &'a ast::Workspace) -> Result<Self> {
+        Ok(Self {
+            opts,
+            workspace,
+            lockfile: fetcher::lock::read(&workspace.dir)?,
+            levels: checker::Levels::new(&workspace.rules)?,
+            reserved: workspace.rules.target.as_ref().map(codegen::reserved_words),
+            parsed: HashMap::new(),
+            loading: Vec::new(),
+            broken: HashSet::new(),
+            imported: IndexMap::new(),
+            sites: HashMap::new(),
+            num_errors: 0,
+            member_errors: 0,
+        })
+    }
+
+    /// Loads the schema of a build or an import, checking it against
+    /// `lavish-lock` if it was vendored.
+    fn load_source(
+        &mut self,
+        base_dir: &Path,
+        name: &ast::Identifier,
+        from: Option<&ast::FromDirective>,
+    ) -> Result<Rc<ast::Schema>> {
+        let path = self.workspace.resolve_source(base_dir, name, from)?;
+
+        if let (Some(lockfile), Some(from)) = (self.lockfile.as_ref(), from) {
+            if !from.is_local() && !fetcher::lock::check(lockfile, name, from, &path)? {
+                self.num_errors += 1;
+            }
+        }
+
+        // every remote source is vendored under its name, so two of them
+        // would resolve to the same path: tell them apart by their URL.
+        let source = match from {
+            Some(from) if !from.is_local() => from.path.value.clone(),
+            _ => {
+                let path = path.canonicalize()?;
+                let dir = self.workspace.dir.canonicalize()?;
+                path.strip_prefix(&dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into()
+            }
+        };
+        if !self.check_site(name, source) {
+            self.num_errors += 1;
+        }
+
+        self.load(name, &path)
+    }
+
+    fn load(&mut self, name: &ast::Identifier, path: &Path) -> Result<Rc<ast::Schema>> {
+        let key = path.canonicalize()?;
+        if let Some(schema) = self.parsed.get(&key) {
+            return Ok(schema.clone());
+        }
+        if self.broken.contains(&key) {
+            // its errors were counted when it first failed
+            return Err(Box::new(checker::Error { num_errors: 0 }));
+        }
+
+        let depth = self.loading.len();
+        let res = self.load_uncached(name, path, &key);
+        if res.is_err() {
+            // a failed import leaves its importers on the stack
+            self.loading.truncate(depth);
+            self.broken.insert(key);
+        }
+        res
+    }
+
+    fn load_uncached(
+        &mut self,
+        name: &ast::Identifier,
+        path: &Path,
+        key: &Path,
+    ) -> Result<Rc<ast::Schema>> {
+        if self.loading.iter().any(|p| p == key) {
+            name.span
+                .position()
+                .diag_err(format!("import cycle: {:?} ends up importing itself", path,))
+                .code("import_cycle")
+                .print();
+            return Err(Box::new(SimpleError::new("import cycle")));
+        }
+
+        if self.opts.verbose {
+            println!("Parsing {} from {:?}", name.text(), path);
+        }
+        let source = parser::Source::from_path(path)?;
+        let mut schema = parser::parse_schema(source)?;
+        let mut results = vec![checker::lint(&schema, &self.levels)];
+        if let Some(words) = self.reserved.as_ref() {
+            results.push(checker::reserved(&schema, words.as_ref()));
+        }
+        checker::merge(results)?;
+
+        // local imports are relative to the importing schema
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        self.loading.push(key.to_path_buf());
+        for import in &mut schema.imports {
+            let imported = self.load_source(base_dir, &import.name, import.from.as_ref())?;
+            if !self.imported.values().any(|s| Rc::ptr_eq(s, &imported)) {
+                self.imported
+                    .entry(import.name.text().to_string())
+                    .or_insert_with(|| imported.clone());
+            }
+            import.schema = Some(imported);
+        }
+        self.loading.pop();
+        if !schema.imports.is_empty() {
+            checker::check_imported_types(&schema)?;
+        }
+
+        let schema = Rc::new(schema);
+        self.parsed.insert(key.to_path_buf(), schema.clone());
+        Ok(schema)
+    }
+
+    /// Counts the errors of a build that failed to load, so that the
+    /// next ones still get checked. Syntax errors are only returned by
+    /// the parser, so they're reported here. Other errors are fatal.
+    fn count_errors(&mut self, e: Box<dyn std::error::Error>) -> Result<()> {
+        if let Some(e) = e.downcast_ref::<checker::Error>() {
+            self.member_errors += e.num_errors;
+            return Ok(());
+        }
+        match e.downcast_ref::<parser::Error>() {
+            Some(parser::Error::Source(errors)) => {
+                for e in errors {
+                    e.diagnostic().print();
+                }
+                self.member_errors += errors.len() as i64;
+            }
+            Some(parser::Error::Schema { syntax, checker }) => {
+                for e in syntax {
+                    e.diagnostic().print();
+                }
+                self.member_errors += syntax.len() as i64 + checker.num_errors;
+            }
+            Some(parser::Error::Checker(e)) => self.member_errors += e.num_errors,
+            _ => return Err(e),
+        }
+        Ok(())
+    }
+
+    /// Makes sure the target can name the module of a build: imports
+    /// are checked along with their schema. Prints a diagnostic and
+    /// returns false if not.
+    fn check_build_name(&self, name: &ast::Identifier) -> bool {
+        let words = match self.reserved.as_ref() {
+            Some(words) => words,
+            None => return true,
+        };
+        if !words.is_unusable(codegen::Usage::Module, name.text()) {
+            return true;
+        }
+
+        name.span
+            .position()
+            .diag_err(format!(
+                "build {} is a reserved word in {}, and can't be used as a name",
+                name.text(),
+                words.language(),
+            ))
+            .code("reserved_word")
+            .print();
+        false
+    }
+
+    /// Makes sure a name always refers to the same schema across the
+    /// workspace: members share the types of their imports, so they can't
+    /// each have their own version. Prints a diagnostic and returns false if not.
+    fn check_site(&mut self, name: &ast::Identifier, source: String) -> bool {
+        let first = match self.sites.get(name.text()) {
+            Some(first) => first,
+            None => {
+                self.sites.insert(
+                    name.text().to_string(),
+                    Site {
+                        name: name.clone(),
+                        source,
+                    },
+                );
+                return true;
+            }
+        };
+        if first.source == source {
+            return true;
+        }
+
+        name.span
+            .position()
+            .diag_err(format!(
+                "{} comes from {:?} here, but from {:?} elsewhere in the workspace",
+                name.text(),
+                source,
+                first.source,
+            ))
+            .code("conflicting_sources")
+            .note(
+                first.name.span.position(),
+                format!("{} first comes from {:?} here", name.text(), first.source),
+            )
+            .print();
+        false
+    }
+
+    /// Returns every imported schema, or an error if any
+    /// problems were reported while loading.
+    fn finish(self) -> Result<IndexMap<String, Rc<ast::Schema>>> {
+        if self.member_errors > 0 {
+            return Err(Box::new(checker::Error {
+                num_errors: self.member_errors + self.num_errors,
+            }));
+        }
+        if self.num_errors > 0 {
+            return Err(Box::new(SimpleError::new(match self.num_errors {
+                1 => "1 error while resolving imports".to_string(),
+                n => format!("{} errors while resolving imports", n),
+            })));
+        }
+        Ok(self.imported)
+    }
+}
+
+/// A build or an import, and the schema it resolved to
+struct Site {
+    name: ast::Identifier,
+    source: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::sink;
+
+    fn parse_test_workspace(name: &str) -> Result<ast::Workspace> {
+        let opts = crate::Opts { verbose: false };
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("workspace")
+            .join("tests")
+            .join(name);
+        parse_workspace(&opts, &dir)
+    }
+
+    /// Parses a test workspace, along with the diagnostics it printed
+    fn collect_test_workspace(name: &str) -> (Result<ast::Workspace>, Vec<sink::Record>) {
+        let collector = sink::Collector::default();
+        sink::set(Box::new(collector.clone()));
+        let res = parse_test_workspace(name);
+        sink::set(Box::new(sink::Colored));
+        (res, collector.take())
+    }
+
+    #[test]
+    fn shared_import() -> Result<()> {
+        let workspace = parse_test_workspace("shared_import")?;
+
+        let mut names: Vec<_> = workspace.members.keys().cloned().collect();
+        names.sort();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert!(workspace.members["c"].build.is_none());
+
+        // both builds must point to the very same schema for `c`
+        let a = workspace.members["a"].schema.as_ref().unwrap();
+        let b = workspace.members["b"].schema.as_ref().unwrap();
+        assert!(a.imports[0].same_schema(&b.imports[0]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_imports() {
+        let (res, records) = collect_test_workspace("conflicting_imports");
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "1 error while resolving imports"
+        );
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.code, Some("conflicting_sources"));
+        assert_eq!(
+            record.message,
+            "c comes from \"v2/c.lavish\" here, but from \"v1/c.lavish\" elsewhere in the workspace"
+        );
+        assert!(record.file.ends_with("b.lavish"));
+        assert_eq!(record.start, sink::Location { line: 0, column: 7 });
+
+        assert_eq!(record.notes.len(), 1);
+        let note = &record.notes[0];
+        assert_eq!(note.message, "c first comes from \"v1/c.lavish\" here");
+        assert!(note.file.ends_with("a.lavish"));
+        assert_eq!(note.start, sink::Location { line: 0, column: 7 });
+    }
+
+    /// The lint, severity and line of every diagnostic
+    fn lint_summary(records: &[sink::Record]) -> Vec<(&str, parser::Level, usize)> {
+        records
+            .iter()
+            .map(|r| (r.code.unwrap_or(""), r.level, r.start.line))
+            .collect()
+    }
+
+    #[test]
+    fn lints_allowed() {
+        let (res, records) = collect_test_workspace("lints_allowed");
+        assert!(res.is_ok());
+        assert_eq!(
+            lint_summary(&records),
+            vec![("non_snake_case", parser::Level::Warning, 3)]
+        );
+    }
+
+    #[test]
+    fn lints_denied() {
+        let (res, records) = collect_test_workspace("lints_denied");
+        assert_eq!(format!("{}", res.unwrap_err()), "3 errors found");
+        assert_eq!(
+            lint_summary(&records),
+            vec![
+                ("non_snake_case", parser::Level::Error, 1),
+                ("leading_digit", parser::Level::Error, 5),
+                ("non_snake_case", parser::Level::Error, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_lint() {
+        let (res, records) = collect_test_workspace("unknown_lint");
+        assert_eq!(format!("{}", res.unwrap_err()), "1 error found");
+        assert_eq!(
+            lint_summary(&records),
+            vec![("unknown_lint", parser::Level::Error, 2)]
+        );
+        assert!(records[0]
+            .message
+            .starts_with("unknown lint non_kebab_case"));
+    }
+
+    #[test]
+    fn errors_in_every_member() {
+        let (res, records) = collect_test_workspace("errors_in_every_member");
+        assert_eq!(format!("{}", res.unwrap_err()), "5 errors found");
+
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| {
+                let file = Path::new(&r.file).file_name().unwrap().to_owned();
+                (file.into_string().unwrap(), r.code, r.start.line)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a.lavish".into(), Some("redefinition"), 5),
+                ("a.lavish".into(), Some("unknown_type"), 1),
+                ("b.lavish".into(), Some("syntax_error"), 3),
+                ("c.lavish".into(), Some("unknown_type"), 7),
+                ("c.lavish".into(), Some("syntax_error"), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn data_map_keys() {
+        let err = parse_test_workspace("data_map_keys").unwrap_err();
+        assert_eq!(format!("{}", err), "1 error found");
+    }
+
+    #[test]
+    fn data_map_keys_allowed() -> Result<()> {
+        parse_test_workspace("data_map_keys_allowed")?;
+        Ok(())
+    }
+
+    #[test]
+    fn reserved_words() {
+        let err = parse_test_workspace("reserved_words").unwrap_err();
+        assert_eq!(format!("{}", err), "3 errors found");
+    }
+
+    #[test]
+    fn unknown_imported_type() {
+        let err = parse_test_workspace("unknown_imported_type").unwrap_err();
+        assert_eq!(format!("{}", err), "1 error found");
+    }
+
+    #[test]
+    fn go_name_collisions() {
+        let (res, records) = collect_test_workspace("go_name_collisions");
+        assert_eq!(format!("{}", res.unwrap_err()), "3 errors found");
+
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.code, r.start.line, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Some("name_collision"),
+                    7,
+                    "struct user_profile.Data is emitted as UserProfileData in Go, like struct user.ProfileData"
+                ),
+                (
+                    Some("name_collision"),
+                    14,
+                    "struct Caller is emitted as Caller in Go, which the generated code declares on its own"
+                ),
+                (
+                    Some("name_collision"),
+                    12,
+                    "params of function get is emitted as GetParams in Go, like struct GetParams"
+                ),
+            ]
+        );
+        assert_eq!(records[0].notes[0].start.line, 3);
+        assert_eq!(records[2].notes[0].start.line, 10);
+    }
+
+    #[test]
+    fn typescript_reserved_words() {
+        let (res, records) = collect_test_workspace("typescript_reserved_words");
+        assert_eq!(format!("{}", res.unwrap_err()), "3 errors found");
+
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.code, r.start.line, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Some("reserved_word"),
+                    6,
+                    "namespace default is a reserved word in TypeScript, and can't be used as a name"
+                ),
+                (
+                    Some("reserved_word"),
+                    9,
+                    "function delete is a reserved word in TypeScript, and can't be used as a name"
+                ),
+                (
+                    Some("reserved_word"),
+                    10,
+                    "function new is a reserved word in TypeScript, and can't be used as a name"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_reserved_words() {
+        let (res, records) = collect_test_workspace("go_reserved_words");
+        assert_eq!(format!("{}", res.unwrap_err()), "1 error found");
+
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.code, r.start.line, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(
+                Some("reserved_word"),
+                3,
+                "build type is a reserved word in Go, and can't be used as a name"
+            )]
+        );
+        assert!(records[0].file.ends_with("lavish-rules"));
+    }
+}
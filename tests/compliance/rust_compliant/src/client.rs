@@ -19,6 +19,17 @@ pub fn run(addr: &str) {
             };
         }
 
+        // generated types don't implement PartialEq
+        macro_rules! roundtrip_debug {
+            ($method: ident, $val: expr) => {
+                eq(
+                    stringify!($method),
+                    format!("{:?}", $val),
+                    format!("{:?}", client.call($method::Params { x: $val }).unwrap().x),
+                );
+            };
+        }
+
         roundtrip!(identity_u8, 0);
         roundtrip!(identity_u8, std::u8::MIN);
         roundtrip!(identity_u8, std::u8::MAX);
@@ -54,6 +65,31 @@ pub fn run(addr: &str) {
         roundtrip!(identity_bool, true);
         roundtrip!(identity_bool, false);
 
+        roundtrip_debug!(identity_enum, Mood::Negative);
+        roundtrip_debug!(identity_enum_string, LoginType::Password);
+        roundtrip_debug!(identity_enum_integer, Level::Guest);
+        roundtrip_debug!(identity_enum_integer, Level::Admin);
+
+        roundtrip_debug!(identity_union, Either::Mood(Mood::Positive));
+        roundtrip_debug!(
+            identity_union,
+            Either::Participant(Participant {
+                name: "Joe".to_string(),
+                age: 32,
+            })
+        );
+
+        roundtrip_debug!(
+            identity_recursive,
+            Node {
+                value: 1,
+                next: Some(Box::new(Node {
+                    value: 2,
+                    next: None,
+                })),
+            }
+        );
+
         roundtrip!(identity_string, "".to_string());
         roundtrip!(identity_string, "Short".to_string());
         roundtrip!(identity_string, "Long".to_string().repeat(128));
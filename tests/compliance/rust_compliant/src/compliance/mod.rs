@@ -29,6 +29,10 @@ pub mod protocol {
         Types_IdentityMapStringBool(super::schema::types::identity_map_string_bool::Params),
         Types_IdentityStruct(super::schema::types::identity_struct::Params),
         Types_IdentityEnum(super::schema::types::identity_enum::Params),
+        Types_IdentityEnumString(super::schema::types::identity_enum_string::Params),
+        Types_IdentityEnumInteger(super::schema::types::identity_enum_integer::Params),
+        Types_IdentityUnion(super::schema::types::identity_union::Params),
+        Types_IdentityRecursive(super::schema::types::identity_recursive::Params),
         Layered_Login_Challenge(super::schema::layered::login::challenge::Params),
         Layered_Login(super::schema::layered::login::Params),
         Lifetime_Shutdown(super::schema::lifetime::shutdown::Params),
@@ -55,6 +59,10 @@ pub mod protocol {
                 Params::Types_IdentityMapStringBool(_) => "types.identity_map_string_bool",
                 Params::Types_IdentityStruct(_) => "types.identity_struct",
                 Params::Types_IdentityEnum(_) => "types.identity_enum",
+                Params::Types_IdentityEnumString(_) => "types.identity_enum_string",
+                Params::Types_IdentityEnumInteger(_) => "types.identity_enum_integer",
+                Params::Types_IdentityUnion(_) => "types.identity_union",
+                Params::Types_IdentityRecursive(_) => "types.identity_recursive",
                 Params::Layered_Login_Challenge(_) => "layered.login.challenge",
                 Params::Layered_Login(_) => "layered.login",
                 Params::Lifetime_Shutdown(_) => "lifetime.shutdown",
@@ -69,7 +77,7 @@ pub mod protocol {
         {
             let len = rd.read_array_len()?;
             if len != 2 {
-                unreachable!();
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 2 elements, got {}", len)));
             }
             let typ: u32 = rd.read_int()?;
             match typ {
@@ -92,10 +100,14 @@ pub mod protocol {
                 16 => Ok(Params::Types_IdentityMapStringBool(Self::subread(rd)?)),
                 17 => Ok(Params::Types_IdentityStruct(Self::subread(rd)?)),
                 18 => Ok(Params::Types_IdentityEnum(Self::subread(rd)?)),
-                19 => Ok(Params::Layered_Login_Challenge(Self::subread(rd)?)),
-                20 => Ok(Params::Layered_Login(Self::subread(rd)?)),
-                21 => Ok(Params::Lifetime_Shutdown(Self::subread(rd)?)),
-                _ => unreachable!(),
+                19 => Ok(Params::Types_IdentityEnumString(Self::subread(rd)?)),
+                20 => Ok(Params::Types_IdentityEnumInteger(Self::subread(rd)?)),
+                21 => Ok(Params::Types_IdentityUnion(Self::subread(rd)?)),
+                22 => Ok(Params::Types_IdentityRecursive(Self::subread(rd)?)),
+                23 => Ok(Params::Layered_Login_Challenge(Self::subread(rd)?)),
+                24 => Ok(Params::Layered_Login(Self::subread(rd)?)),
+                25 => Ok(Params::Lifetime_Shutdown(Self::subread(rd)?)),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized variant for Params: {}", typ))),
             }
         }
 
@@ -104,52 +116,59 @@ pub mod protocol {
             W: ::std::io::Write,
         {
             let o = &mapping.__Params;
-            match self {
-                Params::Types_IdentityU8(value) =>
+            match *self {
+                Params::Types_IdentityU8(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityU8", 0, value),
-                Params::Types_IdentityU16(value) =>
+                Params::Types_IdentityU16(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityU16", 1, value),
-                Params::Types_IdentityU32(value) =>
+                Params::Types_IdentityU32(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityU32", 2, value),
-                Params::Types_IdentityU64(value) =>
+                Params::Types_IdentityU64(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityU64", 3, value),
-                Params::Types_IdentityI8(value) =>
+                Params::Types_IdentityI8(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityI8", 4, value),
-                Params::Types_IdentityI16(value) =>
+                Params::Types_IdentityI16(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityI16", 5, value),
-                Params::Types_IdentityI32(value) =>
+                Params::Types_IdentityI32(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityI32", 6, value),
-                Params::Types_IdentityI64(value) =>
+                Params::Types_IdentityI64(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityI64", 7, value),
-                Params::Types_IdentityMultiple(value) =>
+                Params::Types_IdentityMultiple(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityMultiple", 8, value),
-                Params::Types_IdentityBool(value) =>
+                Params::Types_IdentityBool(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityBool", 9, value),
-                Params::Types_IdentityString(value) =>
+                Params::Types_IdentityString(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityString", 10, value),
-                Params::Types_IdentityData(value) =>
+                Params::Types_IdentityData(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityData", 11, value),
-                Params::Types_IdentityTimestamp(value) =>
+                Params::Types_IdentityTimestamp(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityTimestamp", 12, value),
-                Params::Types_IdentityArrayString(value) =>
+                Params::Types_IdentityArrayString(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityArrayString", 13, value),
-                Params::Types_IdentityArrayOptionU32(value) =>
+                Params::Types_IdentityArrayOptionU32(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityArrayOptionU32", 14, value),
-                Params::Types_IdentityOptionArrayU8(value) =>
+                Params::Types_IdentityOptionArrayU8(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityOptionArrayU8", 15, value),
-                Params::Types_IdentityMapStringBool(value) =>
+                Params::Types_IdentityMapStringBool(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityMapStringBool", 16, value),
-                Params::Types_IdentityStruct(value) =>
+                Params::Types_IdentityStruct(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityStruct", 17, value),
-                Params::Types_IdentityEnum(value) =>
+                Params::Types_IdentityEnum(ref value) =>
                     o.write_union(wr, mapping, "Params", "Types_IdentityEnum", 18, value),
-                Params::Layered_Login_Challenge(value) =>
-                    o.write_union(wr, mapping, "Params", "Layered_Login_Challenge", 19, value),
-                Params::Layered_Login(value) =>
-                    o.write_union(wr, mapping, "Params", "Layered_Login", 20, value),
-                Params::Lifetime_Shutdown(value) =>
-                    o.write_union(wr, mapping, "Params", "Lifetime_Shutdown", 21, value),
-                _ => unreachable!(),
+                Params::Types_IdentityEnumString(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Types_IdentityEnumString", 19, value),
+                Params::Types_IdentityEnumInteger(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Types_IdentityEnumInteger", 20, value),
+                Params::Types_IdentityUnion(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Types_IdentityUnion", 21, value),
+                Params::Types_IdentityRecursive(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Types_IdentityRecursive", 22, value),
+                Params::Layered_Login_Challenge(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Layered_Login_Challenge", 23, value),
+                Params::Layered_Login(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Layered_Login", 24, value),
+                Params::Lifetime_Shutdown(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Lifetime_Shutdown", 25, value),
             }
         }
     }
@@ -176,6 +195,10 @@ pub mod protocol {
         Types_IdentityMapStringBool(super::schema::types::identity_map_string_bool::Results),
         Types_IdentityStruct(super::schema::types::identity_struct::Results),
         Types_IdentityEnum(super::schema::types::identity_enum::Results),
+        Types_IdentityEnumString(super::schema::types::identity_enum_string::Results),
+        Types_IdentityEnumInteger(super::schema::types::identity_enum_integer::Results),
+        Types_IdentityUnion(super::schema::types::identity_union::Results),
+        Types_IdentityRecursive(super::schema::types::identity_recursive::Results),
         Layered_Login_Challenge(super::schema::layered::login::challenge::Results),
         Layered_Login(super::schema::layered::login::Results),
         Lifetime_Shutdown(super::schema::lifetime::shutdown::Results),
@@ -202,6 +225,10 @@ pub mod protocol {
                 Results::Types_IdentityMapStringBool(_) => "types.identity_map_string_bool",
                 Results::Types_IdentityStruct(_) => "types.identity_struct",
                 Results::Types_IdentityEnum(_) => "types.identity_enum",
+                Results::Types_IdentityEnumString(_) => "types.identity_enum_string",
+                Results::Types_IdentityEnumInteger(_) => "types.identity_enum_integer",
+                Results::Types_IdentityUnion(_) => "types.identity_union",
+                Results::Types_IdentityRecursive(_) => "types.identity_recursive",
                 Results::Layered_Login_Challenge(_) => "layered.login.challenge",
                 Results::Layered_Login(_) => "layered.login",
                 Results::Lifetime_Shutdown(_) => "lifetime.shutdown",
@@ -216,7 +243,7 @@ pub mod protocol {
         {
             let len = rd.read_array_len()?;
             if len != 2 {
-                unreachable!();
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 2 elements, got {}", len)));
             }
             let typ: u32 = rd.read_int()?;
             match typ {
@@ -239,10 +266,14 @@ pub mod protocol {
                 16 => Ok(Results::Types_IdentityMapStringBool(Self::subread(rd)?)),
                 17 => Ok(Results::Types_IdentityStruct(Self::subread(rd)?)),
                 18 => Ok(Results::Types_IdentityEnum(Self::subread(rd)?)),
-                19 => Ok(Results::Layered_Login_Challenge(Self::subread(rd)?)),
-                20 => Ok(Results::Layered_Login(Self::subread(rd)?)),
-                21 => Ok(Results::Lifetime_Shutdown(Self::subread(rd)?)),
-                _ => unreachable!(),
+                19 => Ok(Results::Types_IdentityEnumString(Self::subread(rd)?)),
+                20 => Ok(Results::Types_IdentityEnumInteger(Self::subread(rd)?)),
+                21 => Ok(Results::Types_IdentityUnion(Self::subread(rd)?)),
+                22 => Ok(Results::Types_IdentityRecursive(Self::subread(rd)?)),
+                23 => Ok(Results::Layered_Login_Challenge(Self::subread(rd)?)),
+                24 => Ok(Results::Layered_Login(Self::subread(rd)?)),
+                25 => Ok(Results::Lifetime_Shutdown(Self::subread(rd)?)),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized variant for Results: {}", typ))),
             }
         }
 
@@ -251,62 +282,75 @@ pub mod protocol {
             W: ::std::io::Write,
         {
             let o = &mapping.__Results;
-            match self {
-                Results::Types_IdentityU8(value) =>
+            match *self {
+                Results::Types_IdentityU8(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityU8", 0, value),
-                Results::Types_IdentityU16(value) =>
+                Results::Types_IdentityU16(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityU16", 1, value),
-                Results::Types_IdentityU32(value) =>
+                Results::Types_IdentityU32(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityU32", 2, value),
-                Results::Types_IdentityU64(value) =>
+                Results::Types_IdentityU64(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityU64", 3, value),
-                Results::Types_IdentityI8(value) =>
+                Results::Types_IdentityI8(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityI8", 4, value),
-                Results::Types_IdentityI16(value) =>
+                Results::Types_IdentityI16(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityI16", 5, value),
-                Results::Types_IdentityI32(value) =>
+                Results::Types_IdentityI32(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityI32", 6, value),
-                Results::Types_IdentityI64(value) =>
+                Results::Types_IdentityI64(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityI64", 7, value),
-                Results::Types_IdentityMultiple(value) =>
+                Results::Types_IdentityMultiple(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityMultiple", 8, value),
-                Results::Types_IdentityBool(value) =>
+                Results::Types_IdentityBool(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityBool", 9, value),
-                Results::Types_IdentityString(value) =>
+                Results::Types_IdentityString(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityString", 10, value),
-                Results::Types_IdentityData(value) =>
+                Results::Types_IdentityData(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityData", 11, value),
-                Results::Types_IdentityTimestamp(value) =>
+                Results::Types_IdentityTimestamp(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityTimestamp", 12, value),
-                Results::Types_IdentityArrayString(value) =>
+                Results::Types_IdentityArrayString(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityArrayString", 13, value),
-                Results::Types_IdentityArrayOptionU32(value) =>
+                Results::Types_IdentityArrayOptionU32(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityArrayOptionU32", 14, value),
-                Results::Types_IdentityOptionArrayU8(value) =>
+                Results::Types_IdentityOptionArrayU8(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityOptionArrayU8", 15, value),
-                Results::Types_IdentityMapStringBool(value) =>
+                Results::Types_IdentityMapStringBool(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityMapStringBool", 16, value),
-                Results::Types_IdentityStruct(value) =>
+                Results::Types_IdentityStruct(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityStruct", 17, value),
-                Results::Types_IdentityEnum(value) =>
+                Results::Types_IdentityEnum(ref value) =>
                     o.write_union(wr, mapping, "Results", "Types_IdentityEnum", 18, value),
-                Results::Layered_Login_Challenge(value) =>
-                    o.write_union(wr, mapping, "Results", "Layered_Login_Challenge", 19, value),
-                Results::Layered_Login(value) =>
-                    o.write_union(wr, mapping, "Results", "Layered_Login", 20, value),
-                Results::Lifetime_Shutdown(value) =>
-                    o.write_union(wr, mapping, "Results", "Lifetime_Shutdown", 21, value),
-                _ => unreachable!(),
+                Results::Types_IdentityEnumString(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Types_IdentityEnumString", 19, value),
+                Results::Types_IdentityEnumInteger(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Types_IdentityEnumInteger", 20, value),
+                Results::Types_IdentityUnion(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Types_IdentityUnion", 21, value),
+                Results::Types_IdentityRecursive(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Types_IdentityRecursive", 22, value),
+                Results::Layered_Login_Challenge(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Layered_Login_Challenge", 23, value),
+                Results::Layered_Login(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Layered_Login", 24, value),
+                Results::Lifetime_Shutdown(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Lifetime_Shutdown", 25, value),
             }
         }
     }
 
     #[derive(Clone, Debug)]
     #[allow(non_camel_case_types, unused)]
-    pub enum NotificationParams {}
+    pub enum NotificationParams {
+        Notifications_Ping(super::schema::notifications::ping::Params),
+        Notifications_Pong(super::schema::notifications::pong::Params),
+    }
     impl ::lavish::Atom<ProtocolMapping> for NotificationParams {
         fn method(&self) -> &'static str {
-            panic!("no variants for NotificationParams")
+            match self {
+                NotificationParams::Notifications_Ping(_) => "notifications.ping",
+                NotificationParams::Notifications_Pong(_) => "notifications.pong",
+            }
         }
     }
     impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for NotificationParams {
@@ -317,11 +361,13 @@ pub mod protocol {
         {
             let len = rd.read_array_len()?;
             if len != 2 {
-                unreachable!();
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected NotificationParams to be an array of 2 elements, got {}", len)));
             }
             let typ: u32 = rd.read_int()?;
             match typ {
-                _ => unreachable!(),
+                0 => Ok(NotificationParams::Notifications_Ping(Self::subread(rd)?)),
+                1 => Ok(NotificationParams::Notifications_Pong(Self::subread(rd)?)),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized variant for NotificationParams: {}", typ))),
             }
         }
 
@@ -330,8 +376,11 @@ pub mod protocol {
             W: ::std::io::Write,
         {
             let o = &mapping.__NotificationParams;
-            match self {
-                _ => unreachable!(),
+            match *self {
+                NotificationParams::Notifications_Ping(ref value) =>
+                    o.write_union(wr, mapping, "NotificationParams", "Notifications_Ping", 0, value),
+                NotificationParams::Notifications_Pong(ref value) =>
+                    o.write_union(wr, mapping, "NotificationParams", "Notifications_Pong", 1, value),
             }
         }
     }
@@ -359,7 +408,15 @@ pub mod protocol {
         pub phantom: std::marker::PhantomData<(P, R)>,
     }
 
+    pub trait Notifiable: Sized
+    {
+        fn method() -> &'static str;
+        fn upcast_params(self) -> NotificationParams;
+        fn downcast_params(params: NotificationParams) -> Option<Self>;
+    }
+
     use ::lavish::facts::{OffsetList, TypeMapping};
+    use ::std::collections::HashMap;
     #[derive(Debug)]
     pub struct ProtocolMapping {
         // builtins
@@ -368,6 +425,7 @@ pub mod protocol {
         pub __NotificationParams: TypeMapping,
         // structs
         pub Types_Participant: TypeMapping,
+        pub Types_Node: TypeMapping,
         pub Types_IdentityU8_Params: TypeMapping,
         pub Types_IdentityU8_Results: TypeMapping,
         pub Types_IdentityU16_Params: TypeMapping,
@@ -406,25 +464,42 @@ pub mod protocol {
         pub Types_IdentityStruct_Results: TypeMapping,
         pub Types_IdentityEnum_Params: TypeMapping,
         pub Types_IdentityEnum_Results: TypeMapping,
+        pub Types_IdentityEnumString_Params: TypeMapping,
+        pub Types_IdentityEnumString_Results: TypeMapping,
+        pub Types_IdentityEnumInteger_Params: TypeMapping,
+        pub Types_IdentityEnumInteger_Results: TypeMapping,
+        pub Types_IdentityUnion_Params: TypeMapping,
+        pub Types_IdentityUnion_Results: TypeMapping,
+        pub Types_IdentityRecursive_Params: TypeMapping,
+        pub Types_IdentityRecursive_Results: TypeMapping,
         pub Layered_Login_Params: TypeMapping,
         pub Layered_Login_Results: TypeMapping,
         pub Layered_Login_Challenge_Params: TypeMapping,
         pub Layered_Login_Challenge_Results: TypeMapping,
+        pub Notifications_Ping_Params: TypeMapping,
+        pub Notifications_Ping_Results: TypeMapping,
+        pub Notifications_Pong_Params: TypeMapping,
+        pub Notifications_Pong_Results: TypeMapping,
         pub Lifetime_Shutdown_Params: TypeMapping,
         pub Lifetime_Shutdown_Results: TypeMapping,
         // enums
         pub Types_Mood: TypeMapping,
+        pub Types_LoginType: TypeMapping,
+        pub Types_Level: TypeMapping,
+        // unions
+        pub Types_Either: TypeMapping,
     }
 
     impl Default for ProtocolMapping {
         fn default() -> Self {
             Self {
                 // builtins
-                __Params: TypeMapping::Mapped(OffsetList(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21])),
-                __Results: TypeMapping::Mapped(OffsetList(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21])),
-                __NotificationParams: TypeMapping::Mapped(OffsetList(vec![])),
+                __Params: TypeMapping::Mapped(OffsetList(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25])),
+                __Results: TypeMapping::Mapped(OffsetList(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25])),
+                __NotificationParams: TypeMapping::Mapped(OffsetList(vec![0, 1])),
                 // structs
                 Types_Participant: TypeMapping::Mapped(OffsetList(vec![0, 1])),
+                Types_Node: TypeMapping::Mapped(OffsetList(vec![0, 1])),
                 Types_IdentityU8_Params: TypeMapping::Mapped(OffsetList(vec![0])),
                 Types_IdentityU8_Results: TypeMapping::Mapped(OffsetList(vec![0])),
                 Types_IdentityU16_Params: TypeMapping::Mapped(OffsetList(vec![0])),
@@ -463,18 +538,265 @@ pub mod protocol {
                 Types_IdentityStruct_Results: TypeMapping::Mapped(OffsetList(vec![0])),
                 Types_IdentityEnum_Params: TypeMapping::Mapped(OffsetList(vec![0])),
                 Types_IdentityEnum_Results: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityEnumString_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityEnumString_Results: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityEnumInteger_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityEnumInteger_Results: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityUnion_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityUnion_Results: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityRecursive_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Types_IdentityRecursive_Results: TypeMapping::Mapped(OffsetList(vec![0])),
                 Layered_Login_Params: TypeMapping::Mapped(OffsetList(vec![])),
                 Layered_Login_Results: TypeMapping::Mapped(OffsetList(vec![0])),
                 Layered_Login_Challenge_Params: TypeMapping::Mapped(OffsetList(vec![0])),
                 Layered_Login_Challenge_Results: TypeMapping::Mapped(OffsetList(vec![0])),
+                Notifications_Ping_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Notifications_Ping_Results: TypeMapping::Mapped(OffsetList(vec![])),
+                Notifications_Pong_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Notifications_Pong_Results: TypeMapping::Mapped(OffsetList(vec![])),
                 Lifetime_Shutdown_Params: TypeMapping::Mapped(OffsetList(vec![])),
                 Lifetime_Shutdown_Results: TypeMapping::Mapped(OffsetList(vec![])),
                 // enums
                 Types_Mood: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                Types_LoginType: TypeMapping::Mapped(OffsetList(vec![0, 1])),
+                Types_Level: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                // unions
+                Types_Either: TypeMapping::Mapped(OffsetList(vec![0, 1])),
             }
         }
     }
     impl ::lavish::facts::Mapping for ProtocolMapping {}
+    /// Names of the functions, fields, enum variants and union members
+    /// of a protocol, which peers exchange to build a `ProtocolMapping`.
+    #[derive(Clone, Debug)]
+    pub struct Descriptor {
+        pub requests: Vec<String>,
+        pub notifications: Vec<String>,
+        pub structs: HashMap<String, Vec<String>>,
+        pub enums: HashMap<String, Vec<String>>,
+        pub unions: HashMap<String, Vec<String>>,
+    }
+
+    impl<M> ::lavish::facts::Factual<M> for Descriptor
+    where
+        M: ::lavish::facts::Mapping,
+    {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            rd.expect_array_len(5)?;
+            Ok(Self {
+                requests: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                notifications: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                structs: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                enums: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                unions: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+            })
+        }
+
+        fn write<W>(&self, mapping: &M, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            ::lavish::rmp::encode::write_array_len(wr, 5)?;
+            self.requests.write(mapping, wr)?;
+            self.notifications.write(mapping, wr)?;
+            self.structs.write(mapping, wr)?;
+            self.enums.write(mapping, wr)?;
+            self.unions.write(mapping, wr)?;
+            Ok(())
+        }
+    }
+
+    /// Describes this protocol, to be sent to peers at handshake
+    pub fn descriptor() -> Descriptor {
+        let mut d = Descriptor {
+            requests: names(&["types.identity_u8", "types.identity_u16", "types.identity_u32", "types.identity_u64", "types.identity_i8", "types.identity_i16", "types.identity_i32", "types.identity_i64", "types.identity_multiple", "types.identity_bool", "types.identity_string", "types.identity_data", "types.identity_timestamp", "types.identity_array_string", "types.identity_array_option_u32", "types.identity_option_array_u8", "types.identity_map_string_bool", "types.identity_struct", "types.identity_enum", "types.identity_enum_string", "types.identity_enum_integer", "types.identity_union", "types.identity_recursive", "layered.login.challenge", "layered.login", "lifetime.shutdown"]),
+            notifications: names(&["notifications.ping", "notifications.pong"]),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            unions: HashMap::new(),
+        };
+        // structs
+        d.structs.insert("types.Participant".into(), names(&["name", "age"]));
+        d.structs.insert("types.Node".into(), names(&["value", "next"]));
+        d.structs.insert("types.identity_u8.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_u8.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_u16.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_u16.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_u32.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_u32.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_u64.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_u64.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_i8.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_i8.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_i16.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_i16.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_i32.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_i32.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_i64.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_i64.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_multiple.Params".into(), names(&["x", "y", "z"]));
+        d.structs.insert("types.identity_multiple.Results".into(), names(&["x", "y", "z"]));
+        d.structs.insert("types.identity_bool.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_bool.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_string.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_string.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_data.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_data.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_timestamp.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_timestamp.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_array_string.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_array_string.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_array_option_u32.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_array_option_u32.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_option_array_u8.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_option_array_u8.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_map_string_bool.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_map_string_bool.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_struct.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_struct.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_enum.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_enum.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_enum_string.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_enum_string.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_enum_integer.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_enum_integer.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_union.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_union.Results".into(), names(&["x"]));
+        d.structs.insert("types.identity_recursive.Params".into(), names(&["x"]));
+        d.structs.insert("types.identity_recursive.Results".into(), names(&["x"]));
+        d.structs.insert("layered.login.Params".into(), names(&[]));
+        d.structs.insert("layered.login.Results".into(), names(&["ok"]));
+        d.structs.insert("layered.login.challenge.Params".into(), names(&["input"]));
+        d.structs.insert("layered.login.challenge.Results".into(), names(&["hashed"]));
+        d.structs.insert("notifications.ping.Params".into(), names(&["x"]));
+        d.structs.insert("notifications.ping.Results".into(), names(&[]));
+        d.structs.insert("notifications.pong.Params".into(), names(&["x"]));
+        d.structs.insert("notifications.pong.Results".into(), names(&[]));
+        d.structs.insert("lifetime.shutdown.Params".into(), names(&[]));
+        d.structs.insert("lifetime.shutdown.Results".into(), names(&[]));
+        // enums
+        d.enums.insert("types.Mood".into(), names(&["Positive", "Neutral", "Negative"]));
+        d.enums.insert("types.LoginType".into(), names(&["Anonymous", "Password"]));
+        d.enums.insert("types.Level".into(), names(&["Guest", "User", "Admin"]));
+        // unions
+        d.unions.insert("types.Either".into(), names(&["Mood", "Participant"]));
+        d
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&name| name.into()).collect()
+    }
+
+    impl ProtocolMapping {
+        /// Builds the mapping used to send values to a peer whose schema is
+        /// described by `remote`, matching functions, fields, enum variants
+        /// and union members by name, so they can be added or reordered.
+        pub fn negotiate(local: &Descriptor, remote: &Descriptor) -> Self {
+            Self {
+                // builtins
+                __Params: TypeMapping::Mapped(offsets(&local.requests, &remote.requests)),
+                __Results: TypeMapping::Mapped(offsets(&local.requests, &remote.requests)),
+                __NotificationParams: TypeMapping::Mapped(offsets(&local.notifications, &remote.notifications)),
+                // structs
+                Types_Participant: struct_mapping(&local.structs, &remote.structs, "types.Participant"),
+                Types_Node: struct_mapping(&local.structs, &remote.structs, "types.Node"),
+                Types_IdentityU8_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_u8.Params"),
+                Types_IdentityU8_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_u8.Results"),
+                Types_IdentityU16_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_u16.Params"),
+                Types_IdentityU16_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_u16.Results"),
+                Types_IdentityU32_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_u32.Params"),
+                Types_IdentityU32_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_u32.Results"),
+                Types_IdentityU64_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_u64.Params"),
+                Types_IdentityU64_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_u64.Results"),
+                Types_IdentityI8_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_i8.Params"),
+                Types_IdentityI8_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_i8.Results"),
+                Types_IdentityI16_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_i16.Params"),
+                Types_IdentityI16_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_i16.Results"),
+                Types_IdentityI32_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_i32.Params"),
+                Types_IdentityI32_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_i32.Results"),
+                Types_IdentityI64_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_i64.Params"),
+                Types_IdentityI64_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_i64.Results"),
+                Types_IdentityMultiple_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_multiple.Params"),
+                Types_IdentityMultiple_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_multiple.Results"),
+                Types_IdentityBool_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_bool.Params"),
+                Types_IdentityBool_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_bool.Results"),
+                Types_IdentityString_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_string.Params"),
+                Types_IdentityString_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_string.Results"),
+                Types_IdentityData_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_data.Params"),
+                Types_IdentityData_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_data.Results"),
+                Types_IdentityTimestamp_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_timestamp.Params"),
+                Types_IdentityTimestamp_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_timestamp.Results"),
+                Types_IdentityArrayString_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_array_string.Params"),
+                Types_IdentityArrayString_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_array_string.Results"),
+                Types_IdentityArrayOptionU32_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_array_option_u32.Params"),
+                Types_IdentityArrayOptionU32_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_array_option_u32.Results"),
+                Types_IdentityOptionArrayU8_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_option_array_u8.Params"),
+                Types_IdentityOptionArrayU8_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_option_array_u8.Results"),
+                Types_IdentityMapStringBool_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_map_string_bool.Params"),
+                Types_IdentityMapStringBool_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_map_string_bool.Results"),
+                Types_IdentityStruct_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_struct.Params"),
+                Types_IdentityStruct_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_struct.Results"),
+                Types_IdentityEnum_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_enum.Params"),
+                Types_IdentityEnum_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_enum.Results"),
+                Types_IdentityEnumString_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_enum_string.Params"),
+                Types_IdentityEnumString_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_enum_string.Results"),
+                Types_IdentityEnumInteger_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_enum_integer.Params"),
+                Types_IdentityEnumInteger_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_enum_integer.Results"),
+                Types_IdentityUnion_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_union.Params"),
+                Types_IdentityUnion_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_union.Results"),
+                Types_IdentityRecursive_Params: struct_mapping(&local.structs, &remote.structs, "types.identity_recursive.Params"),
+                Types_IdentityRecursive_Results: struct_mapping(&local.structs, &remote.structs, "types.identity_recursive.Results"),
+                Layered_Login_Params: struct_mapping(&local.structs, &remote.structs, "layered.login.Params"),
+                Layered_Login_Results: struct_mapping(&local.structs, &remote.structs, "layered.login.Results"),
+                Layered_Login_Challenge_Params: struct_mapping(&local.structs, &remote.structs, "layered.login.challenge.Params"),
+                Layered_Login_Challenge_Results: struct_mapping(&local.structs, &remote.structs, "layered.login.challenge.Results"),
+                Notifications_Ping_Params: struct_mapping(&local.structs, &remote.structs, "notifications.ping.Params"),
+                Notifications_Ping_Results: struct_mapping(&local.structs, &remote.structs, "notifications.ping.Results"),
+                Notifications_Pong_Params: struct_mapping(&local.structs, &remote.structs, "notifications.pong.Params"),
+                Notifications_Pong_Results: struct_mapping(&local.structs, &remote.structs, "notifications.pong.Results"),
+                Lifetime_Shutdown_Params: struct_mapping(&local.structs, &remote.structs, "lifetime.shutdown.Params"),
+                Lifetime_Shutdown_Results: struct_mapping(&local.structs, &remote.structs, "lifetime.shutdown.Results"),
+                // enums
+                Types_Mood: index_mapping(&local.enums, &remote.enums, "enum", "types.Mood"),
+                Types_LoginType: index_mapping(&local.enums, &remote.enums, "enum", "types.LoginType"),
+                Types_Level: index_mapping(&local.enums, &remote.enums, "enum", "types.Level"),
+                // unions
+                Types_Either: index_mapping(&local.unions, &remote.unions, "union", "types.Either"),
+            }
+        }
+    }
+
+    /// Returns the position of each of the `from` names among the `to` ones,
+    /// or -1 for those missing.
+    fn offsets(from: &[String], to: &[String]) -> OffsetList {
+        OffsetList(
+            from.iter()
+                .map(|name| to.iter().position(|n| n == name).map(|i| i as i32).unwrap_or(-1))
+                .collect(),
+        )
+    }
+
+    /// Fields are sent in the order the peer reads them: for each of the
+    /// peer's fields, the mapping gives which of ours to write, if any.
+    fn struct_mapping(local: &HashMap<String, Vec<String>>, remote: &HashMap<String, Vec<String>>, name: &str) -> TypeMapping {
+        match (local.get(name), remote.get(name)) {
+            (Some(local), Some(remote)) => TypeMapping::Mapped(offsets(remote, local)),
+            _ => TypeMapping::Incompatible(format!("struct {} is not known by the peer", name)),
+        }
+    }
+
+    /// Enum variants and union members are sent by index: for each of ours,
+    /// the mapping gives the index the peer knows it by, if any.
+    fn index_mapping(local: &HashMap<String, Vec<String>>, remote: &HashMap<String, Vec<String>>, kind: &str, name: &str) -> TypeMapping {
+        match (local.get(name), remote.get(name)) {
+            (Some(local), Some(remote)) => TypeMapping::Mapped(offsets(local, remote)),
+            _ => TypeMapping::Incompatible(format!("{} {} is not known by the peer", kind, name)),
+        }
+    }
 }
 
 pub mod schema {
@@ -491,10 +813,13 @@ pub mod schema {
                 Self: Sized,
                 R: ::std::io::Read,
             {
-                rd.expect_array_len(2)?;
+                let len = rd.read_array_len()?;
+                if len != 2 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Participant to be an array of 2 elements, got {}", len)));
+                }
                 Ok(Self {
-                    name: Self::subread(rd)?,
-                    age: Self::subread(rd)?,
+                    name: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    age: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
                 })
             }
 
@@ -505,11 +830,60 @@ pub mod schema {
                 mapping.Types_Participant.write(wr, |wr, i| match i {
                     0 => self.name.write(mapping, wr),
                     1 => self.age.write(mapping, wr),
-                    _ => unreachable!(),
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Participant: {}", i))),
+                })
+            }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Node {
+            pub value: i64,
+            pub next: Option<Box<Node>>,
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Node {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 2 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Node to be an array of 2 elements, got {}", len)));
+                }
+                Ok(Self {
+                    value: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    next: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Types_Node.write(wr, |wr, i| match i {
+                    0 => self.value.write(mapping, wr),
+                    1 => self.next.write(mapping, wr),
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Node: {}", i))),
                 })
             }
         }
-        #[derive(Clone, Copy, Debug)]
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Box<Node> {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                Ok(Box::new(<Node as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::read(rd)?))
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                (**self).write(mapping, wr)
+            }
+        }
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
         #[repr(u32)]
         pub enum Mood {
             Positive = 0,
@@ -546,6 +920,111 @@ pub mod schema {
                 }
             }
         }
+        /// Sent as strings, whatever the order of variants
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub enum LoginType {
+            Anonymous,
+            Password,
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for LoginType {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let value: String = <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?;
+                use LoginType as E;
+                Ok(match value.as_str() {
+                    "anonymous" => E::Anonymous,
+                    "password" => E::Password,
+                    _ => return Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized enum variant for LoginType: {:#?}", value))),
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                Self: Sized,
+                W: ::std::io::Write,
+            {
+                mapping.Types_LoginType.validate()?;
+                use LoginType as E;
+                let value: &str = match self {
+                    E::Anonymous => "anonymous",
+                    E::Password => "password",
+                };
+                value.write(mapping, wr)
+            }
+        }
+        /// Sent as integers, whatever the order of variants
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        #[repr(i64)]
+        pub enum Level {
+            Guest = -1,
+            User = 0,
+            Admin = 100,
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Level {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let value: i64 = rd.read_int()?;
+                use Level as E;
+                Ok(match value {
+                    -1 => E::Guest,
+                    0 => E::User,
+                    100 => E::Admin,
+                    _ => return Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized enum variant for Level: {:#?}", value))),
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                Self: Sized,
+                W: ::std::io::Write,
+            {
+                mapping.Types_Level.validate()?;
+                (*self as i64).write(mapping, wr)
+            }
+        }
+        #[derive(Clone, Debug)]
+        pub enum Either {
+            Mood(Mood),
+            Participant(Participant),
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Either {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 2 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Either to be an array of 2 elements, got {}", len)));
+                }
+                let typ: u32 = rd.read_int()?;
+                match typ {
+                    0 => Ok(Either::Mood(<Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?)),
+                    1 => Ok(Either::Participant(<Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?)),
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized union variant for Either: {}", typ))),
+                }
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                let o = &mapping.Types_Either;
+                match *self {
+                    Either::Mood(ref value) => o.write_union(wr, mapping, "Either", "Mood", 0, value),
+                    Either::Participant(ref value) => o.write_union(wr, mapping, "Either", "Participant", 1, value),
+                }
+            }
+        }
         pub use identity_u8::method as identity_u8;
         pub mod identity_u8 {
             pub fn method() -> super::super::super::protocol::Slottable<Params, Results> {
@@ -562,9 +1041,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -574,7 +1056,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU8_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -589,9 +1071,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -601,7 +1086,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU8_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -649,9 +1134,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -661,7 +1149,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU16_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -676,9 +1164,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -688,7 +1179,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU16_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -736,9 +1227,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -748,7 +1242,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU32_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -763,9 +1257,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -775,7 +1272,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU32_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -823,9 +1320,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -835,7 +1335,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU64_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -850,9 +1350,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -862,7 +1365,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityU64_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -910,9 +1413,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -922,7 +1428,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI8_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -937,9 +1443,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -949,7 +1458,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI8_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -997,9 +1506,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1009,7 +1521,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI16_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1024,9 +1536,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1036,7 +1551,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI16_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1084,9 +1599,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1096,7 +1614,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI32_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1111,9 +1629,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1123,7 +1644,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI32_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1171,9 +1692,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1183,7 +1707,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI64_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1198,9 +1722,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1210,7 +1737,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityI64_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1260,11 +1787,14 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(3)?;
+                    let len = rd.read_array_len()?;
+                    if len != 3 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 3 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
-                        y: Self::subread(rd)?,
-                        z: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                        y: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                        z: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1276,7 +1806,7 @@ pub mod schema {
                         0 => self.x.write(mapping, wr),
                         1 => self.y.write(mapping, wr),
                         2 => self.z.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1293,11 +1823,14 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(3)?;
+                    let len = rd.read_array_len()?;
+                    if len != 3 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 3 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
-                        y: Self::subread(rd)?,
-                        z: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                        y: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                        z: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1309,7 +1842,7 @@ pub mod schema {
                         0 => self.x.write(mapping, wr),
                         1 => self.y.write(mapping, wr),
                         2 => self.z.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1357,9 +1890,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1369,7 +1905,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityBool_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1384,9 +1920,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1396,7 +1935,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityBool_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1444,9 +1983,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1456,7 +1998,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityString_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1471,9 +2013,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1483,7 +2028,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityString_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1531,9 +2076,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1543,7 +2091,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityData_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1558,9 +2106,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1570,7 +2121,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityData_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1618,9 +2169,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1630,7 +2184,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityTimestamp_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1645,9 +2199,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1657,7 +2214,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityTimestamp_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1705,9 +2262,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1717,7 +2277,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityArrayString_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1732,9 +2292,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1744,7 +2307,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityArrayString_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1792,9 +2355,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1804,7 +2370,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityArrayOptionU32_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1819,9 +2385,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1831,7 +2400,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityArrayOptionU32_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1879,9 +2448,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1891,7 +2463,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityOptionArrayU8_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1906,9 +2478,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1918,7 +2493,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityOptionArrayU8_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -1966,9 +2541,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -1978,7 +2556,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityMapStringBool_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -1993,9 +2571,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2005,7 +2586,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityMapStringBool_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -2053,9 +2634,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2065,7 +2649,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityStruct_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -2080,9 +2664,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2092,7 +2679,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityStruct_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -2140,9 +2727,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2152,7 +2742,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityEnum_Params.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -2167,9 +2757,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        x: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2179,7 +2772,7 @@ pub mod schema {
                 {
                     mapping.Types_IdentityEnum_Results.write(wr, |wr, i| match i {
                         0 => self.x.write(mapping, wr),
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -2211,15 +2804,14 @@ pub mod schema {
                 }
             }
         }
-    }
-    pub mod layered {
-        pub use login::method as login;
-        pub mod login {
+        pub use identity_enum_string::method as identity_enum_string;
+        pub mod identity_enum_string {
             pub fn method() -> super::super::super::protocol::Slottable<Params, Results> {
                 super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
             }
             #[derive(Clone, Debug)]
             pub struct Params {
+                pub x: super::LoginType,
             }
 
             impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
@@ -2228,8 +2820,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(0)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2237,14 +2833,15 @@ pub mod schema {
                 where
                     W: ::std::io::Write,
                 {
-                    mapping.Layered_Login_Params.write(wr, |wr, i| match i {
-                        _ => unreachable!(),
+                    mapping.Types_IdentityEnumString_Params.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
             #[derive(Clone, Debug)]
             pub struct Results {
-                pub ok: bool,
+                pub x: super::LoginType,
             }
 
             impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Results {
@@ -2253,9 +2850,12 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(1)?;
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
                     Ok(Self {
-                        ok: Self::subread(rd)?,
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                     })
                 }
 
@@ -2263,20 +2863,20 @@ pub mod schema {
                 where
                     W: ::std::io::Write,
                 {
-                    mapping.Layered_Login_Results.write(wr, |wr, i| match i {
-                        0 => self.ok.write(mapping, wr),
-                        _ => unreachable!(),
+                    mapping.Types_IdentityEnumString_Results.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
 
             impl super::super::super::protocol::Callable<Results> for Params {
                 fn upcast_params(self) -> super::super::super::protocol::Params {
-                    super::super::super::protocol::Params::Layered_Login(self)
+                    super::super::super::protocol::Params::Types_IdentityEnumString(self)
                 }
                 fn downcast_results(results: super::super::super::protocol::Results) -> Option<Results> {
                     match results {
-                        super::super::super::protocol::Results::Layered_Login(r) => Some(r),
+                        super::super::super::protocol::Results::Types_IdentityEnumString(r) => Some(r),
                         _ => None,
                     }
                 }
@@ -2284,54 +2884,428 @@ pub mod schema {
 
             impl super::super::super::protocol::Implementable<Params> for Results {
                 fn method() -> &'static str {
-                    "layered.login"
+                    "types.identity_enum_string"
                 }
                 fn upcast_results(self) -> super::super::super::protocol::Results {
-                    super::super::super::protocol::Results::Layered_Login(self)
+                    super::super::super::protocol::Results::Types_IdentityEnumString(self)
                 }
                 fn downcast_params(params: super::super::super::protocol::Params) -> Option<Params> {
                     match params {
-                        super::super::super::protocol::Params::Layered_Login(p) => Some(p),
+                        super::super::super::protocol::Params::Types_IdentityEnumString(p) => Some(p),
                         _ => None,
                     }
                 }
             }
+        }
+        pub use identity_enum_integer::method as identity_enum_integer;
+        pub mod identity_enum_integer {
+            pub fn method() -> super::super::super::protocol::Slottable<Params, Results> {
+                super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Params {
+                pub x: super::Level,
+            }
 
-            pub use challenge::method as challenge;
-            pub mod challenge {
-                pub fn method() -> super::super::super::super::protocol::Slottable<Params, Results> {
-                    super::super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
-                }
-                #[derive(Clone, Debug)]
-                pub struct Params {
-                    pub input: String,
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
                 }
 
-                impl ::lavish::facts::Factual<super::super::super::super::protocol::ProtocolMapping> for Params {
-                    fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
-                    where
-                        Self: Sized,
-                        R: ::std::io::Read,
-                    {
-                        rd.expect_array_len(1)?;
-                        Ok(Self {
-                            input: Self::subread(rd)?,
-                        })
-                    }
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Types_IdentityEnumInteger_Params.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                    })
+                }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Results {
+                pub x: super::Level,
+            }
 
-                    fn write<W>(&self, mapping: &super::super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
-                    where
-                        W: ::std::io::Write,
-                    {
-                        mapping.Layered_Login_Challenge_Params.write(wr, |wr, i| match i {
-                            0 => self.input.write(mapping, wr),
-                            _ => unreachable!(),
-                        })
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Results {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
                     }
-                }
-                #[derive(Clone, Debug)]
-                pub struct Results {
-                    pub hashed: String,
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Types_IdentityEnumInteger_Results.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                    })
+                }
+            }
+
+            impl super::super::super::protocol::Callable<Results> for Params {
+                fn upcast_params(self) -> super::super::super::protocol::Params {
+                    super::super::super::protocol::Params::Types_IdentityEnumInteger(self)
+                }
+                fn downcast_results(results: super::super::super::protocol::Results) -> Option<Results> {
+                    match results {
+                        super::super::super::protocol::Results::Types_IdentityEnumInteger(r) => Some(r),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl super::super::super::protocol::Implementable<Params> for Results {
+                fn method() -> &'static str {
+                    "types.identity_enum_integer"
+                }
+                fn upcast_results(self) -> super::super::super::protocol::Results {
+                    super::super::super::protocol::Results::Types_IdentityEnumInteger(self)
+                }
+                fn downcast_params(params: super::super::super::protocol::Params) -> Option<Params> {
+                    match params {
+                        super::super::super::protocol::Params::Types_IdentityEnumInteger(p) => Some(p),
+                        _ => None,
+                    }
+                }
+            }
+        }
+        pub use identity_union::method as identity_union;
+        pub mod identity_union {
+            pub fn method() -> super::super::super::protocol::Slottable<Params, Results> {
+                super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Params {
+                pub x: super::Either,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Types_IdentityUnion_Params.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                    })
+                }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Results {
+                pub x: super::Either,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Results {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Types_IdentityUnion_Results.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                    })
+                }
+            }
+
+            impl super::super::super::protocol::Callable<Results> for Params {
+                fn upcast_params(self) -> super::super::super::protocol::Params {
+                    super::super::super::protocol::Params::Types_IdentityUnion(self)
+                }
+                fn downcast_results(results: super::super::super::protocol::Results) -> Option<Results> {
+                    match results {
+                        super::super::super::protocol::Results::Types_IdentityUnion(r) => Some(r),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl super::super::super::protocol::Implementable<Params> for Results {
+                fn method() -> &'static str {
+                    "types.identity_union"
+                }
+                fn upcast_results(self) -> super::super::super::protocol::Results {
+                    super::super::super::protocol::Results::Types_IdentityUnion(self)
+                }
+                fn downcast_params(params: super::super::super::protocol::Params) -> Option<Params> {
+                    match params {
+                        super::super::super::protocol::Params::Types_IdentityUnion(p) => Some(p),
+                        _ => None,
+                    }
+                }
+            }
+        }
+        pub use identity_recursive::method as identity_recursive;
+        pub mod identity_recursive {
+            pub fn method() -> super::super::super::protocol::Slottable<Params, Results> {
+                super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Params {
+                pub x: super::Node,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Types_IdentityRecursive_Params.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                    })
+                }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Results {
+                pub x: super::Node,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Results {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Types_IdentityRecursive_Results.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                    })
+                }
+            }
+
+            impl super::super::super::protocol::Callable<Results> for Params {
+                fn upcast_params(self) -> super::super::super::protocol::Params {
+                    super::super::super::protocol::Params::Types_IdentityRecursive(self)
+                }
+                fn downcast_results(results: super::super::super::protocol::Results) -> Option<Results> {
+                    match results {
+                        super::super::super::protocol::Results::Types_IdentityRecursive(r) => Some(r),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl super::super::super::protocol::Implementable<Params> for Results {
+                fn method() -> &'static str {
+                    "types.identity_recursive"
+                }
+                fn upcast_results(self) -> super::super::super::protocol::Results {
+                    super::super::super::protocol::Results::Types_IdentityRecursive(self)
+                }
+                fn downcast_params(params: super::super::super::protocol::Params) -> Option<Params> {
+                    match params {
+                        super::super::super::protocol::Params::Types_IdentityRecursive(p) => Some(p),
+                        _ => None,
+                    }
+                }
+            }
+        }
+    }
+    pub mod layered {
+        pub use login::method as login;
+        pub mod login {
+            pub fn method() -> super::super::super::protocol::Slottable<Params, Results> {
+                super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Params {
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 0 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 0 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Layered_Login_Params.write(wr, |wr, i| match i {
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                    })
+                }
+            }
+            #[derive(Clone, Debug)]
+            pub struct Results {
+                pub ok: bool,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Results {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        ok: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Layered_Login_Results.write(wr, |wr, i| match i {
+                        0 => self.ok.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                    })
+                }
+            }
+
+            impl super::super::super::protocol::Callable<Results> for Params {
+                fn upcast_params(self) -> super::super::super::protocol::Params {
+                    super::super::super::protocol::Params::Layered_Login(self)
+                }
+                fn downcast_results(results: super::super::super::protocol::Results) -> Option<Results> {
+                    match results {
+                        super::super::super::protocol::Results::Layered_Login(r) => Some(r),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl super::super::super::protocol::Implementable<Params> for Results {
+                fn method() -> &'static str {
+                    "layered.login"
+                }
+                fn upcast_results(self) -> super::super::super::protocol::Results {
+                    super::super::super::protocol::Results::Layered_Login(self)
+                }
+                fn downcast_params(params: super::super::super::protocol::Params) -> Option<Params> {
+                    match params {
+                        super::super::super::protocol::Params::Layered_Login(p) => Some(p),
+                        _ => None,
+                    }
+                }
+            }
+
+            pub use challenge::method as challenge;
+            pub mod challenge {
+                pub fn method() -> super::super::super::super::protocol::Slottable<Params, Results> {
+                    super::super::super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+                }
+                #[derive(Clone, Debug)]
+                pub struct Params {
+                    pub input: String,
+                }
+
+                impl ::lavish::facts::Factual<super::super::super::super::protocol::ProtocolMapping> for Params {
+                    fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                    where
+                        Self: Sized,
+                        R: ::std::io::Read,
+                    {
+                        let len = rd.read_array_len()?;
+                        if len != 1 {
+                            return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                        }
+                        Ok(Self {
+                            input: <Self as ::lavish::facts::Factual<super::super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                        })
+                    }
+
+                    fn write<W>(&self, mapping: &super::super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                    where
+                        W: ::std::io::Write,
+                    {
+                        mapping.Layered_Login_Challenge_Params.write(wr, |wr, i| match i {
+                            0 => self.input.write(mapping, wr),
+                            _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                        })
+                    }
+                }
+                #[derive(Clone, Debug)]
+                pub struct Results {
+                    pub hashed: String,
                 }
 
                 impl ::lavish::facts::Factual<super::super::super::super::protocol::ProtocolMapping> for Results {
@@ -2340,9 +3314,12 @@ pub mod schema {
                         Self: Sized,
                         R: ::std::io::Read,
                     {
-                        rd.expect_array_len(1)?;
+                        let len = rd.read_array_len()?;
+                        if len != 1 {
+                            return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 1 elements, got {}", len)));
+                        }
                         Ok(Self {
-                            hashed: Self::subread(rd)?,
+                            hashed: <Self as ::lavish::facts::Factual<super::super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
                         })
                     }
 
@@ -2352,7 +3329,7 @@ pub mod schema {
                     {
                         mapping.Layered_Login_Challenge_Results.write(wr, |wr, i| match i {
                             0 => self.hashed.write(mapping, wr),
-                            _ => unreachable!(),
+                            _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                         })
                     }
                 }
@@ -2386,6 +3363,102 @@ pub mod schema {
             }
         }
     }
+    pub mod notifications {
+        pub mod ping {
+            #[derive(Clone, Debug)]
+            pub struct Params {
+                pub x: String,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Notifications_Ping_Params.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                    })
+                }
+            }
+
+            impl super::super::super::protocol::Notifiable for Params {
+                fn method() -> &'static str {
+                    "notifications.ping"
+                }
+                fn upcast_params(self) -> super::super::super::protocol::NotificationParams {
+                    super::super::super::protocol::NotificationParams::Notifications_Ping(self)
+                }
+                fn downcast_params(params: super::super::super::protocol::NotificationParams) -> Option<Self> {
+                    match params {
+                        super::super::super::protocol::NotificationParams::Notifications_Ping(p) => Some(p),
+                        _ => None,
+                    }
+                }
+            }
+        }
+        pub mod pong {
+            #[derive(Clone, Debug)]
+            pub struct Params {
+                pub x: String,
+            }
+
+            impl ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping> for Params {
+                fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+                where
+                    Self: Sized,
+                    R: ::std::io::Read,
+                {
+                    let len = rd.read_array_len()?;
+                    if len != 1 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                    }
+                    Ok(Self {
+                        x: <Self as ::lavish::facts::Factual<super::super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    })
+                }
+
+                fn write<W>(&self, mapping: &super::super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+                where
+                    W: ::std::io::Write,
+                {
+                    mapping.Notifications_Pong_Params.write(wr, |wr, i| match i {
+                        0 => self.x.write(mapping, wr),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                    })
+                }
+            }
+
+            impl super::super::super::protocol::Notifiable for Params {
+                fn method() -> &'static str {
+                    "notifications.pong"
+                }
+                fn upcast_params(self) -> super::super::super::protocol::NotificationParams {
+                    super::super::super::protocol::NotificationParams::Notifications_Pong(self)
+                }
+                fn downcast_params(params: super::super::super::protocol::NotificationParams) -> Option<Self> {
+                    match params {
+                        super::super::super::protocol::NotificationParams::Notifications_Pong(p) => Some(p),
+                        _ => None,
+                    }
+                }
+            }
+        }
+    }
     pub mod lifetime {
         pub use shutdown::method as shutdown;
         pub mod shutdown {
@@ -2402,7 +3475,10 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(0)?;
+                    let len = rd.read_array_len()?;
+                    if len != 0 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 0 elements, got {}", len)));
+                    }
                     Ok(Self {
                     })
                 }
@@ -2412,7 +3488,7 @@ pub mod schema {
                     W: ::std::io::Write,
                 {
                     mapping.Lifetime_Shutdown_Params.write(wr, |wr, i| match i {
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
                     })
                 }
             }
@@ -2426,7 +3502,10 @@ pub mod schema {
                     Self: Sized,
                     R: ::std::io::Read,
                 {
-                    rd.expect_array_len(0)?;
+                    let len = rd.read_array_len()?;
+                    if len != 0 {
+                        return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 0 elements, got {}", len)));
+                    }
                     Ok(Self {
                     })
                 }
@@ -2436,7 +3515,7 @@ pub mod schema {
                     W: ::std::io::Write,
                 {
                     mapping.Lifetime_Shutdown_Results.write(wr, |wr, i| match i {
-                        _ => unreachable!(),
+                        _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
                     })
                 }
             }
@@ -42,6 +42,10 @@ pub fn run() {
 
         identity_clone!(identity_struct);
         identity_clone!(identity_enum);
+        identity_clone!(identity_enum_string);
+        identity_clone!(identity_enum_integer);
+        identity_clone!(identity_union);
+        identity_clone!(identity_recursive);
     }
     let server = ::lavish::serve_once(r, "localhost:0").unwrap();
     println!("{}", server.local_addr());
@@ -41,6 +41,7 @@ fn main() {
 
     context.run_codegen_tests();
     context.run_compliance_tests();
+    context.run_negotiation_tests();
 
     status("All done!")
 }
@@ -216,6 +217,90 @@ impl Context {
                 .env("CARGO_TARGET_DIR", target_dir)
                 .run_verbose();
         }
+
+        // Go
+        {
+            task("Go codegen...");
+
+            // generated code is checked in, so it can be reviewed
+            let fixtures_dir = self
+                .tests_dir
+                .join("..")
+                .join("src")
+                .join("codegen")
+                .join("go")
+                .join("tests");
+
+            Command::new(&self.compiler_path)
+                .args(&["build", "--check", &fixtures_dir.to_string_lossy()])
+                .run_verbose();
+
+            if Command::new("go").arg("version").output().is_ok() {
+                let output = Command::new("gofmt")
+                    .args(&["-l", &fixtures_dir.to_string_lossy()])
+                    .output()
+                    .unwrap();
+                let unformatted = String::from_utf8_lossy(&output.stdout);
+                if !output.status.success() || !unformatted.trim().is_empty() {
+                    println!(
+                        "{}",
+                        format!("Generated Go code isn't gofmt-formatted:\n{}", unformatted).red()
+                    );
+                    process::exit(1);
+                }
+
+                Command::new("go")
+                    .args(&["vet", "./..."])
+                    .current_dir(&fixtures_dir)
+                    .run_verbose();
+            } else {
+                status("go not found, skipping gofmt and go vet");
+            }
+        }
+
+        // TypeScript
+        {
+            task("TypeScript codegen...");
+
+            let fixtures_dir = self
+                .tests_dir
+                .join("..")
+                .join("src")
+                .join("codegen")
+                .join("typescript")
+                .join("tests");
+
+            Command::new(&self.compiler_path)
+                .args(&["build", "--check", &fixtures_dir.to_string_lossy()])
+                .run_verbose();
+
+            // borrow the compliance tests' TypeScript compiler
+            let ts_dir = self.tests_dir.join("compliance").join("ts_compliant");
+            Command::new("npm")
+                .args(&["ci"])
+                .current_dir(&ts_dir)
+                .run_verbose();
+
+            let tsc_path = ts_dir.join("node_modules").join(".bin").join("tsc");
+            Command::new(&tsc_path)
+                .args(&["--project", &fixtures_dir.to_string_lossy()])
+                .run_verbose();
+        }
+    }
+
+    fn run_negotiation_tests(&self) {
+        let negotiation_dir = self.tests_dir.join("negotiation");
+        task("Negotiation tests");
+
+        let src_dir = negotiation_dir.join("src");
+        Command::new(&self.compiler_path)
+            .args(&["build", &src_dir.to_string_lossy()])
+            .run_verbose();
+
+        let cargo_path = negotiation_dir.join("Cargo.toml");
+        Command::new("cargo")
+            .args(&["test", "--manifest-path", &cargo_path.to_string_lossy()])
+            .run_verbose();
     }
 
     fn run_compliance_tests(&self) {
@@ -0,0 +1,175 @@
+//! Sends values from a peer built against `schema/v2.lavish` to one built
+//! against `schema/v1.lavish`, using the mapping the newer peer negotiates
+//! from the older one's descriptor, and checks that the generated decoders
+//! return errors for frames they can't make sense of.
+
+mod v1;
+mod v2;
+
+#[cfg(test)]
+mod tests {
+    use super::{v1, v2};
+    use lavish::facts::{Error, Factual, Reader};
+
+    /// v1's descriptor, as v2 receives it over the wire.
+    fn remote_descriptor() -> v2::protocol::Descriptor {
+        let mut buf = Vec::new();
+        v1::protocol::descriptor().write(&(), &mut buf).unwrap();
+        Factual::<()>::read(&mut Reader::new(&buf[..])).unwrap()
+    }
+
+    fn mapping() -> v2::protocol::ProtocolMapping {
+        v2::protocol::ProtocolMapping::negotiate(&v2::protocol::descriptor(), &remote_descriptor())
+    }
+
+    /// Encodes `value` with v2 types and decodes it with v1 types.
+    fn send<T, U>(value: &T) -> Result<U, Error>
+    where
+        T: Factual<v2::protocol::ProtocolMapping>,
+        U: Factual<v1::protocol::ProtocolMapping>,
+    {
+        let mut buf = Vec::new();
+        value.write(&mapping(), &mut buf)?;
+        U::read(&mut Reader::new(&buf[..]))
+    }
+
+    fn point() -> v2::Point {
+        v2::Point {
+            y: 2,
+            x: 1,
+            depth: Some(3),
+        }
+    }
+
+    #[test]
+    fn descriptor_round_trips() {
+        let remote = remote_descriptor();
+        assert_eq!(remote.requests, vec!["reset", "draw", "tag"]);
+        assert_eq!(remote.structs["Point"], vec!["x", "y", "label"]);
+        assert_eq!(remote.enums["Color"], vec!["Red", "Green", "Blue"]);
+        assert_eq!(remote.unions["Shape"], vec!["Point", "Color", "Text"]);
+    }
+
+    #[test]
+    fn fields() {
+        // reordered fields keep their values, the removed optional
+        // field reads as None and the added one is dropped
+        let p: v1::Point = send(&point()).unwrap();
+        assert_eq!((p.x, p.y, p.label), (1, 2, None));
+
+        // the peer still requires a field we removed
+        let res: Result<v1::Tag, _> = send(&v2::Tag {});
+        assert!(res.is_err());
+
+        // the peer doesn't know this struct at all
+        let line = v2::Line {
+            from: point(),
+            to: point(),
+        };
+        let mut buf = Vec::new();
+        assert!(line.write(&mapping(), &mut buf).is_err());
+    }
+
+    #[test]
+    fn enum_variants() {
+        let c: v1::Color = send(&v2::Color::Blue).unwrap();
+        assert_eq!(c, v1::Color::Blue);
+        let c: v1::Color = send(&v2::Color::Red).unwrap();
+        assert_eq!(c, v1::Color::Red);
+
+        let res: Result<v1::Color, _> = send(&v2::Color::Black);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn union_members() {
+        match send(&v2::Shape::Point(point())).unwrap() {
+            v1::Shape::Point(p) => assert_eq!((p.x, p.y), (1, 2)),
+            s => panic!("expected a point, got {:?}", s),
+        }
+        match send(&v2::Shape::Color(v2::Color::Blue)).unwrap() {
+            v1::Shape::Color(c) => assert_eq!(c, v1::Color::Blue),
+            s => panic!("expected a color, got {:?}", s),
+        }
+
+        let line = v2::Shape::Line(v2::Line {
+            from: point(),
+            to: point(),
+        });
+        let res: Result<v1::Shape, _> = send(&line);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn functions() {
+        let params = v2::protocol::Params::Draw(v2::draw::Params {
+            point: point(),
+            color: v2::Color::Red,
+            shape: v2::Shape::Color(v2::Color::Blue),
+        });
+        match send(&params).unwrap() {
+            v1::protocol::Params::Draw(p) => {
+                assert_eq!((p.point.x, p.point.y), (1, 2));
+                assert_eq!(p.color, v1::Color::Red);
+            }
+            p => panic!("expected draw, got {:?}", p),
+        }
+
+        let undo = v2::protocol::Params::Undo(v2::undo::Params {});
+        let res: Result<v1::protocol::Params, _> = send(&undo);
+        assert!(res.is_err());
+    }
+
+    /// Decodes `frame` with v1 types.
+    fn decode<U>(frame: &[u8]) -> Result<U, Error>
+    where
+        U: Factual<v1::protocol::ProtocolMapping>,
+    {
+        U::read(&mut Reader::new(frame))
+    }
+
+    #[test]
+    fn malformed_frames() {
+        use lavish::rmp::encode::{write_array_len, write_sint, write_uint};
+
+        // empty
+        assert!(decode::<v1::Point>(&[]).is_err());
+
+        // too few fields
+        let mut buf = Vec::new();
+        write_array_len(&mut buf, 2).unwrap();
+        write_sint(&mut buf, 1).unwrap();
+        write_sint(&mut buf, 2).unwrap();
+        assert!(decode::<v1::Point>(&buf).is_err());
+
+        // cut short
+        let mut buf = Vec::new();
+        write_array_len(&mut buf, 3).unwrap();
+        write_sint(&mut buf, 1).unwrap();
+        assert!(decode::<v1::Point>(&buf).is_err());
+
+        // unknown enum variant
+        let mut buf = Vec::new();
+        write_uint(&mut buf, 9).unwrap();
+        assert!(decode::<v1::Color>(&buf).is_err());
+
+        // unknown union member
+        let mut buf = Vec::new();
+        write_array_len(&mut buf, 2).unwrap();
+        write_uint(&mut buf, 9).unwrap();
+        write_uint(&mut buf, 0).unwrap();
+        assert!(decode::<v1::Shape>(&buf).is_err());
+
+        // unknown function, and a function without its params
+        let mut buf = Vec::new();
+        write_array_len(&mut buf, 2).unwrap();
+        write_uint(&mut buf, 9).unwrap();
+        write_array_len(&mut buf, 0).unwrap();
+        assert!(decode::<v1::protocol::Params>(&buf).is_err());
+
+        let mut buf = Vec::new();
+        write_array_len(&mut buf, 1).unwrap();
+        write_uint(&mut buf, 0).unwrap();
+        assert!(decode::<v1::protocol::Params>(&buf).is_err());
+    }
+}
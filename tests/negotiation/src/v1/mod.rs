@@ -0,0 +1,1048 @@
+// This file is generated by lavish: DO NOT EDIT
+// https://github.com/fasterthanlime/lavish
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(clippy::all, unknown_lints, unused, non_snake_case)]
+
+pub use schema::*;
+
+pub mod protocol {
+    #[derive(Clone, Debug)]
+    #[allow(non_camel_case_types, unused)]
+    pub enum Params {
+        Reset(super::schema::reset::Params),
+        Draw(super::schema::draw::Params),
+        Tag(super::schema::tag::Params),
+    }
+    impl ::lavish::Atom<ProtocolMapping> for Params {
+        fn method(&self) -> &'static str {
+            match self {
+                Params::Reset(_) => "reset",
+                Params::Draw(_) => "draw",
+                Params::Tag(_) => "tag",
+            }
+        }
+    }
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Params {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 2 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 2 elements, got {}", len)));
+            }
+            let typ: u32 = rd.read_int()?;
+            match typ {
+                0 => Ok(Params::Reset(Self::subread(rd)?)),
+                1 => Ok(Params::Draw(Self::subread(rd)?)),
+                2 => Ok(Params::Tag(Self::subread(rd)?)),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized variant for Params: {}", typ))),
+            }
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            let o = &mapping.__Params;
+            match *self {
+                Params::Reset(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Reset", 0, value),
+                Params::Draw(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Draw", 1, value),
+                Params::Tag(ref value) =>
+                    o.write_union(wr, mapping, "Params", "Tag", 2, value),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    #[allow(non_camel_case_types, unused)]
+    pub enum Results {
+        Reset(super::schema::reset::Results),
+        Draw(super::schema::draw::Results),
+        Tag(super::schema::tag::Results),
+    }
+    impl ::lavish::Atom<ProtocolMapping> for Results {
+        fn method(&self) -> &'static str {
+            match self {
+                Results::Reset(_) => "reset",
+                Results::Draw(_) => "draw",
+                Results::Tag(_) => "tag",
+            }
+        }
+    }
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Results {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 2 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 2 elements, got {}", len)));
+            }
+            let typ: u32 = rd.read_int()?;
+            match typ {
+                0 => Ok(Results::Reset(Self::subread(rd)?)),
+                1 => Ok(Results::Draw(Self::subread(rd)?)),
+                2 => Ok(Results::Tag(Self::subread(rd)?)),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized variant for Results: {}", typ))),
+            }
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            let o = &mapping.__Results;
+            match *self {
+                Results::Reset(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Reset", 0, value),
+                Results::Draw(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Draw", 1, value),
+                Results::Tag(ref value) =>
+                    o.write_union(wr, mapping, "Results", "Tag", 2, value),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    #[allow(non_camel_case_types, unused)]
+    pub enum NotificationParams {}
+    impl ::lavish::Atom<ProtocolMapping> for NotificationParams {
+        fn method(&self) -> &'static str {
+            panic!("no variants for NotificationParams")
+        }
+    }
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for NotificationParams {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 2 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected NotificationParams to be an array of 2 elements, got {}", len)));
+            }
+            let typ: u32 = rd.read_int()?;
+            match typ {
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized variant for NotificationParams: {}", typ))),
+            }
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            let o = &mapping.__NotificationParams;
+            match *self {
+            }
+        }
+    }
+
+    pub type Caller = ::lavish::Caller<super::protocol::ProtocolMapping, super::protocol::Params, super::protocol::NotificationParams, super::protocol::Results>;
+    pub type Handler<CL> = ::lavish::Handler<CL, super::protocol::ProtocolMapping, super::protocol::Params, super::protocol::NotificationParams, super::protocol::Results>;
+    pub trait Callable<R>
+    {
+        fn upcast_params(self) -> Params;
+        fn downcast_results(results: Results) -> Option<R>;
+    }
+
+    pub trait Implementable<P>
+    {
+        fn method() -> &'static str;
+        fn downcast_params(params: Params) -> Option<P>;
+        fn upcast_results(self) -> Results;
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct Slottable<P, R>
+    where
+        R: Implementable<P>,
+    {
+        pub phantom: std::marker::PhantomData<(P, R)>,
+    }
+
+    pub trait Notifiable: Sized
+    {
+        fn method() -> &'static str;
+        fn upcast_params(self) -> NotificationParams;
+        fn downcast_params(params: NotificationParams) -> Option<Self>;
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct NotificationSlottable<P>
+    where
+        P: Notifiable,
+    {
+        pub phantom: std::marker::PhantomData<P>,
+    }
+
+    use ::lavish::facts::{OffsetList, TypeMapping};
+    use ::std::collections::HashMap;
+    #[derive(Debug)]
+    pub struct ProtocolMapping {
+        // builtins
+        pub __Params: TypeMapping,
+        pub __Results: TypeMapping,
+        pub __NotificationParams: TypeMapping,
+        // structs
+        pub Point: TypeMapping,
+        pub Tag: TypeMapping,
+        pub Text: TypeMapping,
+        pub Reset_Params: TypeMapping,
+        pub Reset_Results: TypeMapping,
+        pub Draw_Params: TypeMapping,
+        pub Draw_Results: TypeMapping,
+        pub Tag_Params: TypeMapping,
+        pub Tag_Results: TypeMapping,
+        // enums
+        pub Color: TypeMapping,
+        // unions
+        pub Shape: TypeMapping,
+    }
+
+    impl Default for ProtocolMapping {
+        fn default() -> Self {
+            Self {
+                // builtins
+                __Params: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                __Results: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                __NotificationParams: TypeMapping::Mapped(OffsetList(vec![])),
+                // structs
+                Point: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                Tag: TypeMapping::Mapped(OffsetList(vec![0])),
+                Text: TypeMapping::Mapped(OffsetList(vec![0])),
+                Reset_Params: TypeMapping::Mapped(OffsetList(vec![])),
+                Reset_Results: TypeMapping::Mapped(OffsetList(vec![])),
+                Draw_Params: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                Draw_Results: TypeMapping::Mapped(OffsetList(vec![])),
+                Tag_Params: TypeMapping::Mapped(OffsetList(vec![0])),
+                Tag_Results: TypeMapping::Mapped(OffsetList(vec![])),
+                // enums
+                Color: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+                // unions
+                Shape: TypeMapping::Mapped(OffsetList(vec![0, 1, 2])),
+            }
+        }
+    }
+    impl ::lavish::facts::Mapping for ProtocolMapping {}
+    /// Names of the functions, fields, enum variants and union members
+    /// of a protocol, which peers exchange to build a `ProtocolMapping`.
+    #[derive(Clone, Debug)]
+    pub struct Descriptor {
+        pub requests: Vec<String>,
+        pub notifications: Vec<String>,
+        pub structs: HashMap<String, Vec<String>>,
+        pub enums: HashMap<String, Vec<String>>,
+        pub unions: HashMap<String, Vec<String>>,
+    }
+
+    impl<M> ::lavish::facts::Factual<M> for Descriptor
+    where
+        M: ::lavish::facts::Mapping,
+    {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            rd.expect_array_len(5)?;
+            Ok(Self {
+                requests: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                notifications: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                structs: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                enums: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+                unions: <Self as ::lavish::facts::Factual<M>>::subread(rd)?,
+            })
+        }
+
+        fn write<W>(&self, mapping: &M, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            ::lavish::rmp::encode::write_array_len(wr, 5)?;
+            self.requests.write(mapping, wr)?;
+            self.notifications.write(mapping, wr)?;
+            self.structs.write(mapping, wr)?;
+            self.enums.write(mapping, wr)?;
+            self.unions.write(mapping, wr)?;
+            Ok(())
+        }
+    }
+
+    /// Describes this protocol, to be sent to peers at handshake
+    pub fn descriptor() -> Descriptor {
+        let mut d = Descriptor {
+            requests: names(&["reset", "draw", "tag"]),
+            notifications: names(&[]),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            unions: HashMap::new(),
+        };
+        // structs
+        d.structs.insert("Point".into(), names(&["x", "y", "label"]));
+        d.structs.insert("Tag".into(), names(&["name"]));
+        d.structs.insert("Text".into(), names(&["value"]));
+        d.structs.insert("reset.Params".into(), names(&[]));
+        d.structs.insert("reset.Results".into(), names(&[]));
+        d.structs.insert("draw.Params".into(), names(&["point", "color", "shape"]));
+        d.structs.insert("draw.Results".into(), names(&[]));
+        d.structs.insert("tag.Params".into(), names(&["tag"]));
+        d.structs.insert("tag.Results".into(), names(&[]));
+        // enums
+        d.enums.insert("Color".into(), names(&["Red", "Green", "Blue"]));
+        // unions
+        d.unions.insert("Shape".into(), names(&["Point", "Color", "Text"]));
+        d
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&name| name.into()).collect()
+    }
+
+    impl ProtocolMapping {
+        /// Builds the mapping used to send values to a peer whose schema is
+        /// described by `remote`, matching functions, fields, enum variants
+        /// and union members by name, so they can be added or reordered.
+        pub fn negotiate(local: &Descriptor, remote: &Descriptor) -> Self {
+            Self {
+                // builtins
+                __Params: TypeMapping::Mapped(offsets(&local.requests, &remote.requests)),
+                __Results: TypeMapping::Mapped(offsets(&local.requests, &remote.requests)),
+                __NotificationParams: TypeMapping::Mapped(offsets(&local.notifications, &remote.notifications)),
+                // structs
+                Point: struct_mapping(&local.structs, &remote.structs, "Point"),
+                Tag: struct_mapping(&local.structs, &remote.structs, "Tag"),
+                Text: struct_mapping(&local.structs, &remote.structs, "Text"),
+                Reset_Params: struct_mapping(&local.structs, &remote.structs, "reset.Params"),
+                Reset_Results: struct_mapping(&local.structs, &remote.structs, "reset.Results"),
+                Draw_Params: struct_mapping(&local.structs, &remote.structs, "draw.Params"),
+                Draw_Results: struct_mapping(&local.structs, &remote.structs, "draw.Results"),
+                Tag_Params: struct_mapping(&local.structs, &remote.structs, "tag.Params"),
+                Tag_Results: struct_mapping(&local.structs, &remote.structs, "tag.Results"),
+                // enums
+                Color: index_mapping(&local.enums, &remote.enums, "enum", "Color"),
+                // unions
+                Shape: index_mapping(&local.unions, &remote.unions, "union", "Shape"),
+            }
+        }
+    }
+
+    /// Returns the position of each of the `from` names among the `to` ones,
+    /// or -1 for those missing.
+    fn offsets(from: &[String], to: &[String]) -> OffsetList {
+        OffsetList(
+            from.iter()
+                .map(|name| to.iter().position(|n| n == name).map(|i| i as i32).unwrap_or(-1))
+                .collect(),
+        )
+    }
+
+    /// Fields are sent in the order the peer reads them: for each of the
+    /// peer's fields, the mapping gives which of ours to write, if any.
+    fn struct_mapping(local: &HashMap<String, Vec<String>>, remote: &HashMap<String, Vec<String>>, name: &str) -> TypeMapping {
+        match (local.get(name), remote.get(name)) {
+            (Some(local), Some(remote)) => TypeMapping::Mapped(offsets(remote, local)),
+            _ => TypeMapping::Incompatible(format!("struct {} is not known by the peer", name)),
+        }
+    }
+
+    /// Enum variants and union members are sent by index: for each of ours,
+    /// the mapping gives the index the peer knows it by, if any.
+    fn index_mapping(local: &HashMap<String, Vec<String>>, remote: &HashMap<String, Vec<String>>, kind: &str, name: &str) -> TypeMapping {
+        match (local.get(name), remote.get(name)) {
+            (Some(local), Some(remote)) => TypeMapping::Mapped(offsets(local, remote)),
+            _ => TypeMapping::Incompatible(format!("{} {} is not known by the peer", kind, name)),
+        }
+    }
+}
+
+pub mod schema {
+    #[derive(Clone, Debug)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+        pub label: Option<String>,
+    }
+
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Point {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 3 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Point to be an array of 3 elements, got {}", len)));
+            }
+            Ok(Self {
+                x: <Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?,
+                y: <Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?,
+                label: <Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?,
+            })
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            mapping.Point.write(wr, |wr, i| match i {
+                0 => self.x.write(mapping, wr),
+                1 => self.y.write(mapping, wr),
+                2 => self.label.write(mapping, wr),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Point: {}", i))),
+            })
+        }
+    }
+    #[derive(Clone, Debug)]
+    pub struct Tag {
+        pub name: String,
+    }
+
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Tag {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 1 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Tag to be an array of 1 elements, got {}", len)));
+            }
+            Ok(Self {
+                name: <Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?,
+            })
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            mapping.Tag.write(wr, |wr, i| match i {
+                0 => self.name.write(mapping, wr),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Tag: {}", i))),
+            })
+        }
+    }
+    #[derive(Clone, Debug)]
+    pub struct Text {
+        pub value: String,
+    }
+
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Text {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 1 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Text to be an array of 1 elements, got {}", len)));
+            }
+            Ok(Self {
+                value: <Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?,
+            })
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            mapping.Text.write(wr, |wr, i| match i {
+                0 => self.value.write(mapping, wr),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Text: {}", i))),
+            })
+        }
+    }
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[repr(u32)]
+    pub enum Color {
+        Red = 0,
+        Green = 1,
+        Blue = 2,
+    }
+
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Color {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let value: u32 = rd.read_int()?;
+            use Color as E;
+            Ok(match value {
+                0 => E::Red,
+                1 => E::Green,
+                2 => E::Blue,
+                _ => return Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized enum variant for Color: {:#?}", value))),
+            })
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            Self: Sized,
+            W: ::std::io::Write,
+        {
+            let offsets = mapping.Color.validate()?;
+            match offsets.get(*self as usize)
+            {
+                Some(value) => value.write(mapping, wr),
+                None => Err(::lavish::facts::Error::IncompatibleSchema(format!("Enum variant for Color not known by the peer: {:#?}", self))),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    pub enum Shape {
+        Point(Point),
+        Color(Color),
+        Text(Text),
+    }
+
+    impl ::lavish::facts::Factual<super::protocol::ProtocolMapping> for Shape {
+        fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+        where
+            Self: Sized,
+            R: ::std::io::Read,
+        {
+            let len = rd.read_array_len()?;
+            if len != 2 {
+                return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Shape to be an array of 2 elements, got {}", len)));
+            }
+            let typ: u32 = rd.read_int()?;
+            match typ {
+                0 => Ok(Shape::Point(<Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?)),
+                1 => Ok(Shape::Color(<Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?)),
+                2 => Ok(Shape::Text(<Self as ::lavish::facts::Factual<super::protocol::ProtocolMapping>>::subread(rd)?)),
+                _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Received unrecognized union variant for Shape: {}", typ))),
+            }
+        }
+
+        fn write<W>(&self, mapping: &super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+        where
+            W: ::std::io::Write,
+        {
+            let o = &mapping.Shape;
+            match *self {
+                Shape::Point(ref value) => o.write_union(wr, mapping, "Shape", "Point", 0, value),
+                Shape::Color(ref value) => o.write_union(wr, mapping, "Shape", "Color", 1, value),
+                Shape::Text(ref value) => o.write_union(wr, mapping, "Shape", "Text", 2, value),
+            }
+        }
+    }
+    pub use reset::method as reset;
+    pub mod reset {
+        pub fn method() -> super::super::protocol::Slottable<Params, Results> {
+            super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Params {
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Params {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 0 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 0 elements, got {}", len)));
+                }
+                Ok(Self {
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Reset_Params.write(wr, |wr, i| match i {
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                })
+            }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Results {
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Results {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 0 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 0 elements, got {}", len)));
+                }
+                Ok(Self {
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Reset_Results.write(wr, |wr, i| match i {
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                })
+            }
+        }
+
+        impl super::super::protocol::Callable<Results> for Params {
+            fn upcast_params(self) -> super::super::protocol::Params {
+                super::super::protocol::Params::Reset(self)
+            }
+            fn downcast_results(results: super::super::protocol::Results) -> Option<Results> {
+                match results {
+                    super::super::protocol::Results::Reset(r) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+
+        impl super::super::protocol::Implementable<Params> for Results {
+            fn method() -> &'static str {
+                "reset"
+            }
+            fn upcast_results(self) -> super::super::protocol::Results {
+                super::super::protocol::Results::Reset(self)
+            }
+            fn downcast_params(params: super::super::protocol::Params) -> Option<Params> {
+                match params {
+                    super::super::protocol::Params::Reset(p) => Some(p),
+                    _ => None,
+                }
+            }
+        }
+    }
+    pub use draw::method as draw;
+    pub mod draw {
+        pub fn method() -> super::super::protocol::Slottable<Params, Results> {
+            super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Params {
+            pub point: super::Point,
+            pub color: super::Color,
+            pub shape: super::Shape,
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Params {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 3 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 3 elements, got {}", len)));
+                }
+                Ok(Self {
+                    point: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    color: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                    shape: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Draw_Params.write(wr, |wr, i| match i {
+                    0 => self.point.write(mapping, wr),
+                    1 => self.color.write(mapping, wr),
+                    2 => self.shape.write(mapping, wr),
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                })
+            }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Results {
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Results {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 0 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 0 elements, got {}", len)));
+                }
+                Ok(Self {
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Draw_Results.write(wr, |wr, i| match i {
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                })
+            }
+        }
+
+        impl super::super::protocol::Callable<Results> for Params {
+            fn upcast_params(self) -> super::super::protocol::Params {
+                super::super::protocol::Params::Draw(self)
+            }
+            fn downcast_results(results: super::super::protocol::Results) -> Option<Results> {
+                match results {
+                    super::super::protocol::Results::Draw(r) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+
+        impl super::super::protocol::Implementable<Params> for Results {
+            fn method() -> &'static str {
+                "draw"
+            }
+            fn upcast_results(self) -> super::super::protocol::Results {
+                super::super::protocol::Results::Draw(self)
+            }
+            fn downcast_params(params: super::super::protocol::Params) -> Option<Params> {
+                match params {
+                    super::super::protocol::Params::Draw(p) => Some(p),
+                    _ => None,
+                }
+            }
+        }
+    }
+    pub use tag::method as tag;
+    pub mod tag {
+        pub fn method() -> super::super::protocol::Slottable<Params, Results> {
+            super::super::protocol::Slottable { phantom: std::marker::PhantomData }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Params {
+            pub tag: super::Tag,
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Params {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 1 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Params to be an array of 1 elements, got {}", len)));
+                }
+                Ok(Self {
+                    tag: <Self as ::lavish::facts::Factual<super::super::protocol::ProtocolMapping>>::subread(rd)?,
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Tag_Params.write(wr, |wr, i| match i {
+                    0 => self.tag.write(mapping, wr),
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Params: {}", i))),
+                })
+            }
+        }
+        #[derive(Clone, Debug)]
+        pub struct Results {
+        }
+
+        impl ::lavish::facts::Factual<super::super::protocol::ProtocolMapping> for Results {
+            fn read<R>(rd: &mut ::lavish::facts::Reader<R>) -> Result<Self, ::lavish::facts::Error>
+            where
+                Self: Sized,
+                R: ::std::io::Read,
+            {
+                let len = rd.read_array_len()?;
+                if len != 0 {
+                    return Err(::lavish::facts::Error::IncompatibleSchema(format!("Expected Results to be an array of 0 elements, got {}", len)));
+                }
+                Ok(Self {
+                })
+            }
+
+            fn write<W>(&self, mapping: &super::super::protocol::ProtocolMapping, wr: &mut W) -> Result<(), ::lavish::facts::Error>
+            where
+                W: ::std::io::Write,
+            {
+                mapping.Tag_Results.write(wr, |wr, i| match i {
+                    _ => Err(::lavish::facts::Error::IncompatibleSchema(format!("Field index out of range for Results: {}", i))),
+                })
+            }
+        }
+
+        impl super::super::protocol::Callable<Results> for Params {
+            fn upcast_params(self) -> super::super::protocol::Params {
+                super::super::protocol::Params::Tag(self)
+            }
+            fn downcast_results(results: super::super::protocol::Results) -> Option<Results> {
+                match results {
+                    super::super::protocol::Results::Tag(r) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+
+        impl super::super::protocol::Implementable<Params> for Results {
+            fn method() -> &'static str {
+                "tag"
+            }
+            fn upcast_results(self) -> super::super::protocol::Results {
+                super::super::protocol::Results::Tag(self)
+            }
+            fn downcast_params(params: super::super::protocol::Params) -> Option<Params> {
+                match params {
+                    super::super::protocol::Params::Tag(p) => Some(p),
+                    _ => None,
+                }
+            }
+        }
+    }
+    pub mod client {
+        #[derive(Clone)]
+        pub struct Client {
+            caller: super::super::protocol::Caller,
+        }
+
+        impl Client {
+            pub fn new(caller: super::super::protocol::Caller) -> Self {
+                Self { caller }
+            }
+            pub fn call<P, R>(&self, p: P) -> Result<R, ::lavish::Error>
+            where
+                P: super::super::protocol::Callable<R>,
+            {
+                self.caller.call(
+                    p.upcast_params(),
+                    P::downcast_results,
+                )
+            }
+        }
+        pub struct Call<T, P> {
+            pub state: ::std::sync::Arc<T>,
+            pub client: super::client::Client,
+            pub params: P,
+        }
+
+        impl<T, P> Call<T, P> {
+            fn downcast<PP, F>(self, f: F) -> Result<Call<T, PP>, ::lavish::Error>
+            where
+                F: Fn(P) -> Option<PP>,
+            {
+                Ok(Call {
+                    state: self.state,
+                    client: self.client,
+                    params: f(self.params).ok_or_else(|| ::lavish::Error::WrongParams)?,
+                })
+            }
+            pub fn shutdown_runtime(&self) {
+                self.client.caller.shutdown_runtime();
+            }
+        }
+        pub type SlotReturn = Result<super::super::protocol::Results, ::lavish::Error>;
+        pub type SlotFn<T> = Fn(Call<T, super::super::protocol::Params>) -> SlotReturn + 'static + Send + Sync;
+        pub type NotificationSlotFn<T> = Fn(Call<T, super::super::protocol::NotificationParams>) -> Result<(), ::lavish::Error> + 'static + Send + Sync;
+        pub struct Router<T>
+        where
+            T: Send + Sync + 'static
+        {
+            state: std::sync::Arc<T>,
+            slots: ::std::collections::HashMap<&'static str, Box<SlotFn<T>>>,
+            notification_slots: ::std::collections::HashMap<&'static str, Box<NotificationSlotFn<T>>>,
+        }
+
+        impl<T> Router<T>
+        where
+            T: Send + Sync + 'static,
+        {
+            pub fn new(state: ::std::sync::Arc<T>) -> Self {
+                Self {
+                    state,
+                    slots: ::std::collections::HashMap::new(),
+                    notification_slots: ::std::collections::HashMap::new(),
+                }
+            }
+            pub fn handle<S, P, R, F>(&mut self, s: S, f: F)
+            where
+                S: Fn() -> super::super::protocol::Slottable<P, R>,
+                R: super::super::protocol::Implementable<P>,
+                F: Fn(Call<T, P>) -> Result<R, ::lavish::Error> + 'static + Send + Sync,
+            {
+                self.slots.insert(R::method(), Box::new(move |call| {
+                    let call = call.downcast(R::downcast_params)?;
+                    f(call).map(|r| r.upcast_results())
+                }));
+            }
+            pub fn handle_notification<S, P, F>(&mut self, s: S, f: F)
+            where
+                S: Fn() -> super::super::protocol::NotificationSlottable<P>,
+                P: super::super::protocol::Notifiable,
+                F: Fn(Call<T, P>) -> Result<(), ::lavish::Error> + 'static + Send + Sync,
+            {
+                self.notification_slots.insert(P::method(), Box::new(move |call| {
+                    let call = call.downcast(P::downcast_params)?;
+                    f(call)
+                }));
+            }
+            pub fn dispatch_notification(&self, caller: super::super::protocol::Caller, params: super::super::protocol::NotificationParams) -> Result<(), ::lavish::Error> {
+                use ::lavish::Atom;
+                let slot = self.notification_slots.get(params.method())
+                    .ok_or_else(|| ::lavish::Error::MethodUnimplemented(params.method()))?;
+                let call = Call {
+                    state: self.state.clone(),
+                    client: super::client::Client { caller },
+                    params,
+                };
+                slot(call)
+            }
+        }
+        impl<T> ::lavish::Handler<Client, super::super::protocol::ProtocolMapping, super::super::protocol::Params, super::super::protocol::NotificationParams, super::super::protocol::Results> for Router<T>
+        where
+            T: Send + Sync + 'static,
+        {
+            fn handle(&self, caller: super::super::protocol::Caller, params: super::super::protocol::Params) -> Result<super::super::protocol::Results, ::lavish::Error> {
+                use ::lavish::Atom;
+                let slot = self.slots.get(params.method())
+                    .ok_or_else(|| ::lavish::Error::MethodUnimplemented(params.method()))?;
+                let call = Call {
+                    state: self.state.clone(),
+                    client: super::client::Client { caller },
+                    params,
+                };
+                slot(call)
+            }
+            fn make_client(caller: super::super::protocol::Caller) -> Client {
+                Client { caller }
+            }
+        }
+    }
+
+    pub mod server {
+        #[derive(Clone)]
+        pub struct Client {
+            caller: super::super::protocol::Caller,
+        }
+
+        impl Client {
+            pub fn new(caller: super::super::protocol::Caller) -> Self {
+                Self { caller }
+            }
+            pub fn call<P, R>(&self, p: P) -> Result<R, ::lavish::Error>
+            where
+                P: super::super::protocol::Callable<R>,
+            {
+                self.caller.call(
+                    p.upcast_params(),
+                    P::downcast_results,
+                )
+            }
+        }
+        pub struct Call<T, P> {
+            pub state: ::std::sync::Arc<T>,
+            pub client: super::server::Client,
+            pub params: P,
+        }
+
+        impl<T, P> Call<T, P> {
+            fn downcast<PP, F>(self, f: F) -> Result<Call<T, PP>, ::lavish::Error>
+            where
+                F: Fn(P) -> Option<PP>,
+            {
+                Ok(Call {
+                    state: self.state,
+                    client: self.client,
+                    params: f(self.params).ok_or_else(|| ::lavish::Error::WrongParams)?,
+                })
+            }
+            pub fn shutdown_runtime(&self) {
+                self.client.caller.shutdown_runtime();
+            }
+        }
+        pub type SlotReturn = Result<super::super::protocol::Results, ::lavish::Error>;
+        pub type SlotFn<T> = Fn(Call<T, super::super::protocol::Params>) -> SlotReturn + 'static + Send + Sync;
+        pub type NotificationSlotFn<T> = Fn(Call<T, super::super::protocol::NotificationParams>) -> Result<(), ::lavish::Error> + 'static + Send + Sync;
+        pub struct Router<T>
+        where
+            T: Send + Sync + 'static
+        {
+            state: std::sync::Arc<T>,
+            slots: ::std::collections::HashMap<&'static str, Box<SlotFn<T>>>,
+            notification_slots: ::std::collections::HashMap<&'static str, Box<NotificationSlotFn<T>>>,
+        }
+
+        impl<T> Router<T>
+        where
+            T: Send + Sync + 'static,
+        {
+            pub fn new(state: ::std::sync::Arc<T>) -> Self {
+                Self {
+                    state,
+                    slots: ::std::collections::HashMap::new(),
+                    notification_slots: ::std::collections::HashMap::new(),
+                }
+            }
+            pub fn handle<S, P, R, F>(&mut self, s: S, f: F)
+            where
+                S: Fn() -> super::super::protocol::Slottable<P, R>,
+                R: super::super::protocol::Implementable<P>,
+                F: Fn(Call<T, P>) -> Result<R, ::lavish::Error> + 'static + Send + Sync,
+            {
+                self.slots.insert(R::method(), Box::new(move |call| {
+                    let call = call.downcast(R::downcast_params)?;
+                    f(call).map(|r| r.upcast_results())
+                }));
+            }
+            pub fn handle_notification<S, P, F>(&mut self, s: S, f: F)
+            where
+                S: Fn() -> super::super::protocol::NotificationSlottable<P>,
+                P: super::super::protocol::Notifiable,
+                F: Fn(Call<T, P>) -> Result<(), ::lavish::Error> + 'static + Send + Sync,
+            {
+                self.notification_slots.insert(P::method(), Box::new(move |call| {
+                    let call = call.downcast(P::downcast_params)?;
+                    f(call)
+                }));
+            }
+            pub fn dispatch_notification(&self, caller: super::super::protocol::Caller, params: super::super::protocol::NotificationParams) -> Result<(), ::lavish::Error> {
+                use ::lavish::Atom;
+                let slot = self.notification_slots.get(params.method())
+                    .ok_or_else(|| ::lavish::Error::MethodUnimplemented(params.method()))?;
+                let call = Call {
+                    state: self.state.clone(),
+                    client: super::server::Client { caller },
+                    params,
+                };
+                slot(call)
+            }
+        }
+        impl<T> ::lavish::Handler<Client, super::super::protocol::ProtocolMapping, super::super::protocol::Params, super::super::protocol::NotificationParams, super::super::protocol::Results> for Router<T>
+        where
+            T: Send + Sync + 'static,
+        {
+            fn handle(&self, caller: super::super::protocol::Caller, params: super::super::protocol::Params) -> Result<super::super::protocol::Results, ::lavish::Error> {
+                use ::lavish::Atom;
+                let slot = self.slots.get(params.method())
+                    .ok_or_else(|| ::lavish::Error::MethodUnimplemented(params.method()))?;
+                let call = Call {
+                    state: self.state.clone(),
+                    client: super::server::Client { caller },
+                    params,
+                };
+                slot(call)
+            }
+            fn make_client(caller: super::super::protocol::Caller) -> Client {
+                Client { caller }
+            }
+        }
+    }
+
+}
+